@@ -0,0 +1,59 @@
+use std::error::Error;
+use std::fmt;
+
+/// The ways that starting up the gui can fail.
+#[derive(Debug)]
+pub enum GUIError {
+    /// The operating system couldn't make a window.
+    Window(winit::error::OsError),
+    /// None of the backends had an adapter that could draw to the window.
+    NoAdapter,
+    /// The adapter was found but wouldn't give a device to draw with.
+    RequestDevice(wgpu::RequestDeviceError),
+    /// A frame couldn't be drawn, which the gui carries on from.
+    Surface(wgpu::SurfaceError),
+    /// The software rasterizer's frames can't be copied onto the window without the gpu.
+    Blit(&'static str),
+}
+
+impl fmt::Display for GUIError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GUIError::Window(e) => write!(f, "couldn't make a window: {e}"),
+            GUIError::NoAdapter => write!(f, "none of the backends had a suitable adapter"),
+            GUIError::RequestDevice(e) => write!(f, "couldn't get a device: {e}"),
+            GUIError::Surface(e) => write!(f, "couldn't draw a frame: {e}"),
+            GUIError::Blit(reason) => write!(f, "couldn't copy frames onto the window: {reason}"),
+        }
+    }
+}
+
+impl Error for GUIError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GUIError::Window(e) => Some(e),
+            GUIError::NoAdapter => None,
+            GUIError::RequestDevice(e) => Some(e),
+            GUIError::Surface(e) => Some(e),
+            GUIError::Blit(_) => None,
+        }
+    }
+}
+
+impl From<winit::error::OsError> for GUIError {
+    fn from(e: winit::error::OsError) -> Self {
+        GUIError::Window(e)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for GUIError {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        GUIError::RequestDevice(e)
+    }
+}
+
+impl From<wgpu::SurfaceError> for GUIError {
+    fn from(e: wgpu::SurfaceError) -> Self {
+        GUIError::Surface(e)
+    }
+}
@@ -0,0 +1,183 @@
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+use winit::window::Window;
+
+use crate::guierror::GUIError;
+
+use super::software::Framebuffer;
+
+/// Copies the software rasterizer's frames straight onto a window, without wgpu,
+/// so that windows can be drawn on machines that don't have an adapter at all.
+/// Only X11 windows can be copied onto so far. Everywhere else the frames are shown
+/// through wgpu, which on Windows and macOS always has an adapter.
+pub struct WindowBlitter {
+    #[cfg(all(
+        unix,
+        not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+    ))]
+    xlib: xlib::XlibBlitter,
+    /// The frame's pixels as 0x00RRGGBB, kept so that they're only allocated
+    /// when the window changes size.
+    pixels: Vec<u32>,
+}
+
+impl WindowBlitter {
+    /// Sets up copying onto window, or returns GUIError::Blit if its platform isn't supported.
+    pub fn new(window: &Window) -> Result<Self, GUIError> {
+        match window.raw_window_handle() {
+            #[cfg(all(
+                unix,
+                not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+            ))]
+            RawWindowHandle::Xlib(handle) => Ok(Self {
+                xlib: xlib::XlibBlitter::new(handle)?,
+                pixels: Vec::new(),
+            }),
+            _ => Err(GUIError::Blit(
+                "only X11 windows can be drawn without the gpu",
+            )),
+        }
+    }
+
+    /// Copies framebuffer onto the top left corner of the window.
+    pub fn present(&mut self, framebuffer: &Framebuffer) {
+        self.pixels.clear();
+        self.pixels
+            .extend(framebuffer.to_rgba8().chunks_exact(4).map(to_xrgb));
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+        ))]
+        self.xlib
+            .put_image(&mut self.pixels, framebuffer.width(), framebuffer.height());
+    }
+}
+
+/// Packs the red, green and blue of an RGBA pixel into the low three bytes of a u32.
+/// Windows are opaque, so the alpha is dropped.
+fn to_xrgb(pixel: &[u8]) -> u32 {
+    (pixel[0] as u32) << 16 | (pixel[1] as u32) << 8 | pixel[2] as u32
+}
+
+#[cfg(all(
+    unix,
+    not(any(target_os = "macos", target_os = "ios", target_os = "android"))
+))]
+mod xlib {
+    use std::mem;
+    use std::os::raw::{c_char, c_int, c_uint, c_ulong};
+    use std::ptr;
+
+    use raw_window_handle::XlibHandle;
+    use x11_dl::xlib::{self, Display, Visual, Xlib, GC};
+
+    use crate::guierror::GUIError;
+
+    /// Puts images onto an X11 window with the same connection to the display as winit.
+    pub struct XlibBlitter {
+        xlib: Box<Xlib>,
+        display: *mut Display,
+        window: c_ulong,
+        gc: GC,
+        visual: *mut Visual,
+        depth: c_int,
+    }
+
+    impl XlibBlitter {
+        pub fn new(handle: XlibHandle) -> Result<Self, GUIError> {
+            let xlib =
+                Box::new(Xlib::open().map_err(|_| GUIError::Blit("libX11 couldn't be loaded"))?);
+            let display = handle.display as *mut Display;
+            let mut attributes: xlib::XWindowAttributes = unsafe { mem::zeroed() };
+            if unsafe { (xlib.XGetWindowAttributes)(display, handle.window, &mut attributes) } == 0
+            {
+                return Err(GUIError::Blit("the window's attributes couldn't be read"));
+            }
+            // The pixels are written as 0x00RRGGBB, which needs a true color visual.
+            let visual = attributes.visual;
+            let masks = unsafe {
+                (
+                    (*visual).red_mask,
+                    (*visual).green_mask,
+                    (*visual).blue_mask,
+                )
+            };
+            if attributes.depth < 24 || masks != (0xff0000, 0xff00, 0xff) {
+                return Err(GUIError::Blit("the window doesn't have 24 bit color"));
+            }
+            let gc = unsafe { (xlib.XCreateGC)(display, handle.window, 0, ptr::null_mut()) };
+
+            Ok(Self {
+                xlib,
+                display,
+                window: handle.window,
+                gc,
+                visual,
+                depth: attributes.depth,
+            })
+        }
+
+        /// Puts width by height pixels onto the top left corner of the window.
+        pub fn put_image(&self, pixels: &mut [u32], width: u32, height: u32) {
+            unsafe {
+                let image = (self.xlib.XCreateImage)(
+                    self.display,
+                    self.visual,
+                    self.depth as c_uint,
+                    xlib::ZPixmap,
+                    0,
+                    pixels.as_mut_ptr() as *mut c_char,
+                    width,
+                    height,
+                    32,
+                    0,
+                );
+                if image.is_null() {
+                    return;
+                }
+                // The pixels are u32s in this machine's byte order,
+                // which Xlib swaps if the server's is different.
+                (*image).byte_order = if cfg!(target_endian = "little") {
+                    xlib::LSBFirst
+                } else {
+                    xlib::MSBFirst
+                };
+                if (*image).bits_per_pixel == 32 {
+                    (self.xlib.XPutImage)(
+                        self.display,
+                        self.window,
+                        self.gc,
+                        image,
+                        0,
+                        0,
+                        0,
+                        0,
+                        width,
+                        height,
+                    );
+                }
+                // The pixels belong to the caller, so they mustn't be freed with the image.
+                (*image).data = ptr::null_mut();
+                (self.xlib.XDestroyImage)(image);
+                (self.xlib.XFlush)(self.display);
+            }
+        }
+    }
+
+    impl Drop for XlibBlitter {
+        fn drop(&mut self) {
+            unsafe {
+                (self.xlib.XFreeGC)(self.display, self.gc);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packs_pixels_without_alpha() {
+        assert_eq!(to_xrgb(&[0x12, 0x34, 0x56, 0x78]), 0x123456);
+    }
+}
@@ -0,0 +1,87 @@
+use std::mem;
+use std::ops::Range;
+
+use wgpu::{BufferAddress, BufferUsages, Device, Queue};
+
+/// The smallest buffer that's created, so that small trees don't reallocate as they grow.
+const MIN_CAPACITY: BufferAddress = 4096;
+
+/// A gpu buffer that's written to in place with queue.write_buffer,
+/// and is only replaced when the data outgrows it.
+pub struct GrowableBuffer {
+    buffer: wgpu::Buffer,
+    capacity: BufferAddress,
+    label: &'static str,
+    usage: BufferUsages,
+}
+
+impl GrowableBuffer {
+    pub fn new(device: &Device, label: &'static str, usage: BufferUsages) -> Self {
+        let usage = usage | BufferUsages::COPY_DST;
+        Self {
+            buffer: make_buffer(device, label, usage, MIN_CAPACITY),
+            capacity: MIN_CAPACITY,
+            label,
+            usage,
+        }
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Copies data to the start of the buffer, first doubling the buffer
+    /// until it's large enough if it's too small.
+    pub fn write(&mut self, device: &Device, queue: &Queue, data: &[u8]) {
+        let size = data.len() as BufferAddress;
+        if size > self.capacity {
+            let capacity = size.next_power_of_two().max(MIN_CAPACITY);
+            self.buffer = make_buffer(device, self.label, self.usage, capacity);
+            self.capacity = capacity;
+        }
+        if size > 0 {
+            queue.write_buffer(&self.buffer, 0, data);
+        }
+    }
+
+    /// Converts the given ranges of items and copies them to the same places in the buffer,
+    /// leaving the rest of it as it was. If the buffer is too small to hold every item,
+    /// they're all converted and written, as with write.
+    pub fn write_ranges<T, V: bytemuck::Pod>(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        items: &[T],
+        ranges: &[Range<usize>],
+        convert: impl Fn(&T) -> V,
+    ) {
+        let item_size = mem::size_of::<V>() as BufferAddress;
+        if items.len() as BufferAddress * item_size > self.capacity {
+            let data: Vec<V> = items.iter().map(convert).collect();
+            self.write(device, queue, bytemuck::cast_slice(&data[..]));
+            return;
+        }
+        for range in ranges.iter().filter(|range| !range.is_empty()) {
+            let data: Vec<V> = items[range.clone()].iter().map(&convert).collect();
+            queue.write_buffer(
+                &self.buffer,
+                range.start as BufferAddress * item_size,
+                bytemuck::cast_slice(&data[..]),
+            );
+        }
+    }
+}
+
+fn make_buffer(
+    device: &Device,
+    label: &'static str,
+    usage: BufferUsages,
+    size: BufferAddress,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size,
+        usage,
+        mapped_at_creation: false,
+    })
+}
@@ -0,0 +1,344 @@
+use std::iter;
+use std::num::NonZeroU32;
+
+use crate::guiproperties::guiposition::GUISize;
+use crate::guiresources::{GUIRasterizer, GUIResources};
+use crate::guiwidgets::GUIBase;
+
+use super::renderer::{Renderer, WindowPrimitives};
+use super::software::Framebuffer;
+use super::tessellation::WindowGeometry;
+
+/// The bytes per row of a texture copied into a buffer have to be a multiple of this.
+const ROW_ALIGNMENT: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+/// An image that a window has been drawn into.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GUIImage {
+    /// The width in physical pixels.
+    pub width: u32,
+    /// The height in physical pixels.
+    pub height: u32,
+    /// Red, green, blue and alpha bytes in the sRGB color space, not premultiplied,
+    /// row by row from the top left corner.
+    pub pixels: Vec<u8>,
+    /// How the image was drawn, which can be Software even if the gpu was asked for,
+    /// so that tests comparing it against a known good image can check which it was.
+    pub rasterizer: GUIRasterizer,
+}
+
+impl GUIImage {
+    /// Returns the red, green, blue and alpha bytes of a single pixel.
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let start = ((y * self.width + x) * 4) as usize;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.pixels[start..start + 4]);
+        pixel
+    }
+
+    /// Encodes the image as a png file.
+    #[cfg(feature = "png")]
+    pub fn to_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.pixels)?;
+        }
+        Ok(bytes)
+    }
+
+    /// Writes the image to a png file.
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), png::EncodingError> {
+        std::fs::write(path, self.to_png()?)?;
+        Ok(())
+    }
+}
+
+/// Draws windows into images instead of onto the screen,
+/// so that they can be compared against known good images in tests.
+pub struct HeadlessRenderer {
+    /// The software rasterizer's renderer doesn't have a gpu.
+    renderer: Renderer,
+}
+
+impl HeadlessRenderer {
+    /// Sets up a renderer that draws with guiresources' rasterizer.
+    /// The gpu rasterizer uses wgpu's fallback adapter, which draws in software
+    /// so that it works on machines without a gpu, or any adapter if there isn't one.
+    /// If there aren't any adapters at all it draws with the software rasterizer instead,
+    /// which rasterizer() and the rasterizer of each image that's drawn tell.
+    pub fn new(guiresources: &GUIResources) -> Self {
+        let renderer = match guiresources.rasterizer() {
+            GUIRasterizer::Gpu => pollster::block_on(async {
+                match Renderer::new(guiresources, None, &guiresources.backends(), true).await {
+                    Ok((renderer, _)) => Some(renderer),
+                    Err(_) => Renderer::new(guiresources, None, &[wgpu::Backends::all()], false)
+                        .await
+                        .ok()
+                        .map(|(renderer, _)| renderer),
+                }
+            }),
+            GUIRasterizer::Software => None,
+        };
+        Self {
+            renderer: renderer.unwrap_or_else(Renderer::without_gpu),
+        }
+    }
+
+    /// Returns how the windows are being drawn,
+    /// which is Software if the gpu was asked for but there wasn't an adapter.
+    pub fn rasterizer(&self) -> GUIRasterizer {
+        self.renderer.rasterizer
+    }
+
+    /// Lays out and draws the base window at size, in logical pixels, and scale,
+    /// which converts logical pixels into the image's physical pixels.
+    pub fn render(&mut self, guibase: &mut GUIBase, size: GUISize, scale: f64) -> GUIImage {
+        self.render_window(guibase, guibase.base_window, size, scale)
+    }
+
+    /// Lays out and draws a window at size, in logical pixels, and scale,
+    /// which converts logical pixels into the image's physical pixels.
+    pub fn render_window(
+        &mut self,
+        guibase: &mut GUIBase,
+        window_id: u128,
+        size: GUISize,
+        scale: f64,
+    ) -> GUIImage {
+        guibase.logical_scale = Some(scale);
+        guibase.get_window_mut(window_id).size = size;
+        guibase.layout();
+
+        let width = (size.width.get_physical_length(&scale).round() as u32).max(1);
+        let height = (size.height.get_physical_length(&scale).round() as u32).max(1);
+        let renderer = &mut self.renderer;
+        match renderer.rasterizer {
+            GUIRasterizer::Gpu => render_on_gpu(renderer, guibase, window_id, size, width, height),
+            GUIRasterizer::Software => {
+                let Renderer {
+                    glyph_atlas,
+                    tessellation,
+                    ..
+                } = renderer;
+                tessellation.update(guibase);
+                let mut framebuffer = Framebuffer::new(
+                    width,
+                    height,
+                    &size,
+                    &guibase.get_window(window_id).background_color,
+                );
+                let (vertices, indices, polygons) =
+                    tessellation.vertices_and_indices(guibase, window_id);
+                framebuffer.fill(&vertices, &indices, &polygons);
+                let atlas_generation = glyph_atlas.generation();
+                let (mut text_vertices, _) =
+                    tessellation.text_vertices_and_indices(guibase, window_id, glyph_atlas);
+                if atlas_generation != glyph_atlas.generation() {
+                    (text_vertices, _) =
+                        tessellation.text_vertices_and_indices(guibase, window_id, glyph_atlas);
+                }
+                framebuffer.draw_text(&text_vertices, glyph_atlas);
+                GUIImage {
+                    width,
+                    height,
+                    pixels: framebuffer.to_rgba8(),
+                    rasterizer: GUIRasterizer::Software,
+                }
+            }
+        }
+    }
+}
+
+/// Draws a window into a texture and copies it back from the gpu.
+fn render_on_gpu(
+    renderer: &mut Renderer,
+    guibase: &mut GUIBase,
+    window_id: u128,
+    size: GUISize,
+    width: u32,
+    height: u32,
+) -> GUIImage {
+    renderer.tessellation.update(guibase);
+
+    let mut geometry = WindowGeometry::default();
+    let atlas_generation = renderer.glyph_atlas.generation();
+    renderer
+        .tessellation
+        .assemble(guibase, window_id, &mut renderer.glyph_atlas, &mut geometry);
+    // Glyphs that were written before the atlas grew or was cleared have moved.
+    if atlas_generation != renderer.glyph_atlas.generation() {
+        renderer.tessellation.assemble(
+            guibase,
+            window_id,
+            &mut renderer.glyph_atlas,
+            &mut geometry,
+        );
+    }
+    renderer.upload_glyph_atlas();
+    let gpu = renderer
+        .gpu
+        .as_ref()
+        .expect("the gpu rasterizer always has a gpu");
+    let mut primitives = WindowPrimitives::new(&gpu.device);
+    primitives.write(gpu, &renderer.glyph_atlas, &geometry, None, &size);
+
+    let texture_size = wgpu::Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let texture = gpu.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Headless Texture"),
+        size: texture_size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: gpu.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let padded_bytes_per_row = (width * 4).div_ceil(ROW_ALIGNMENT) * ROW_ALIGNMENT;
+    let output_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Headless Output Buffer"),
+        size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = gpu
+        .device
+        .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Headless Encoder"),
+        });
+    gpu.draw(
+        &mut encoder,
+        &view,
+        (width, height),
+        &guibase.get_window(window_id).background_color,
+        &primitives,
+    );
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &output_buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: NonZeroU32::new(height),
+            },
+        },
+        texture_size,
+    );
+    gpu.queue.submit(iter::once(encoder.finish()));
+
+    let slice = output_buffer.slice(..);
+    let mapping = slice.map_async(wgpu::MapMode::Read);
+    gpu.device.poll(wgpu::Maintain::Wait);
+    pollster::block_on(mapping).unwrap();
+    let padded = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for row in padded.chunks(padded_bytes_per_row as usize) {
+        pixels.extend_from_slice(&row[..(width * 4) as usize]);
+    }
+    drop(padded);
+    output_buffer.unmap();
+
+    to_straight_rgba(&mut pixels, gpu.format);
+    GUIImage {
+        width,
+        height,
+        pixels,
+        rasterizer: GUIRasterizer::Gpu,
+    }
+}
+
+/// Converts premultiplied pixels in format into straight red, green, blue and alpha.
+fn to_straight_rgba(pixels: &mut [u8], format: wgpu::TextureFormat) {
+    let is_bgra = matches!(
+        format,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+    );
+    for pixel in pixels.chunks_mut(4) {
+        if is_bgra {
+            pixel.swap(0, 2);
+        }
+        let alpha = pixel[3] as u32;
+        if alpha > 0 && alpha < 255 {
+            for channel in pixel[..3].iter_mut() {
+                *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guiposition::GUIPosition;
+    use crate::guiproperties::GUIColor;
+    use crate::guiwidgets::{GUIButton, GUIWindow};
+
+    #[test]
+    fn converts_premultiplied_bgra() {
+        let mut pixels = vec![10, 20, 30, 255, 0, 64, 128, 128, 0, 0, 0, 0];
+        to_straight_rgba(&mut pixels, wgpu::TextureFormat::Bgra8UnormSrgb);
+        assert_eq!(pixels, vec![30, 20, 10, 255, 255, 128, 0, 128, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn renders_a_button() {
+        for rasterizer in [GUIRasterizer::Gpu, GUIRasterizer::Software] {
+            let mut headless =
+                HeadlessRenderer::new(&GUIResources::default().with_rasterizer(rasterizer));
+            let image = render_a_button(&mut headless);
+            assert_eq!(image.rasterizer, headless.rasterizer());
+            if rasterizer == GUIRasterizer::Software {
+                assert_eq!(image.rasterizer, GUIRasterizer::Software);
+            }
+        }
+    }
+
+    fn render_a_button(headless: &mut HeadlessRenderer) -> GUIImage {
+        let mut guibase = GUIBase::new();
+        let mut window = GUIWindow::default();
+        window.background_color = GUIColor {
+            r: 1.,
+            g: 1.,
+            b: 1.,
+            a: 1.,
+        };
+        let window_id = guibase.add_window(window);
+        guibase.add_child_to_parent(
+            GUIButton {
+                position: GUIPosition::from_pixels(10., 10.),
+                text: "",
+                background_color: GUIColor {
+                    r: 1.,
+                    g: 0.,
+                    b: 0.,
+                    a: 1.,
+                },
+                ..Default::default()
+            },
+            window_id,
+        );
+
+        let image = headless.render(&mut guibase, GUISize::from_pixels(200., 100.), 2.);
+        assert_eq!((image.width, image.height), (400, 200));
+        assert_eq!(image.get_pixel(2, 2), [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(100, 60), [255, 0, 0, 255]);
+        image
+    }
+}
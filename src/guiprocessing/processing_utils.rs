@@ -1,10 +1,14 @@
+use std::ops::Range;
+
 use winit::dpi::PhysicalSize;
 use winit::window::Window;
 
-use super::vertices::{LogicalVertex, Polygon};
+use super::text::{self, GlyphAtlas};
+use super::vertices::{ClipRect, LogicalTextVertex, LogicalVertex, Polygon};
 use crate::{
     guiproperties::{
         guiposition::{GUIPosition, GUISize},
+        guitext::GUIText,
         guitraits::Widget,
     },
     guiwidgets::{GUIBase, GUIWindow},
@@ -46,44 +50,123 @@ pub fn set_window_properties(window: Window, guibase: &GUIBase, guiwindow: &GUIW
     window
 }
 
+pub(crate) const INDEX_OVERFLOW: &str = "the widget tree has more vertices than a u32 index can address";
+
+/// Converts a vertex count into the index of the next vertex.
+/// Panics instead of silently wrapping around if it doesn't fit in a u32.
+pub fn to_index(vertex_count: usize) -> u32 {
+    u32::try_from(vertex_count).expect(INDEX_OVERFLOW)
+}
+
+/// Tessellates the widgets of a single window.
 pub fn make_vertices_and_indices(
     guibase: &GUIBase,
-) -> (Vec<LogicalVertex>, Vec<u16>, Vec<Polygon>) {
+    window_id: u128,
+) -> (Vec<LogicalVertex>, Vec<u32>, Vec<Polygon>) {
     let mut all_vertices: Vec<LogicalVertex> = Vec::new();
-    let mut all_indices: Vec<u16> = Vec::new();
+    let mut all_indices: Vec<u32> = Vec::new();
     // let mut all_triangles: Triangles = Triangles::new();
     let mut all_polygons: Vec<Polygon> = Vec::new();
     let mut index_offset = 0;
 
-    for (_, gwindow) in guibase.windows.iter() {
-        let child_ids = gwindow.get_child_ids();
-        // let size = gwindow.get_window().get_size();
-        for child_id in child_ids {
-            let (vertices, indices, polygons) = make_child(
-                &guibase,
-                gwindow.get_window().get_position(),
-                child_id,
-                index_offset,
-            );
-            all_vertices.extend(vertices);
-            all_indices.extend(indices);
-            all_polygons.extend(polygons);
-            // all_triangles.extend(triangles);
-            index_offset = all_vertices.len() as u16;
-        }
+    let gwindow = guibase.windows.get(&window_id).unwrap();
+    let child_ids = gwindow.get_child_ids();
+    // let size = gwindow.get_window().get_size();
+    for child_id in child_ids {
+        let (vertices, indices, polygons) = make_child(
+            guibase,
+            gwindow.get_window().get_position(),
+            child_id,
+            index_offset,
+            None,
+        );
+        all_vertices.extend(vertices);
+        all_indices.extend(indices);
+        all_polygons.extend(polygons);
+        // all_triangles.extend(triangles);
+        index_offset = to_index(all_vertices.len());
     }
 
+    push_focus_ring(
+        guibase,
+        window_id,
+        &mut all_vertices,
+        &mut all_indices,
+        &mut all_polygons,
+    );
+
     (all_vertices, all_indices, all_polygons)
 }
 
+/// Adds the ring around the widget with keyboard focus to a window's shapes,
+/// if the widget is in the window. The ring is drawn on top of everything
+/// else and isn't hit tested.
+pub(crate) fn push_focus_ring(
+    guibase: &GUIBase,
+    window_id: u128,
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    polygons: &mut Vec<Polygon>,
+) {
+    let widget_id = match guibase.get_focused_widget() {
+        Some(id) if guibase.get_window_id_of(id) == Some(window_id) => id,
+        _ => return,
+    };
+    let position = guibase.get_absolute_position(widget_id);
+    let size = guibase.get_widget(widget_id).get_size();
+    let width = guibase.focus_ring_width.get_length() as f32;
+    // The ring sits outside the widget, a ring's width away from it.
+    let left = position.x.get_length() as f32 - 2. * width;
+    let top = position.y.get_length() as f32 - 2. * width;
+    let right = left + size.width.get_length() as f32 + 4. * width;
+    let bottom = top + size.height.get_length() as f32 + 4. * width;
+    let color = guibase.focus_ring_color.to_f32_array();
+    // The ring is cut off with the widget when it's partly scrolled out of view.
+    let clip = polygons
+        .iter()
+        .find(|polygon| polygon.widget_id == widget_id)
+        .and_then(|polygon| polygon.clip);
+
+    let start_index = vertices.len();
+    for [x0, y0, x1, y1] in [
+        [left, top, right, top + width],
+        [left, bottom - width, right, bottom],
+        [left, top + width, left + width, bottom - width],
+        [right - width, top + width, right, bottom - width],
+    ] {
+        let offset = to_index(vertices.len());
+        // Counter clockwise, the same as the widgets.
+        for [x, y] in [[x0, y0], [x0, y1], [x1, y1], [x1, y0]] {
+            vertices.push(LogicalVertex {
+                position: [x, y, 0.],
+                color,
+            });
+        }
+        indices.extend(
+            [0, 1, 2, 0, 2, 3]
+                .iter()
+                .map(|index: &u32| index.checked_add(offset).expect(INDEX_OVERFLOW)),
+        );
+    }
+    polygons.push(Polygon {
+        start_index,
+        end_index: vertices.len(),
+        widget_id,
+        convex: false,
+        rendered: false,
+        clip,
+    });
+}
+
 fn make_child(
     guibase: &GUIBase,
     parent_position: &GUIPosition,
     widget_id: &u128,
-    index_offset: u16,
-) -> (Vec<LogicalVertex>, Vec<u16>, Vec<Polygon>) {
+    index_offset: u32,
+    clip: Option<ClipRect>,
+) -> (Vec<LogicalVertex>, Vec<u32>, Vec<Polygon>) {
     let mut all_vertices: Vec<LogicalVertex> = Vec::new();
-    let mut all_indices: Vec<u16> = Vec::new();
+    let mut all_indices: Vec<u32> = Vec::new();
     // let mut all_triangles: Triangles = Triangles::new();
     let mut all_polygons: Vec<Polygon> = Vec::new();
     let gwidget = guibase.widgets.get(widget_id).unwrap();
@@ -94,15 +177,18 @@ fn make_child(
     // all_indices.extend(indices.iter());
     all_indices.extend(indices);
     // all_triangles.extend(triangles);
-    all_polygons.push(polygon);
+    all_polygons.push(Polygon { clip, ..polygon });
 
+    let widget = gwidget.get_widget().as_ref();
+    let position = absolute_position(parent_position, widget.get_position());
+    let child_clip = children_clip(&position, widget, clip);
+    let position = children_position(&position, widget);
     for child_id in gwidget.get_child_ids() {
-        let (vertices, indices, polygons) = make_child(
-            &guibase,
-            gwidget.get_widget().get_position(),
-            child_id,
-            all_vertices.len() as u16,
-        );
+        let child_offset = index_offset
+            .checked_add(to_index(all_vertices.len()))
+            .expect(INDEX_OVERFLOW);
+        let (vertices, indices, polygons) =
+            make_child(guibase, &position, child_id, child_offset, child_clip);
         all_vertices.extend(vertices);
         all_indices.extend(indices);
         // all_triangles.extend(triangles);
@@ -112,6 +198,124 @@ fn make_child(
     (all_vertices, all_indices, all_polygons)
 }
 
+/// Lays out the text of a single window's widgets.
+pub fn make_text_vertices_and_indices(
+    guibase: &GUIBase,
+    window_id: u128,
+    atlas: &mut GlyphAtlas,
+) -> (Vec<LogicalTextVertex>, Vec<u32>) {
+    let mut all_vertices: Vec<LogicalTextVertex> = Vec::new();
+    let mut all_indices: Vec<u32> = Vec::new();
+    let mut all_text: Vec<(GUIText, Option<ClipRect>)> = Vec::new();
+    let scale = guibase.logical_scale.unwrap_or(1.);
+
+    let gwindow = guibase.windows.get(&window_id).unwrap();
+    for child_id in gwindow.get_child_ids() {
+        collect_child_text(
+            guibase,
+            gwindow.get_window().get_position(),
+            child_id,
+            None,
+            &mut all_text,
+        );
+    }
+
+    for (text, clip) in all_text.iter() {
+        let quads = text::layout_text(&guibase.font, text, scale);
+        let (vertices, indices) = text::make_glyph_vertices_and_indices(
+            &quads,
+            &guibase.font,
+            atlas,
+            to_index(all_vertices.len()),
+            clip.as_ref(),
+        );
+        all_vertices.extend(vertices);
+        all_indices.extend(indices);
+    }
+
+    (all_vertices, all_indices)
+}
+
+fn collect_child_text(
+    guibase: &GUIBase,
+    parent_position: &GUIPosition,
+    widget_id: &u128,
+    clip: Option<ClipRect>,
+    all_text: &mut Vec<(GUIText, Option<ClipRect>)>,
+) {
+    let gwidget = guibase.widgets.get(widget_id).unwrap();
+    let widget = gwidget.get_widget().as_ref();
+    all_text.extend(
+        widget
+            .get_text(parent_position)
+            .into_iter()
+            .map(|text| (text, clip)),
+    );
+
+    let position = absolute_position(parent_position, widget.get_position());
+    let child_clip = children_clip(&position, widget, clip);
+    let position = children_position(&position, widget);
+    for child_id in gwidget.get_child_ids() {
+        collect_child_text(guibase, &position, child_id, child_clip, all_text);
+    }
+}
+
+/// Returns the position of a widget within its window,
+/// given its parent's position within the window and its position within its parent.
+pub(crate) fn absolute_position(parent_position: &GUIPosition, position: &GUIPosition) -> GUIPosition {
+    GUIPosition::from_lengths(
+        parent_position.x.add(&position.x),
+        parent_position.y.add(&position.y),
+    )
+}
+
+/// Returns where a widget's children are within the window, given where the widget is,
+/// which is moved back by how far the widget has scrolled them.
+pub(crate) fn children_position(position: &GUIPosition, widget: &dyn Widget) -> GUIPosition {
+    let offset = widget.get_scroll_offset();
+    GUIPosition::from_lengths(
+        position.x.subtract(&offset.x),
+        position.y.subtract(&offset.y),
+    )
+}
+
+/// Returns the area that a widget's children are clipped to, given where the widget is
+/// within the window and the area that the widget is clipped to itself.
+pub(crate) fn children_clip(
+    position: &GUIPosition,
+    widget: &dyn Widget,
+    clip: Option<ClipRect>,
+) -> Option<ClipRect> {
+    let viewport = match widget.get_viewport() {
+        Some((origin, size)) => ClipRect::new(
+            &GUIPosition::from_lengths(position.x.add(&origin.x), position.y.add(&origin.y)),
+            &size,
+        ),
+        None => return clip,
+    };
+    match clip {
+        Some(clip) => Some(clip.intersect(&viewport)),
+        None => Some(viewport),
+    }
+}
+
+/// Splits indices into runs of whole triangles that are clipped to the same area.
+/// A widget's vertices start at its polygon, so a triangle belongs to the last polygon
+/// that starts at or before its first vertex.
+pub fn clip_runs(indices: &[u32], polygons: &[Polygon]) -> Vec<(Range<usize>, Option<ClipRect>)> {
+    let mut runs: Vec<(Range<usize>, Option<ClipRect>)> = Vec::new();
+    for (triangle_index, triangle) in indices.chunks_exact(3).enumerate() {
+        let owner = polygons.partition_point(|polygon| polygon.start_index <= triangle[0] as usize);
+        let clip = owner.checked_sub(1).and_then(|owner| polygons[owner].clip);
+        let start = triangle_index * 3;
+        match runs.last_mut() {
+            Some((range, last_clip)) if *last_clip == clip => range.end = start + 3,
+            _ => runs.push((start..start + 3, clip)),
+        }
+    }
+    runs
+}
+
 pub fn get_clicked_widget(
     polygons: &Vec<Polygon>,
     vertices: &Vec<LogicalVertex>,
@@ -121,6 +325,10 @@ pub fn get_clicked_widget(
     let py = position.y.get_length() as f32;
 
     for polygon in polygons.iter().rev() {
+        // Parts of widgets that have been scrolled out of view can't be clicked.
+        if polygon.clip.is_some_and(|clip| !clip.contains(px, py)) {
+            continue;
+        }
         if polygon.rendered {
             if is_inside_polygon(&vertices[polygon.start_index..polygon.end_index], px, py) {
                 return Some(polygon.widget_id);
@@ -160,3 +368,78 @@ fn does_cross(p1x: f32, p1y: f32, p2x: f32, p2y: f32, px: f32, py: f32) -> u32 {
     }
     0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guiposition::GUIPosition;
+    use crate::guiwidgets::{GUIButton, GUIWindow};
+
+    /// Builds a window with rows of buttons that each have a nested button,
+    /// so that the offsets of both siblings and children are exercised.
+    fn make_large_tree(rows: usize) -> GUIBase {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        for row in 0..rows {
+            let parent_id = guibase.add_child_to_parent(
+                GUIButton {
+                    position: GUIPosition::from_pixels(0., row as f64),
+                    ..Default::default()
+                },
+                window_id,
+            );
+            guibase.add_child_to_parent(GUIButton::default(), parent_id);
+        }
+        guibase
+    }
+
+    #[test]
+    fn large_trees_index_past_u16() {
+        let guibase = make_large_tree(2000);
+        let (vertices, indices, polygons) = make_vertices_and_indices(&guibase, guibase.base_window);
+
+        assert!(vertices.len() > u16::MAX as usize);
+        assert_eq!(polygons.len(), 4000);
+        assert_eq!(*indices.iter().max().unwrap() as usize, vertices.len() - 1);
+        for index in indices.iter() {
+            assert!((*index as usize) < vertices.len());
+        }
+    }
+
+    #[test]
+    fn polygons_cover_their_own_vertices() {
+        let guibase = make_large_tree(1500);
+        let (vertices, indices, polygons) = make_vertices_and_indices(&guibase, guibase.base_window);
+
+        let mut next_start = 0;
+        for polygon in polygons.iter() {
+            assert_eq!(polygon.start_index, next_start);
+            next_start = polygon.end_index;
+        }
+        assert_eq!(next_start, vertices.len());
+
+        // Every triangle fans out from the first vertex of a polygon.
+        for triangle in indices.chunks(3) {
+            let polygon = polygons
+                .iter()
+                .find(|polygon| polygon.start_index == triangle[0] as usize)
+                .unwrap();
+            assert!((triangle[2] as usize) < polygon.end_index);
+        }
+    }
+
+    #[test]
+    fn large_trees_are_still_clickable() {
+        let guibase = make_large_tree(2000);
+        let (vertices, _, polygons) = make_vertices_and_indices(&guibase, guibase.base_window);
+        let last_row = GUIPosition::from_pixels(100., 1999. + 50.);
+        let clicked = get_clicked_widget(&polygons, &vertices, &last_row).unwrap();
+        assert_eq!(clicked, polygons.last().unwrap().widget_id);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_overflow_panics() {
+        to_index(u32::MAX as usize + 1);
+    }
+}
@@ -0,0 +1,756 @@
+use std::num::NonZeroU32;
+use std::ops::Range;
+
+use wgpu::{BindGroup, BindGroupLayout, Device, Queue, Sampler, Texture};
+use winit::window::Window;
+
+use crate::guierror::GUIError;
+use crate::guiproperties::guiposition::GUISize;
+use crate::guiproperties::GUIColor;
+use crate::guiresources::{GUIRasterizer, GUIResources};
+
+use super::buffers::GrowableBuffer;
+use super::processing_utils;
+use super::software::Framebuffer;
+use super::tessellation::{GeometryChanges, TessellationCache, WindowGeometry};
+use super::text::GlyphAtlas;
+use super::vertices::{ClipRect, LogicalTextVertex, LogicalVertex, TextVertex, Vertex};
+
+/// Everything that's shared between windows:
+/// the gpu, the glyph atlas, and the tessellated widget tree.
+pub struct Renderer {
+    /// The gpu, which is None when the software rasterizer's frames are shown without it.
+    pub gpu: Option<Gpu>,
+    /// Whether the widgets are drawn by the gpu or on the cpu.
+    pub rasterizer: GUIRasterizer,
+    pub glyph_atlas: GlyphAtlas,
+    /// The tessellated widgets, kept between frames so only changed ones are redone.
+    pub tessellation: TessellationCache,
+}
+
+/// The gpu and everything on it: the pipelines and the glyph atlas's texture.
+pub struct Gpu {
+    pub instance: wgpu::Instance,
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    /// The texture format that everything is drawn in.
+    pub format: wgpu::TextureFormat,
+
+    render_pipeline: wgpu::RenderPipeline,
+
+    text_render_pipeline: wgpu::RenderPipeline,
+    atlas_bind_group_layout: BindGroupLayout,
+    atlas_sampler: Sampler,
+    atlas_texture: Texture,
+    atlas_texture_size: (u32, u32),
+    atlas_bind_group: BindGroup,
+
+    /// Draws images made by the software rasterizer onto the view.
+    blit_render_pipeline: wgpu::RenderPipeline,
+}
+
+impl Renderer {
+    /// Tries each of backends in order until one has an adapter that can draw to window,
+    /// if there is one, and sets up the pipelines for drawing to it.
+    /// Returns the surface that was made for window with the chosen backend.
+    /// force_fallback_adapter picks a software adapter, such as llvmpipe or WARP.
+    pub async fn new(
+        guiresources: &GUIResources,
+        window: Option<&Window>,
+        backends: &[wgpu::Backends],
+        force_fallback_adapter: bool,
+    ) -> Result<(Self, Option<wgpu::Surface>), GUIError> {
+        let mut error = GUIError::NoAdapter;
+        for backends in backends {
+            let instance = wgpu::Instance::new(*backends);
+            let surface = window.map(|window| unsafe { instance.create_surface(window) });
+            // The adapter is the handle to the actual graphics card.
+            let adapter = match instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    power_preference: guiresources.power_preference(),
+                    compatible_surface: surface.as_ref(),
+                    force_fallback_adapter: force_fallback_adapter
+                        || guiresources.force_fallback_adapter(),
+                })
+                .await
+            {
+                Some(adapter) => adapter,
+                None => continue,
+            };
+
+            let format = match &surface {
+                Some(surface) => match surface.get_preferred_format(&adapter) {
+                    Some(format) => format,
+                    None => continue,
+                },
+                None => wgpu::TextureFormat::Rgba8UnormSrgb,
+            };
+
+            match adapter
+                .request_device(
+                    &wgpu::DeviceDescriptor {
+                        label: None,
+                        // Fallback adapters may only support the downlevel limits,
+                        // but textures can be as big as the adapter allows, so that
+                        // windows on big screens still fit.
+                        features: wgpu::Features::empty(),
+                        limits: wgpu::Limits::downlevel_defaults()
+                            .using_resolution(adapter.limits()),
+                    },
+                    // Some(&std::path::Path::new("trace")), // Trace path
+                    None,
+                )
+                .await
+            {
+                Ok((device, queue)) => {
+                    let mut glyph_atlas = GlyphAtlas::default();
+                    glyph_atlas.set_max_height(device.limits().max_texture_dimension_2d);
+                    let gpu = Gpu::new(instance, device, queue, format, &glyph_atlas);
+                    let renderer = Self {
+                        gpu: Some(gpu),
+                        rasterizer: guiresources.rasterizer(),
+                        glyph_atlas,
+                        tessellation: TessellationCache::new(),
+                    };
+                    return Ok((renderer, surface));
+                }
+                // Keep trying, but remember why this one didn't work.
+                Err(e) => error = e.into(),
+            }
+        }
+        Err(error)
+    }
+
+    /// Sets up a renderer for the software rasterizer whose frames are shown without wgpu,
+    /// so that it doesn't need an adapter at all.
+    pub fn without_gpu() -> Self {
+        Self {
+            gpu: None,
+            rasterizer: GUIRasterizer::Software,
+            glyph_atlas: GlyphAtlas::default(),
+            tessellation: TessellationCache::new(),
+        }
+    }
+
+    /// Copies the glyph atlas to the gpu if glyphs have been added to it.
+    pub fn upload_glyph_atlas(&mut self) {
+        if !self.glyph_atlas.is_dirty() {
+            return;
+        }
+        if let Some(gpu) = &mut self.gpu {
+            gpu.upload_glyph_atlas(&self.glyph_atlas);
+        }
+        self.glyph_atlas.mark_clean();
+    }
+}
+
+impl Gpu {
+    /// Sets up the pipelines for drawing in format with device,
+    /// and a texture that glyph_atlas fits in.
+    fn new(
+        instance: wgpu::Instance,
+        device: Device,
+        queue: Queue,
+        format: wgpu::TextureFormat,
+        glyph_atlas: &GlyphAtlas,
+    ) -> Self {
+        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/shader.wgsl").into()),
+        });
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                polygon_mode: wgpu::PolygonMode::Fill,
+                // Requires Features::DEPTH_CLIP_CONTROL
+                unclipped_depth: false,
+                // Requires Features::CONSERVATIVE_RASTERIZATION
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            // If the pipeline will be used with a multiview render pass, this
+            // indicates how many array layers the attachments will have.
+            multiview: None,
+        });
+
+        let text_shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Text Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/text.wgsl").into()),
+        });
+
+        let atlas_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Glyph Atlas Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let atlas_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Glyph Atlas Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        let text_render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Text Render Pipeline Layout"),
+                bind_group_layouts: &[&atlas_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+
+        let text_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Text Render Pipeline"),
+            layout: Some(&text_render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &text_shader,
+                entry_point: "vs_main",
+                buffers: &[TextVertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &text_shader,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let blit_shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/blit.wgsl").into()),
+        });
+
+        // The image and its sampler are bound the same way as the glyph atlas.
+        let blit_render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Blit Render Pipeline"),
+            layout: Some(&text_render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &blit_shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &blit_shader,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let (atlas_texture, atlas_bind_group) = make_atlas_texture(
+            glyph_atlas,
+            &device,
+            &atlas_bind_group_layout,
+            &atlas_sampler,
+        );
+
+        Self {
+            instance,
+            device,
+            queue,
+            format,
+            render_pipeline,
+            text_render_pipeline,
+            atlas_bind_group_layout,
+            atlas_sampler,
+            atlas_texture,
+            atlas_texture_size: (glyph_atlas.width(), glyph_atlas.height()),
+            atlas_bind_group,
+            blit_render_pipeline,
+        }
+    }
+
+    /// Copies glyph_atlas to its texture, replacing the texture if the atlas has grown.
+    fn upload_glyph_atlas(&mut self, glyph_atlas: &GlyphAtlas) {
+        let atlas_size = (glyph_atlas.width(), glyph_atlas.height());
+        if self.atlas_texture_size != atlas_size {
+            let (atlas_texture, atlas_bind_group) = make_atlas_texture(
+                glyph_atlas,
+                &self.device,
+                &self.atlas_bind_group_layout,
+                &self.atlas_sampler,
+            );
+            self.atlas_texture = atlas_texture;
+            self.atlas_bind_group = atlas_bind_group;
+            self.atlas_texture_size = atlas_size;
+        }
+        write_atlas_texture(glyph_atlas, &self.atlas_texture, &self.queue);
+    }
+
+    /// Records a render pass that clears view to background_color
+    /// and then draws primitives on top of it.
+    /// view_size is the width and height of view in physical pixels.
+    pub fn draw(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        view_size: (u32, u32),
+        background_color: &GUIColor,
+        primitives: &WindowPrimitives,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: background_color.r,
+                        g: background_color.g,
+                        b: background_color.b,
+                        a: background_color.a,
+                    }),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+
+        if primitives.num_indices > 0 {
+            render_pass.set_pipeline(&self.render_pipeline);
+            render_pass.set_vertex_buffer(0, primitives.vertex_buffer.buffer().slice(..));
+            render_pass.set_index_buffer(
+                primitives.index_buffer.buffer().slice(..),
+                wgpu::IndexFormat::Uint32,
+            );
+            // Widgets within scroll views are only drawn within their viewports.
+            for (indices, clip) in primitives.clip_runs.iter() {
+                let (x, y, width, height) = primitives.scissor_rect(clip.as_ref(), view_size);
+                if width == 0 || height == 0 {
+                    continue;
+                }
+                render_pass.set_scissor_rect(x, y, width, height);
+                render_pass.draw_indexed(indices.clone(), 0, 0..1);
+            }
+            render_pass.set_scissor_rect(0, 0, view_size.0, view_size.1);
+        }
+
+        // Text is clipped when it's laid out, so it doesn't need a scissor rect.
+        if primitives.num_text_indices > 0 {
+            render_pass.set_pipeline(&self.text_render_pipeline);
+            render_pass.set_bind_group(0, &self.atlas_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, primitives.text_vertex_buffer.buffer().slice(..));
+            render_pass.set_index_buffer(
+                primitives.text_index_buffer.buffer().slice(..),
+                wgpu::IndexFormat::Uint32,
+            );
+            render_pass.draw_indexed(0..primitives.num_text_indices, 0, 0..1);
+        }
+    }
+
+    /// Copies a framebuffer drawn on the cpu into frame,
+    /// which is only replaced when it's missing or a different size.
+    pub fn write_frame(&self, frame: &mut Option<FrameTexture>, framebuffer: &Framebuffer) {
+        let size = (framebuffer.width(), framebuffer.height());
+        if frame.as_ref().map(|frame| frame.size) != Some(size) {
+            *frame = Some(self.make_frame_texture(size));
+        }
+        let frame = frame.as_ref().unwrap();
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &frame.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &framebuffer.to_rgba8(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(size.0 * 4),
+                rows_per_image: NonZeroU32::new(size.1),
+            },
+            wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    fn make_frame_texture(&self, size: (u32, u32)) -> FrameTexture {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Software Texture"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        });
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Software Bind Group"),
+            layout: &self.atlas_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.atlas_sampler),
+                },
+            ],
+        });
+
+        FrameTexture {
+            texture,
+            bind_group,
+            size,
+        }
+    }
+
+    /// Records a render pass that copies a frame drawn on the cpu onto view,
+    /// stretching it to fit.
+    pub fn draw_image(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        frame: &FrameTexture,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit Render Pass"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&self.blit_render_pipeline);
+        render_pass.set_bind_group(0, &frame.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// A window's last frame from the software rasterizer, kept on the gpu
+/// so that it can be shown again without being drawn or copied again.
+pub struct FrameTexture {
+    texture: Texture,
+    bind_group: BindGroup,
+    /// The width and height in physical pixels.
+    size: (u32, u32),
+}
+
+/// The vertex and index buffers that a single window is drawn from.
+pub struct WindowPrimitives {
+    vertex_buffer: GrowableBuffer,
+    index_buffer: GrowableBuffer,
+    num_indices: u32,
+    /// The runs of indices that are drawn with the same clip rect.
+    clip_runs: Vec<(Range<u32>, Option<ClipRect>)>,
+    /// The logical width and height that's stretched over the whole window.
+    content_size: (f32, f32),
+    text_vertex_buffer: GrowableBuffer,
+    text_index_buffer: GrowableBuffer,
+    num_text_indices: u32,
+}
+
+impl WindowPrimitives {
+    pub fn new(device: &Device) -> Self {
+        Self {
+            vertex_buffer: GrowableBuffer::new(device, "Vertex Buffer", wgpu::BufferUsages::VERTEX),
+            index_buffer: GrowableBuffer::new(device, "Index Buffer", wgpu::BufferUsages::INDEX),
+            num_indices: 0,
+            clip_runs: Vec::new(),
+            content_size: (1., 1.),
+            text_vertex_buffer: GrowableBuffer::new(
+                device,
+                "Text Vertex Buffer",
+                wgpu::BufferUsages::VERTEX,
+            ),
+            text_index_buffer: GrowableBuffer::new(
+                device,
+                "Text Index Buffer",
+                wgpu::BufferUsages::INDEX,
+            ),
+            num_text_indices: 0,
+        }
+    }
+
+    /// Writes a window's joined geometry into the buffers, mapping content_size onto the whole
+    /// window. If changes are given, only the ranges that they cover are written,
+    /// unless content_size is different from last time.
+    pub fn write(
+        &mut self,
+        gpu: &Gpu,
+        glyph_atlas: &GlyphAtlas,
+        geometry: &WindowGeometry,
+        changes: Option<&GeometryChanges>,
+        content_size: &GUISize,
+    ) {
+        let width = content_size.width.get_length() as f32;
+        let height = content_size.height.get_length() as f32;
+        let changes = changes.filter(|_| self.content_size == (width, height));
+        self.content_size = (width, height);
+        let atlas_width = glyph_atlas.width() as f32;
+        let atlas_height = glyph_atlas.height() as f32;
+        let to_vertex = |v: &LogicalVertex| v.to_vertex(width, height);
+        let to_text_vertex =
+            |v: &LogicalTextVertex| v.to_vertex(width, height, atlas_width, atlas_height);
+        let device = &gpu.device;
+        let queue = &gpu.queue;
+
+        match changes {
+            Some(changes) => {
+                self.vertex_buffer.write_ranges(
+                    device,
+                    queue,
+                    &geometry.vertices,
+                    &changes.vertices,
+                    to_vertex,
+                );
+                self.index_buffer.write_ranges(
+                    device,
+                    queue,
+                    &geometry.indices,
+                    &changes.indices,
+                    |index| *index,
+                );
+                self.text_vertex_buffer.write_ranges(
+                    device,
+                    queue,
+                    &geometry.text_vertices,
+                    &changes.text_vertices,
+                    to_text_vertex,
+                );
+                self.text_index_buffer.write_ranges(
+                    device,
+                    queue,
+                    &geometry.text_indices,
+                    &changes.text_indices,
+                    |index| *index,
+                );
+            }
+            None => {
+                let vertices: Vec<Vertex> = geometry.vertices.iter().map(to_vertex).collect();
+                self.vertex_buffer
+                    .write(device, queue, bytemuck::cast_slice(&vertices[..]));
+                self.index_buffer
+                    .write(device, queue, bytemuck::cast_slice(&geometry.indices[..]));
+                let text_vertices: Vec<TextVertex> =
+                    geometry.text_vertices.iter().map(to_text_vertex).collect();
+                self.text_vertex_buffer.write(
+                    device,
+                    queue,
+                    bytemuck::cast_slice(&text_vertices[..]),
+                );
+                self.text_index_buffer.write(
+                    device,
+                    queue,
+                    bytemuck::cast_slice(&geometry.text_indices[..]),
+                );
+            }
+        }
+
+        let clips_changed = match changes {
+            Some(changes) => changes.clips_changed,
+            None => true,
+        };
+        if clips_changed {
+            self.clip_runs = processing_utils::clip_runs(&geometry.indices, &geometry.polygons)
+                .into_iter()
+                .map(|(run, clip)| (run.start as u32..run.end as u32, clip))
+                .collect();
+        }
+        self.num_indices = geometry.indices.len() as u32;
+        self.num_text_indices = geometry.text_indices.len() as u32;
+    }
+
+    /// Converts a clip rect into a scissor rect within a view of view_size physical pixels,
+    /// as x, y, width and height. No clip rect covers the whole view.
+    fn scissor_rect(&self, clip: Option<&ClipRect>, view_size: (u32, u32)) -> (u32, u32, u32, u32) {
+        let clip = match clip {
+            Some(clip) => clip,
+            None => return (0, 0, view_size.0, view_size.1),
+        };
+        let to_physical = |length: f32, content: f32, view: u32| {
+            (length / content * view as f32).round().clamp(0., view as f32) as u32
+        };
+        let left = to_physical(clip.left, self.content_size.0, view_size.0);
+        let top = to_physical(clip.top, self.content_size.1, view_size.1);
+        let right = to_physical(clip.right, self.content_size.0, view_size.0);
+        let bottom = to_physical(clip.bottom, self.content_size.1, view_size.1);
+        (
+            left,
+            top,
+            right.saturating_sub(left),
+            bottom.saturating_sub(top),
+        )
+    }
+}
+
+fn make_atlas_texture(
+    atlas: &GlyphAtlas,
+    device: &Device,
+    layout: &BindGroupLayout,
+    sampler: &Sampler,
+) -> (Texture, BindGroup) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Glyph Atlas Texture"),
+        size: wgpu::Extent3d {
+            width: atlas.width(),
+            height: atlas.height(),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::R8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Glyph Atlas Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    });
+
+    (texture, bind_group)
+}
+
+fn write_atlas_texture(atlas: &GlyphAtlas, texture: &Texture, queue: &Queue) {
+    queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        atlas.pixels(),
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: NonZeroU32::new(atlas.width()),
+            rows_per_image: NonZeroU32::new(atlas.height()),
+        },
+        wgpu::Extent3d {
+            width: atlas.width(),
+            height: atlas.height(),
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_backends_is_no_adapter() {
+        let guiresources = GUIResources::default().with_backends(Vec::new());
+        let result = pollster::block_on(Renderer::new(
+            &guiresources,
+            None,
+            &guiresources.backends(),
+            false,
+        ));
+        assert!(matches!(result, Err(GUIError::NoAdapter)));
+    }
+}
@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use winit::event::{Event, KeyboardInput, VirtualKeyCode, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop, EventLoopWindowTarget};
+use winit::window::{WindowBuilder, WindowId};
+
+use crate::guierror::GUIError;
+use crate::guiproperties::guiposition::{GUIPosition, GUISize};
+use crate::guiresources::{GUIRasterizer, GUIResources};
+use crate::guiwidgets::GUIBase;
+
+use super::blit::WindowBlitter;
+use super::processing_utils;
+use super::renderer::Renderer;
+use super::state::{self, State, WindowOutput};
+
+/// Runs a GUIBase on an event loop, which can be one that the host application already has.
+/// Unlike guiprocessing::run, logging is left to the host unless with_logger is called.
+pub struct GUIRunner {
+    guibase: GUIBase,
+    guiresources: GUIResources,
+    /// Whether to set up env_logger before running.
+    init_logger: bool,
+    /// Given the errors that the gui carries on from while it's running.
+    error_handler: Option<Box<dyn FnMut(GUIError)>>,
+}
+
+impl GUIRunner {
+    pub fn new(guibase: GUIBase, guiresources: GUIResources) -> Self {
+        Self {
+            guibase,
+            guiresources,
+            init_logger: false,
+            error_handler: None,
+        }
+    }
+
+    /// Sets up env_logger when the runner starts, unless the host already has a logger.
+    pub fn with_logger(mut self) -> Self {
+        self.init_logger = true;
+        self
+    }
+
+    /// Sets the callback that's given the errors that the gui carries on from while it's
+    /// running, such as a window that couldn't be opened, so that the host can log them.
+    /// They're ignored otherwise.
+    pub fn on_error(mut self, handler: impl FnMut(GUIError) + 'static) -> Self {
+        self.error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Makes an event loop and runs on it. Like winit's EventLoop::run,
+    /// this only returns if the window or the gpu can't be set up,
+    /// and otherwise exits the process when the gui closes.
+    pub fn run(self) -> Result<(), GUIError> {
+        self.run_on(EventLoop::new())
+    }
+
+    /// Runs on an event loop that the host has made, which can carry its own user events.
+    /// Like winit's EventLoop::run, this only returns if the window or the gpu can't be set up,
+    /// and otherwise exits the process when the gui closes.
+    pub fn run_on<T: 'static>(self, event_loop: EventLoop<T>) -> Result<(), GUIError> {
+        let mut running = self.start(&event_loop)?;
+        event_loop.run(move |event, event_loop, control_flow| {
+            running.handle_event(event, event_loop, control_flow)
+        })
+    }
+
+    /// Runs on an event loop that the host keeps, returning once the gui closes
+    /// with the GUIBase as the gui left it.
+    /// The event loop can be run again afterwards.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub fn run_return<T: 'static>(
+        self,
+        event_loop: &mut EventLoop<T>,
+    ) -> Result<GUIBase, GUIError> {
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        let mut running = self.start(event_loop)?;
+        event_loop.run_return(|event, event_loop, control_flow| {
+            running.handle_event(event, event_loop, control_flow)
+        });
+        // Dropping the states closes the windows.
+        Ok(running.guibase)
+    }
+
+    /// Opens the windows and sets up the gpu to draw them.
+    fn start<T: 'static>(self, event_loop: &EventLoopWindowTarget<T>) -> Result<Running, GUIError> {
+        if self.init_logger {
+            // The host may have set up logging already, which is fine.
+            let _ = env_logger::try_init();
+        }
+        let GUIRunner {
+            mut guibase,
+            guiresources,
+            error_handler,
+            ..
+        } = self;
+        let mut report_error = error_handler.unwrap_or_else(|| Box::new(|_| {}));
+
+        let window = WindowBuilder::new().build(event_loop)?;
+        guibase.logical_scale = Some(window.scale_factor());
+        let window =
+            processing_utils::set_window_properties(window, &guibase, guibase.get_base_window());
+        guibase.layout();
+
+        // The software rasterizer's frames are copied straight onto the windows where that's
+        // supported, so that it doesn't need an adapter at all.
+        let blitter = match guiresources.rasterizer() {
+            GUIRasterizer::Software => WindowBlitter::new(&window).ok(),
+            GUIRasterizer::Gpu => None,
+        };
+        let (mut renderer, output) = match blitter {
+            Some(blitter) => (Renderer::without_gpu(), WindowOutput::Blit(blitter)),
+            None => {
+                // The base window's surface is used to pick an adapter that can draw to windows.
+                // The software rasterizer only needs the adapter to show its frames,
+                // so it'll take any backend that's available if none of the chosen ones work.
+                let mut backends = guiresources.backends();
+                if guiresources.rasterizer() == GUIRasterizer::Software {
+                    backends.push(wgpu::Backends::all());
+                }
+                // Renderer::new uses async code, so we're going to wait for it to finish
+                let (renderer, surface) = pollster::block_on(Renderer::new(
+                    &guiresources,
+                    Some(&window),
+                    &backends,
+                    false,
+                ))?;
+                // A surface is always made when there's a window.
+                (renderer, WindowOutput::Surface(surface.unwrap()))
+            }
+        };
+
+        let mut states: HashMap<WindowId, State> = HashMap::new();
+        let base_state = State::new(window, output, guibase.base_window, &guibase, &renderer);
+        states.insert(base_state.window.id(), base_state);
+        open_windows(
+            event_loop,
+            &mut guibase,
+            &renderer,
+            &mut states,
+            report_error.as_mut(),
+        );
+        state::update_primitives(&mut guibase, &mut renderer, &mut states);
+
+        Ok(Running {
+            guibase,
+            renderer,
+            states,
+            report_error,
+        })
+    }
+}
+
+/// Everything that's needed while the event loop is running.
+struct Running {
+    guibase: GUIBase,
+    renderer: Renderer,
+    states: HashMap<WindowId, State>,
+    /// Given the errors that the gui carries on from.
+    report_error: Box<dyn FnMut(GUIError)>,
+}
+
+impl Running {
+    fn handle_event<T: 'static>(
+        &mut self,
+        event: Event<T>,
+        event_loop: &EventLoopWindowTarget<T>,
+        control_flow: &mut ControlFlow,
+    ) {
+        let Running {
+            guibase,
+            renderer,
+            states,
+            report_error,
+        } = self;
+
+        match event {
+            Event::WindowEvent {
+                ref event,
+                window_id,
+            } => {
+                let my_state = match states.get_mut(&window_id) {
+                    Some(my_state) => my_state,
+                    None => return,
+                };
+                if !my_state.input(guibase, event) {
+                    match event {
+                        WindowEvent::CloseRequested
+                            if my_state.window_id != guibase.base_window =>
+                        {
+                            guibase.remove_window(my_state.window_id);
+                        }
+                        WindowEvent::CloseRequested
+                        | WindowEvent::KeyboardInput {
+                            input:
+                                KeyboardInput {
+                                    state: winit::event::ElementState::Pressed,
+                                    virtual_keycode: Some(VirtualKeyCode::Escape),
+                                    ..
+                                },
+                            ..
+                        } => *control_flow = ControlFlow::Exit,
+                        WindowEvent::Resized(physical_size) => {
+                            let new_size = GUISize::from_physical_pixels(
+                                physical_size.width as f64,
+                                physical_size.height as f64,
+                                &guibase.logical_scale.unwrap(),
+                            );
+                            my_state.resize(guibase, renderer, new_size);
+                        }
+                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                            // new_inner_size is &&mut so w have to dereference it twice
+                            let new_size = GUISize::from_physical_pixels(
+                                new_inner_size.width as f64,
+                                new_inner_size.height as f64,
+                                &guibase.logical_scale.unwrap(),
+                            );
+                            my_state.resize(guibase, renderer, new_size);
+                        }
+                        WindowEvent::MouseInput { state, button, .. } => {
+                            my_state.mouse_input(guibase, state, button);
+                        }
+                        WindowEvent::CursorMoved { position, .. } => {
+                            let position = GUIPosition::from_physical_pixels(
+                                position.x,
+                                position.y,
+                                &guibase.logical_scale.unwrap(),
+                            );
+                            my_state.set_curser_position(guibase, position);
+                        }
+                        _ => {}
+                    }
+                }
+                // Keep what's hit tested up to date with what the event changed.
+                if state::update_primitives(guibase, renderer, states) {
+                    guibase.request_redraw();
+                }
+            }
+            Event::RedrawRequested(window_id) => {
+                let my_state = match states.get_mut(&window_id) {
+                    Some(my_state) => my_state,
+                    None => return,
+                };
+                match my_state.render(guibase, renderer) {
+                    Ok(_) => {}
+                    // Reconfigure the surface if lost
+                    Err(wgpu::SurfaceError::Lost) => {
+                        let size = guibase.get_window(my_state.window_id).size;
+                        my_state.resize(guibase, renderer, size);
+                    }
+                    // The system is out of memory, we should probably quit
+                    Err(wgpu::SurfaceError::OutOfMemory) => *control_flow = ControlFlow::Exit,
+                    // All other errors (Outdated, Timeout) should be resolved by the next frame
+                    Err(e) => report_error(e.into()),
+                }
+            }
+            Event::MainEventsCleared => {
+                // Open windows that have been added and close ones that have been removed.
+                states.retain(|_, my_state| guibase.windows.contains_key(&my_state.window_id));
+                open_windows(event_loop, guibase, renderer, states, report_error.as_mut());
+
+                // Only draw when something has changed, or an animation or timer needs it,
+                // and otherwise sleep until the next event or timer.
+                let now = Instant::now();
+                guibase.advance(now);
+                if state::update_primitives(guibase, renderer, states) {
+                    guibase.request_redraw();
+                }
+                if guibase.take_redraw_request(now) {
+                    for my_state in states.values() {
+                        my_state.window.request_redraw();
+                    }
+                }
+                if guibase.take_exit_request() {
+                    *control_flow = ControlFlow::Exit;
+                }
+                if *control_flow != ControlFlow::Exit {
+                    *control_flow = match guibase.next_wake_time() {
+                        Some(time) => ControlFlow::WaitUntil(time),
+                        None => ControlFlow::Wait,
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Makes a winit window, and the State that draws it, for every GUIWindow that doesn't have one.
+/// GUIWindows whose window can't be made are removed, and why is reported.
+fn open_windows<T: 'static>(
+    event_loop: &EventLoopWindowTarget<T>,
+    guibase: &mut GUIBase,
+    renderer: &Renderer,
+    states: &mut HashMap<WindowId, State>,
+    report_error: &mut dyn FnMut(GUIError),
+) {
+    let mut failures = Vec::new();
+    for (window_id, gwindow) in guibase.windows.iter() {
+        if states
+            .values()
+            .any(|my_state| my_state.window_id == *window_id)
+        {
+            continue;
+        }
+        let window = match WindowBuilder::new().build(event_loop) {
+            Ok(window) => window,
+            Err(e) => {
+                failures.push((*window_id, GUIError::from(e)));
+                continue;
+            }
+        };
+        let window = processing_utils::set_window_properties(window, guibase, gwindow.get_window());
+        let output = match &renderer.gpu {
+            Some(gpu) => WindowOutput::Surface(unsafe { gpu.instance.create_surface(&window) }),
+            None => match WindowBlitter::new(&window) {
+                Ok(blitter) => WindowOutput::Blit(blitter),
+                Err(e) => {
+                    failures.push((*window_id, e));
+                    continue;
+                }
+            },
+        };
+        let my_state = State::new(window, output, *window_id, guibase, renderer);
+        states.insert(my_state.window.id(), my_state);
+    }
+    remove_failed_windows(guibase, failures, report_error);
+}
+
+/// Removes the GUIWindows whose window couldn't be made, rather than trying them again
+/// every frame, and reports why.
+fn remove_failed_windows(
+    guibase: &mut GUIBase,
+    failures: Vec<(u128, GUIError)>,
+    report_error: &mut dyn FnMut(GUIError),
+) {
+    for (window_id, error) in failures {
+        guibase.remove_window(window_id);
+        report_error(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::guiwidgets::{GUILabel, GUIWindow};
+
+    #[test]
+    fn failed_windows_are_removed_and_reported() {
+        let mut guibase = GUIBase::new();
+        let base_id = guibase.add_window(GUIWindow::default());
+        let window_id = guibase.add_window(GUIWindow::default());
+        let mut errors = Vec::new();
+        remove_failed_windows(
+            &mut guibase,
+            vec![(window_id, GUIError::NoAdapter)],
+            &mut |error| errors.push(error),
+        );
+        assert!(guibase.windows.contains_key(&base_id));
+        assert!(!guibase.windows.contains_key(&window_id));
+        assert!(matches!(errors[..], [GUIError::NoAdapter]));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn run_return_gives_the_guibase_back() {
+        use winit::platform::unix::EventLoopExtUnix;
+
+        // Machines such as CI containers don't always have a display to open windows on.
+        let mut event_loop = match std::panic::catch_unwind(EventLoop::<()>::new_any_thread) {
+            Ok(event_loop) => event_loop,
+            Err(_) => return,
+        };
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let label_id = guibase.add_child_to_parent(GUILabel::new("Before"), window_id);
+        guibase.add_timer(Duration::ZERO, move |guibase| {
+            guibase
+                .get_widget_as_mut::<GUILabel>(label_id)
+                .unwrap()
+                .set_text("After");
+            guibase.exit();
+        });
+        let runner = GUIRunner::new(guibase, GUIResources::default());
+
+        match runner.run_return(&mut event_loop) {
+            Ok(guibase) => {
+                let label = guibase.get_widget_as::<GUILabel>(label_id).unwrap();
+                assert_eq!(label.text, "After");
+            }
+            // There's a display, but no adapter that can draw to it.
+            Err(error) => assert!(matches!(error, GUIError::NoAdapter)),
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use crate::guiproperties::guiposition::GUISize;
+use crate::guiproperties::GUIColor;
+use crate::guiwidgets::GUIBase;
+
+use super::processing_utils;
+use super::text::GlyphAtlas;
+use super::vertices::{ClipRect, LogicalTextVertex, LogicalVertex, Polygon};
+
+/// The number of samples taken along each side of a pixel
+/// when working out how much of the pixel a shape covers.
+const SAMPLES_PER_SIDE: usize = 4;
+const SAMPLE_COUNT: usize = SAMPLES_PER_SIDE * SAMPLES_PER_SIDE;
+
+/// An image that the tessellated widgets are drawn into on the cpu, instead of through wgpu.
+/// Colors are premultiplied and linear, the same as the gpu pipelines blend them.
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    /// The logical size that's stretched over the framebuffer.
+    logical_width: f32,
+    logical_height: f32,
+    pixels: Vec<[f32; 4]>,
+}
+
+impl Framebuffer {
+    /// Returns a framebuffer of width by height physical pixels that logical_size is
+    /// stretched over, cleared to background_color.
+    pub fn new(
+        width: u32,
+        height: u32,
+        logical_size: &GUISize,
+        background_color: &GUIColor,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            logical_width: logical_size.width.get_length() as f32,
+            logical_height: logical_size.height.get_length() as f32,
+            pixels: vec![background_color.to_f32_array(); (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the premultiplied, linear red, green, blue and alpha of a pixel.
+    pub fn get_pixel(&self, x: u32, y: u32) -> [f32; 4] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Fills the triangles of every polygon, as made by make_vertices_and_indices.
+    /// Each polygon is anti-aliased as a whole and then blended over what's already been
+    /// drawn, so the edges between the triangles within a polygon don't show.
+    /// Triangles outside of the polygons are grouped the same way when they share vertices.
+    /// Every shape is clipped to the clip rect of the polygon that its widget starts with.
+    pub fn fill(&mut self, vertices: &[LogicalVertex], indices: &[u32], polygons: &[Polygon]) {
+        let mut owners: Vec<Option<usize>> = vec![None; vertices.len()];
+        let mut clips: Vec<Option<ClipRect>> = vec![None; vertices.len()];
+        for (polygon_index, polygon) in polygons.iter().enumerate() {
+            for owner in owners[polygon.start_index..polygon.end_index].iter_mut() {
+                *owner = Some(polygon_index);
+            }
+            // A widget's vertices run up to where the next widget's start.
+            let end = match polygons.get(polygon_index + 1) {
+                Some(next) => next.start_index,
+                None => vertices.len(),
+            };
+            for clip in clips[polygon.start_index..end].iter_mut() {
+                *clip = polygon.clip;
+            }
+        }
+
+        let mut shape: Vec<&[u32]> = Vec::new();
+        for triangle in indices.chunks_exact(3) {
+            if let Some(last) = shape.last() {
+                let owner = owners[triangle[0] as usize];
+                let same_shape = match owner {
+                    Some(_) => owner == owners[last[0] as usize],
+                    None => {
+                        owners[last[0] as usize].is_none()
+                            && triangle.iter().any(|index| last.contains(index))
+                    }
+                };
+                if !same_shape {
+                    self.fill_shape(vertices, &shape, clips[last[0] as usize].as_ref());
+                    shape.clear();
+                }
+            }
+            shape.push(triangle);
+        }
+        if let Some(first) = shape.first() {
+            self.fill_shape(vertices, &shape, clips[first[0] as usize].as_ref());
+        }
+    }
+
+    /// Draws text as made by make_glyph_vertices_and_indices,
+    /// where every glyph is a quad of four vertices.
+    pub fn draw_text(&mut self, vertices: &[LogicalTextVertex], atlas: &GlyphAtlas) {
+        for quad in vertices.chunks_exact(4) {
+            let [left, top] = self.to_pixel(&quad[0].position);
+            let [right, bottom] = self.to_pixel(&quad[2].position);
+            if right <= left || bottom <= top {
+                continue;
+            }
+            let [tex_left, tex_top] = quad[0].tex_coords;
+            let [tex_right, tex_bottom] = quad[2].tex_coords;
+            let color = quad[0].color;
+
+            let (x0, x1) = self.clamp_span(left, right, self.width);
+            let (y0, y1) = self.clamp_span(top, bottom, self.height);
+            for y in y0..y1 {
+                let v = tex_top + (y as f32 + 0.5 - top) / (bottom - top) * (tex_bottom - tex_top);
+                for x in x0..x1 {
+                    let u = tex_left
+                        + (x as f32 + 0.5 - left) / (right - left) * (tex_right - tex_left);
+                    let alpha = color[3] * sample_atlas(atlas, u, v);
+                    if alpha > 0. {
+                        let source = [color[0] * alpha, color[1] * alpha, color[2] * alpha, alpha];
+                        self.blend(x, y, source);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the pixels as red, green, blue and alpha bytes in the sRGB color space,
+    /// not premultiplied, row by row from the top left corner.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.pixels.len() * 4);
+        for [r, g, b, a] in self.pixels.iter().copied() {
+            let a = a.clamp(0., 1.);
+            let unpremultiply = |channel: f32| if a > 0. { channel / a } else { 0. };
+            bytes.push(to_srgb_byte(unpremultiply(r)));
+            bytes.push(to_srgb_byte(unpremultiply(g)));
+            bytes.push(to_srgb_byte(unpremultiply(b)));
+            bytes.push((a * 255.).round() as u8);
+        }
+        bytes
+    }
+
+    /// Converts a logical position into physical pixels within the framebuffer.
+    fn to_pixel(&self, position: &[f32; 3]) -> [f32; 2] {
+        [
+            position[0] / self.logical_width * self.width as f32,
+            position[1] / self.logical_height * self.height as f32,
+        ]
+    }
+
+    /// Returns the pixels that are at least partly within start..end, within 0..limit.
+    fn clamp_span(&self, start: f32, end: f32, limit: u32) -> (u32, u32) {
+        (
+            start.floor().clamp(0., limit as f32) as u32,
+            end.ceil().clamp(0., limit as f32) as u32,
+        )
+    }
+
+    /// Returns the pixels that are within clip, rounded to the nearest pixel like a scissor rect,
+    /// as the columns and rows, or the whole framebuffer if there isn't a clip rect.
+    fn clip_pixels(&self, clip: Option<&ClipRect>) -> ((u32, u32), (u32, u32)) {
+        let clip = match clip {
+            Some(clip) => clip,
+            None => return ((0, self.width), (0, self.height)),
+        };
+        let [left, top] = self.to_pixel(&[clip.left, clip.top, 0.]);
+        let [right, bottom] = self.to_pixel(&[clip.right, clip.bottom, 0.]);
+        let round = |value: f32, limit: u32| value.round().clamp(0., limit as f32) as u32;
+        (
+            (round(left, self.width), round(right, self.width)),
+            (round(top, self.height), round(bottom, self.height)),
+        )
+    }
+
+    fn fill_shape(
+        &mut self,
+        vertices: &[LogicalVertex],
+        triangles: &[&[u32]],
+        clip: Option<&ClipRect>,
+    ) {
+        let points: Vec<[f32; 2]> = triangles
+            .iter()
+            .flat_map(|triangle| triangle.iter())
+            .map(|index| self.to_pixel(&vertices[*index as usize].position))
+            .collect();
+        if points.is_empty() {
+            return;
+        }
+        let min_x = points
+            .iter()
+            .map(|point| point[0])
+            .fold(f32::INFINITY, f32::min);
+        let max_x = points
+            .iter()
+            .map(|point| point[0])
+            .fold(f32::NEG_INFINITY, f32::max);
+        let min_y = points
+            .iter()
+            .map(|point| point[1])
+            .fold(f32::INFINITY, f32::min);
+        let max_y = points
+            .iter()
+            .map(|point| point[1])
+            .fold(f32::NEG_INFINITY, f32::max);
+        let ((clip_x0, clip_x1), (clip_y0, clip_y1)) = self.clip_pixels(clip);
+        let (x0, x1) = self.clamp_span(min_x, max_x, self.width);
+        let (y0, y1) = self.clamp_span(min_y, max_y, self.height);
+        let (x0, x1) = (x0.max(clip_x0), x1.min(clip_x1));
+        let (y0, y1) = (y0.max(clip_y0), y1.min(clip_y1));
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        // Which samples of each pixel are covered, and the sum of their colors.
+        let shape_width = (x1 - x0) as usize;
+        let mut masks = vec![0u16; shape_width * (y1 - y0) as usize];
+        let mut sums = vec![[0f32; 4]; masks.len()];
+
+        for (triangle, corners) in triangles.iter().zip(points.chunks_exact(3)) {
+            let area = edge(&corners[0], &corners[1], &corners[2]);
+            if area == 0. {
+                continue;
+            }
+            let colors: Vec<[f32; 4]> = triangle
+                .iter()
+                .map(|index| premultiply(vertices[*index as usize].color))
+                .collect();
+
+            let left = corners
+                .iter()
+                .map(|point| point[0])
+                .fold(f32::INFINITY, f32::min);
+            let right = corners
+                .iter()
+                .map(|point| point[0])
+                .fold(f32::NEG_INFINITY, f32::max);
+            let top = corners
+                .iter()
+                .map(|point| point[1])
+                .fold(f32::INFINITY, f32::min);
+            let bottom = corners
+                .iter()
+                .map(|point| point[1])
+                .fold(f32::NEG_INFINITY, f32::max);
+            let (tx0, tx1) = self.clamp_span(left, right, self.width);
+            let (ty0, ty1) = self.clamp_span(top, bottom, self.height);
+            let (tx0, tx1) = (tx0.max(x0), tx1.min(x1));
+            let (ty0, ty1) = (ty0.max(y0), ty1.min(y1));
+
+            for y in ty0..ty1 {
+                for x in tx0..tx1 {
+                    let cell = (y - y0) as usize * shape_width + (x - x0) as usize;
+                    for sample in 0..SAMPLE_COUNT {
+                        let bit = 1 << sample;
+                        if masks[cell] & bit != 0 {
+                            continue;
+                        }
+                        let point = [
+                            x as f32
+                                + ((sample % SAMPLES_PER_SIDE) as f32 + 0.5)
+                                    / SAMPLES_PER_SIDE as f32,
+                            y as f32
+                                + ((sample / SAMPLES_PER_SIDE) as f32 + 0.5)
+                                    / SAMPLES_PER_SIDE as f32,
+                        ];
+                        // Dividing by the area makes the weights positive inside
+                        // the triangle whichever way around it's wound.
+                        let w0 = edge(&corners[1], &corners[2], &point) / area;
+                        let w1 = edge(&corners[2], &corners[0], &point) / area;
+                        let w2 = edge(&corners[0], &corners[1], &point) / area;
+                        if w0 < 0. || w1 < 0. || w2 < 0. {
+                            continue;
+                        }
+                        masks[cell] |= bit;
+                        for channel in 0..4 {
+                            sums[cell][channel] += w0 * colors[0][channel]
+                                + w1 * colors[1][channel]
+                                + w2 * colors[2][channel];
+                        }
+                    }
+                }
+            }
+        }
+
+        for (cell, sum) in sums.iter().enumerate() {
+            if masks[cell] == 0 {
+                continue;
+            }
+            let x = x0 + (cell % shape_width) as u32;
+            let y = y0 + (cell / shape_width) as u32;
+            let source = sum.map(|channel| channel / SAMPLE_COUNT as f32);
+            self.blend(x, y, source);
+        }
+    }
+
+    /// Blends a premultiplied color over a pixel.
+    fn blend(&mut self, x: u32, y: u32, source: [f32; 4]) {
+        let pixel = &mut self.pixels[(y * self.width + x) as usize];
+        let remaining = 1. - source[3];
+        for channel in 0..4 {
+            pixel[channel] = source[channel] + pixel[channel] * remaining;
+        }
+    }
+}
+
+/// Draws a window's widgets and text into a framebuffer of width by height physical pixels,
+/// stretching logical_size over it.
+pub fn render_window(
+    guibase: &GUIBase,
+    window_id: u128,
+    atlas: &mut GlyphAtlas,
+    width: u32,
+    height: u32,
+    logical_size: &GUISize,
+) -> Framebuffer {
+    let mut framebuffer = Framebuffer::new(
+        width,
+        height,
+        logical_size,
+        &guibase.get_window(window_id).background_color,
+    );
+    let (vertices, indices, polygons) =
+        processing_utils::make_vertices_and_indices(guibase, window_id);
+    framebuffer.fill(&vertices, &indices, &polygons);
+    let atlas_generation = atlas.generation();
+    let (mut text_vertices, _) =
+        processing_utils::make_text_vertices_and_indices(guibase, window_id, atlas);
+    // Glyphs that were laid out before the atlas grew or was cleared have moved.
+    if atlas_generation != atlas.generation() {
+        (text_vertices, _) =
+            processing_utils::make_text_vertices_and_indices(guibase, window_id, atlas);
+    }
+    framebuffer.draw_text(&text_vertices, atlas);
+
+    framebuffer
+}
+
+/// Returns twice the signed area of the triangle a, b, point.
+fn edge(a: &[f32; 2], b: &[f32; 2], point: &[f32; 2]) -> f32 {
+    (b[0] - a[0]) * (point[1] - a[1]) - (b[1] - a[1]) * (point[0] - a[0])
+}
+
+fn premultiply(color: [f32; 4]) -> [f32; 4] {
+    [
+        color[0] * color[3],
+        color[1] * color[3],
+        color[2] * color[3],
+        color[3],
+    ]
+}
+
+/// Samples the coverage of the atlas at u, v in atlas pixels,
+/// filtering linearly and clamping to the edges like the gpu sampler.
+fn sample_atlas(atlas: &GlyphAtlas, u: f32, v: f32) -> f32 {
+    let texel = |x: i64, y: i64| {
+        let x = x.clamp(0, atlas.width() as i64 - 1) as u32;
+        let y = y.clamp(0, atlas.height() as i64 - 1) as u32;
+        atlas.pixels()[(y * atlas.width() + x) as usize] as f32 / 255.
+    };
+    let x = u - 0.5;
+    let y = v - 0.5;
+    let (left, top) = (x.floor(), y.floor());
+    let (fx, fy) = (x - left, y - top);
+    let (left, top) = (left as i64, top as i64);
+    let upper = texel(left, top) * (1. - fx) + texel(left + 1, top) * fx;
+    let lower = texel(left, top + 1) * (1. - fx) + texel(left + 1, top + 1) * fx;
+    upper * (1. - fy) + lower * fy
+}
+
+/// Encodes a linear color channel in the sRGB transfer function, like an sRGB texture does.
+fn to_srgb_byte(channel: f32) -> u8 {
+    let channel = channel.clamp(0., 1.);
+    let encoded = if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1. / 2.4) - 0.055
+    };
+    (encoded * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guiposition::GUIPosition;
+    use crate::guiwidgets::{GUIButton, GUILabel, GUIWindow};
+
+    const RED: [f32; 4] = [1., 0., 0., 1.];
+    const CLEAR: GUIColor = GUIColor {
+        r: 0.,
+        g: 0.,
+        b: 0.,
+        a: 0.,
+    };
+
+    fn vertex(x: f32, y: f32, color: [f32; 4]) -> LogicalVertex {
+        LogicalVertex {
+            position: [x, y, 0.],
+            color,
+        }
+    }
+
+    /// A square made of two triangles that share a diagonal.
+    fn square(
+        left: f32,
+        top: f32,
+        size: f32,
+        color: [f32; 4],
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Vec<Polygon>) {
+        let vertices = vec![
+            vertex(left, top, color),
+            vertex(left, top + size, color),
+            vertex(left + size, top + size, color),
+            vertex(left + size, top, color),
+        ];
+        let polygon = Polygon {
+            start_index: 0,
+            end_index: 4,
+            ..Default::default()
+        };
+        (vertices, vec![0, 1, 2, 0, 2, 3], vec![polygon])
+    }
+
+    fn framebuffer(size: u32) -> Framebuffer {
+        Framebuffer::new(
+            size,
+            size,
+            &GUISize::from_pixels(size as f64, size as f64),
+            &CLEAR,
+        )
+    }
+
+    fn assert_close(actual: [f32; 4], expected: [f32; 4]) {
+        for (actual, expected) in actual.iter().zip(expected.iter()) {
+            assert!(
+                (actual - expected).abs() < 1e-4,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn fills_polygons_without_seams() {
+        let mut framebuffer = framebuffer(8);
+        let (vertices, indices, polygons) = square(1., 1., 6., RED);
+        framebuffer.fill(&vertices, &indices, &polygons);
+
+        // Pixels on the shared diagonal are covered exactly once.
+        for i in 1..7 {
+            assert_close(framebuffer.get_pixel(i, i), RED);
+        }
+        assert_close(framebuffer.get_pixel(0, 0), [0.; 4]);
+        assert_close(framebuffer.get_pixel(7, 3), [0.; 4]);
+    }
+
+    #[test]
+    fn anti_aliases_partly_covered_pixels() {
+        let mut framebuffer = framebuffer(4);
+        let (vertices, indices, polygons) = square(0.5, 0.5, 3., RED);
+        framebuffer.fill(&vertices, &indices, &polygons);
+
+        assert_close(framebuffer.get_pixel(0, 2), [0.5, 0., 0., 0.5]);
+        assert_close(framebuffer.get_pixel(0, 0), [0.25, 0., 0., 0.25]);
+        assert_close(framebuffer.get_pixel(2, 2), RED);
+    }
+
+    #[test]
+    fn blends_translucent_polygons() {
+        let mut framebuffer = Framebuffer::new(
+            2,
+            2,
+            &GUISize::from_pixels(2., 2.),
+            &GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 1.,
+                a: 1.,
+            },
+        );
+        let (vertices, indices, polygons) = square(0., 0., 2., [1., 0., 0., 0.5]);
+        framebuffer.fill(&vertices, &indices, &polygons);
+
+        assert_close(framebuffer.get_pixel(1, 1), [0.5, 0., 0.5, 1.]);
+        assert_eq!(&framebuffer.to_rgba8()[..4], &[188, 0, 188, 255]);
+    }
+
+    #[test]
+    fn stretches_the_logical_size() {
+        let mut framebuffer = Framebuffer::new(8, 8, &GUISize::from_pixels(4., 4.), &CLEAR);
+        let (vertices, indices, polygons) = square(2., 2., 2., RED);
+        framebuffer.fill(&vertices, &indices, &polygons);
+
+        assert_close(framebuffer.get_pixel(3, 3), [0.; 4]);
+        assert_close(framebuffer.get_pixel(4, 4), RED);
+        assert_close(framebuffer.get_pixel(7, 7), RED);
+    }
+
+    #[test]
+    fn renders_widgets_and_text() {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        guibase.add_child_to_parent(
+            GUIButton {
+                position: GUIPosition::from_pixels(100., 100.),
+                ..Default::default()
+            },
+            window_id,
+        );
+        guibase.add_child_to_parent(
+            GUILabel {
+                text: String::from("Hello"),
+                text_color: GUIColor {
+                    r: 1.,
+                    g: 1.,
+                    b: 1.,
+                    a: 1.,
+                },
+                ..Default::default()
+            },
+            window_id,
+        );
+        let size = guibase.get_base_window().size;
+        let mut atlas = GlyphAtlas::default();
+        let framebuffer = render_window(&guibase, window_id, &mut atlas, 500, 500, &size);
+
+        let button = framebuffer.get_pixel(150, 150);
+        assert_close(button, [0.7, 0.1, 0.4, 1.]);
+        let background = guibase.get_base_window().background_color.to_f32_array();
+        // The label's text is lighter than the background somewhere.
+        let text_drawn =
+            (0..30).any(|y| (0..60).any(|x| framebuffer.get_pixel(x, y)[1] > background[1] + 0.1));
+        assert!(text_drawn);
+    }
+}
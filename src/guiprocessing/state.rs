@@ -1,211 +1,293 @@
+use std::collections::HashMap;
 use std::iter;
 
-use wgpu::{Buffer, Device};
-use wgpu::util::DeviceExt;
-use winit::event::{ElementState, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
-use winit::window::Window;
+use winit::event::{
+    ElementState, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
+use winit::window::{Window, WindowId};
 
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent, GUIModifiers};
+use crate::guiproperties::guipointer::GUIPointerEventKind;
 use crate::guiproperties::guiposition::{GUIPosition, GUISize};
-use crate::guiresources::GUIResources;
+use crate::guiresources::GUIRasterizer;
 use crate::guiwidgets::GUIBase;
 
 use crate::guiprocessing::processing_utils;
-use crate::guiprocessing::vertices::LogicalVertex;
 
-use super::vertices::{Polygon, Vertex};
+use super::blit::WindowBlitter;
+use super::renderer::{FrameTexture, Renderer, WindowPrimitives};
+use super::software::Framebuffer;
+use super::tessellation::WindowGeometry;
 
-pub struct State {
-    surface: wgpu::Surface,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    config: wgpu::SurfaceConfiguration,
-
-    render_pipeline: wgpu::RenderPipeline,
+/// How far a notch of a mouse wheel scrolls, in logical pixels.
+const SCROLL_LINE_LENGTH: f64 = 40.;
 
-    vertex_buffer: wgpu::Buffer,
-    index_buffer: wgpu::Buffer,
-    num_indices: u32,
+const SURFACE_WITHOUT_GPU: &str = "surfaces are only made when there's a gpu";
 
-    pub size: winit::dpi::PhysicalSize<u32>,
+/// Where a window's frames are shown.
+pub enum WindowOutput {
+    /// A surface that's drawn to with wgpu, by either rasterizer.
+    /// It has to have been made from the window with the renderer's instance.
+    Surface(wgpu::Surface),
+    /// The window itself, which the software rasterizer's frames are copied onto without wgpu.
+    Blit(WindowBlitter),
+}
 
-    pub guibase: GUIBase,
+/// A window's output, along with what's kept to draw to it.
+enum Output {
+    Surface {
+        surface: wgpu::Surface,
+        config: wgpu::SurfaceConfiguration,
+        primitives: Box<WindowPrimitives>,
+        /// The software rasterizer's last frame on the gpu.
+        frame: Option<FrameTexture>,
+    },
+    Blit(WindowBlitter),
+}
 
-    logical_vertices: Vec<LogicalVertex>,
-    polygons: Vec<Polygon>,
+/// Everything that's needed to draw and interact with a single window.
+pub struct State {
+    pub window: Window,
+    /// The id of the GUIWindow that this window shows.
+    pub window_id: u128,
+    output: Output,
+    /// The width and height of the window in physical pixels.
+    physical_size: (u32, u32),
+    /// The software rasterizer's last frame,
+    /// which is None when the window has changed and has to be drawn again.
+    framebuffer: Option<Framebuffer>,
+
+    /// The logical size that's stretched over the window.
+    /// It follows the window when the scale is fixed and stays put when it isn't.
+    content_size: GUISize,
+    /// Whether the buffers need to be written even if no widgets have changed.
+    content_resized: bool,
+    /// The window's widgets joined together, which are drawn and hit tested.
+    geometry: WindowGeometry,
     pub curser_position: GUIPosition,
+    /// The widget that the left mouse button was pressed on, if it hasn't been released yet.
     clicked_widget_id: Option<u128>,
+    /// The widget that's under the cursor.
+    hovered_widget_id: Option<u128>,
+    /// The modifier keys that are held down while the window has focus.
+    modifiers: GUIModifiers,
 }
 
 impl State {
-    // pub async fn new(window: &Window, guiwindow: GUIWindow, guiresources: GUIResources) -> Self {
-    pub async fn new(window: &Window, guibase: GUIBase, guiresources: GUIResources) -> Self {
+    /// Sets up a window that shows the GUIWindow with the id window_id on output.
+    pub fn new(
+        window: Window,
+        output: WindowOutput,
+        window_id: u128,
+        guibase: &GUIBase,
+        renderer: &Renderer,
+    ) -> Self {
         let size = window.inner_size();
-        // The instance is a handle to our GPU
-        // BackendBit::PRIMARY => Vulkan + Metal + DX12 + Browser WebGPU
-        let instance = wgpu::Instance::new(guiresources.backend());
-        // The surface is part of the window that's drawn to.
-        let surface = unsafe { instance.create_surface(window) };
-        // The adapter is the handle to the actual graphics card.
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: guiresources.power_preference(),
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::default(),
-                },
-                // Some(&std::path::Path::new("trace")), // Trace path
-                None,
-            )
-            .await
-            .unwrap();
-
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: surface.get_preferred_format(&adapter).unwrap(),
-            width: size.width,
-            height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
+        let output = match output {
+            WindowOutput::Surface(surface) => {
+                let gpu = renderer.gpu.as_ref().expect(SURFACE_WITHOUT_GPU);
+                let config = wgpu::SurfaceConfiguration {
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                    format: gpu.format,
+                    width: size.width,
+                    height: size.height,
+                    present_mode: wgpu::PresentMode::Fifo,
+                };
+                surface.configure(&gpu.device, &config);
+                Output::Surface {
+                    surface,
+                    config,
+                    primitives: Box::new(WindowPrimitives::new(&gpu.device)),
+                    frame: None,
+                }
+            }
+            WindowOutput::Blit(blitter) => Output::Blit(blitter),
         };
-        surface.configure(&device, &config);
-
-        let shader = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/shader.wgsl").into()),
-        });
-
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState {
-                        color: wgpu::BlendComponent::REPLACE,
-                        alpha: wgpu::BlendComponent::REPLACE,
-                    }),
-                    write_mask: wgpu::ColorWrites::ALL,
-                }],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-                // Requires Features::DEPTH_CLIP_CONTROL
-                unclipped_depth: false,
-                // Requires Features::CONSERVATIVE_RASTERIZATION
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            // If the pipeline will be used with a multiview render pass, this
-            // indicates how many array layers the attachments will have.
-            multiview: None,
-        });
-
-        let (logical_vertices, polygons, vertex_buffer, index_buffer, num_indices) = make_wireframe_primitives(&guibase, &device);
 
         Self {
-            surface,
-            device,
-            queue,
-            config,
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            num_indices,
-            size,
-            guibase,
-            logical_vertices,
-            polygons,
+            window,
+            window_id,
+            output,
+            physical_size: (size.width, size.height),
+            framebuffer: None,
+            content_size: guibase.get_window(window_id).size,
+            // The buffers start out empty.
+            content_resized: true,
+            geometry: WindowGeometry::default(),
             curser_position: GUIPosition::default(),
             clicked_widget_id: None,
+            hovered_widget_id: None,
+            modifiers: GUIModifiers::default(),
         }
     }
-    pub fn resize(&mut self, new_size: GUISize) {
-        self.guibase.get_base_window_mut().size = new_size;
-        self.config.width = new_size
-            .width
-            .get_physical_length(&self.guibase.logical_scale.unwrap())
-            .round() as u32;
-        self.config.height = new_size
-            .height
-            .get_physical_length(&self.guibase.logical_scale.unwrap())
-            .round() as u32;
-        self.surface.configure(&self.device, &self.config);
+
+    pub fn resize(&mut self, guibase: &mut GUIBase, renderer: &Renderer, new_size: GUISize) {
+        guibase.get_window_mut(self.window_id).size = new_size;
+        self.physical_size = (
+            new_size
+                .width
+                .get_physical_length(&guibase.logical_scale.unwrap())
+                .round() as u32,
+            new_size
+                .height
+                .get_physical_length(&guibase.logical_scale.unwrap())
+                .round() as u32,
+        );
+        if let Output::Surface {
+            surface, config, ..
+        } = &mut self.output
+        {
+            (config.width, config.height) = self.physical_size;
+            let gpu = renderer.gpu.as_ref().expect(SURFACE_WITHOUT_GPU);
+            surface.configure(&gpu.device, config);
+        }
+        self.framebuffer = None;
+        // When the scale isn't fixed the contents are stretched with the window instead.
+        if guibase.fixed_scale {
+            guibase.layout();
+            self.content_size = new_size;
+            self.content_resized = true;
+        }
+        guibase.request_redraw();
     }
 
-    #[allow(unused_variables)]
-    pub fn input(&mut self, event: &WindowEvent) -> bool {
-        false
+    /// Handles keyboard events, sending keys to the focused widget,
+    /// and scrolling, which goes to the widget under the cursor.
+    /// Returns true if the event was used.
+    pub fn input(&mut self, guibase: &mut GUIBase, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = GUIModifiers {
+                    shift: modifiers.shift(),
+                    ctrl: modifiers.ctrl(),
+                    alt: modifiers.alt(),
+                    logo: modifiers.logo(),
+                };
+                true
+            }
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state,
+                        virtual_keycode: Some(keycode),
+                        ..
+                    },
+                ..
+            } => {
+                let event = GUIKeyEvent {
+                    key: to_gui_key(*keycode),
+                    modifiers: self.modifiers,
+                    pressed: *state == ElementState::Pressed,
+                };
+                guibase.key_input(self.window_id, &event)
+            }
+            WindowEvent::ReceivedCharacter(character) => {
+                guibase.character_input(self.window_id, *character)
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                // Scrolling up moves the content down, which scrolls back towards its top.
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => GUIPosition::from_pixels(
+                        -*x as f64 * SCROLL_LINE_LENGTH,
+                        -*y as f64 * SCROLL_LINE_LENGTH,
+                    ),
+                    MouseScrollDelta::PixelDelta(position) => GUIPosition::from_physical_pixels(
+                        -position.x,
+                        -position.y,
+                        &guibase.logical_scale.unwrap(),
+                    ),
+                };
+                match self.hovered_widget_id {
+                    Some(widget_id) => guibase.scroll_input(widget_id, &delta),
+                    None => false,
+                }
+            }
+            _ => false,
+        }
     }
 
     #[warn(dead_code)]
     pub fn update(&mut self) {}
 
-    pub fn set_curser_position(&mut self, position: GUIPosition) {
+    pub fn set_curser_position(&mut self, guibase: &mut GUIBase, position: GUIPosition) {
         self.curser_position = position;
+
+        let hovered_widget_id = processing_utils::get_clicked_widget(
+            &self.geometry.polygons,
+            &self.geometry.vertices,
+            &self.curser_position,
+        );
+        if hovered_widget_id != self.hovered_widget_id {
+            if let Some(id) = self.hovered_widget_id {
+                set_widget_hovered(guibase, id, false);
+            }
+            if let Some(id) = hovered_widget_id {
+                set_widget_hovered(guibase, id, true);
+            }
+            self.hovered_widget_id = hovered_widget_id;
+        }
+
+        // The widget that the button was pressed on keeps getting the pointer until it's released.
+        if let Some(id) = self.clicked_widget_id {
+            guibase.pointer_input(
+                id,
+                GUIPointerEventKind::Moved,
+                &self.curser_position,
+                self.modifiers,
+            );
+        }
     }
 
-    pub fn mouse_input(&mut self, button: &MouseButton) {
+    pub fn mouse_input(
+        &mut self,
+        guibase: &mut GUIBase,
+        state: &ElementState,
+        button: &MouseButton,
+    ) {
         use MouseButton::*;
 
         match button {
             Left => {
                 let clicked_widget_id = processing_utils::get_clicked_widget(
-                    &self.polygons,
-                    &self.logical_vertices,
+                    &self.geometry.polygons,
+                    &self.geometry.vertices,
                     &self.curser_position,
-                );
-                match self.clicked_widget_id {
-                    Some(last_id) => match clicked_widget_id {
-                        Some(this_id) => {
-                            if last_id == this_id {
-                                println!("You clicked widget id {last_id}");
-                            }
-                            self.clicked_widget_id = None;
-                        }
-                        None => {
-                            self.clicked_widget_id = None;
+                )
+                .filter(|id| match guibase.widgets.get(id) {
+                    Some(gwidget) => gwidget.get_widget().is_enabled(),
+                    None => false,
+                });
+                match state {
+                    ElementState::Pressed => {
+                        // Clicking away from a focusable widget takes the focus away.
+                        let focus_id =
+                            clicked_widget_id.filter(|id| guibase.get_widget(*id).is_focusable());
+                        guibase.set_focus(focus_id);
+                        if let Some(this_id) = clicked_widget_id {
+                            set_widget_pressed(guibase, this_id, true);
+                            guibase.pointer_input(
+                                this_id,
+                                GUIPointerEventKind::Pressed,
+                                &self.curser_position,
+                                self.modifiers,
+                            );
                         }
-                    },
-                    None => match clicked_widget_id {
-                        Some(_) => {
-                            self.clicked_widget_id = clicked_widget_id;
-                        }
-                        None => {
-                            self.clicked_widget_id = None;
+                        self.clicked_widget_id = clicked_widget_id;
+                    }
+                    ElementState::Released => {
+                        if let Some(last_id) = self.clicked_widget_id.take() {
+                            set_widget_pressed(guibase, last_id, false);
+                            guibase.pointer_input(
+                                last_id,
+                                GUIPointerEventKind::Released,
+                                &self.curser_position,
+                                self.modifiers,
+                            );
+                            if clicked_widget_id == Some(last_id) && guibase.click(last_id) {
+                                guibase.layout();
+                            }
                         }
-                    },
+                    }
                 }
             }
             Right => {
@@ -220,86 +302,175 @@ impl State {
         }
     }
 
-    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
-        let output = self.surface.get_current_texture()?;
+    pub fn render(
+        &mut self,
+        guibase: &GUIBase,
+        renderer: &mut Renderer,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let guiwindow = guibase.get_window(self.window_id);
+        // The software rasterizer only draws the window again once it has changed.
+        let redrawn = renderer.rasterizer == GUIRasterizer::Software && self.framebuffer.is_none();
+        if redrawn {
+            let mut framebuffer = Framebuffer::new(
+                self.physical_size.0,
+                self.physical_size.1,
+                &self.content_size,
+                &guiwindow.background_color,
+            );
+            framebuffer.fill(
+                &self.geometry.vertices,
+                &self.geometry.indices,
+                &self.geometry.polygons,
+            );
+            framebuffer.draw_text(&self.geometry.text_vertices, &renderer.glyph_atlas);
+            self.framebuffer = Some(framebuffer);
+        }
+
+        let (surface, config, primitives, frame) = match &mut self.output {
+            Output::Surface {
+                surface,
+                config,
+                primitives,
+                frame,
+            } => (surface, config, primitives, frame),
+            Output::Blit(blitter) => {
+                if let Some(framebuffer) = &self.framebuffer {
+                    blitter.present(framebuffer);
+                }
+                return Ok(());
+            }
+        };
+        if renderer.rasterizer == GUIRasterizer::Gpu {
+            renderer.upload_glyph_atlas();
+        }
+        let gpu = renderer.gpu.as_ref().expect(SURFACE_WITHOUT_GPU);
+
+        let output = surface.get_current_texture()?;
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        let mut encoder = self
+        let mut encoder = gpu
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                 label: Some("Render Encoder"),
             });
 
-        if self.guibase.fixed_scale {
-            let (logical_vertices, polygons, vertex_buffer, index_buffer, num_indices) = make_wireframe_primitives(&self.guibase, &self.device);
-            self.logical_vertices = logical_vertices;
-            self.polygons = polygons;
-            self.vertex_buffer = vertex_buffer;
-            self.index_buffer = index_buffer;
-            self.num_indices = num_indices;
+        match renderer.rasterizer {
+            GUIRasterizer::Gpu => {
+                gpu.draw(
+                    &mut encoder,
+                    &view,
+                    (config.width, config.height),
+                    &guiwindow.background_color,
+                    primitives,
+                );
+            }
+            GUIRasterizer::Software => {
+                if let (true, Some(framebuffer)) = (redrawn, &self.framebuffer) {
+                    gpu.write_frame(frame, framebuffer);
+                }
+                if let Some(frame) = frame {
+                    gpu.draw_image(&mut encoder, &view, frame);
+                }
+            }
         }
 
-        {
-            let guiwindow = self.guibase.get_base_window();
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: guiwindow.background_color.r,
-                            g: guiwindow.background_color.g,
-                            b: guiwindow.background_color.b,
-                            a: guiwindow.background_color.a,
-                        }),
-                        store: true,
-                    },
-                }],
-                depth_stencil_attachment: None,
-            });
+        gpu.queue.submit(iter::once(encoder.finish()));
+        output.present();
+
+        Ok(())
+    }
 
-            render_pass.set_pipeline(&self.render_pipeline);
+    /// Brings the window's geometry up to date and writes the parts that changed
+    /// into its buffers. The software rasterizer draws straight from the geometry,
+    /// the next time that the window is rendered.
+    fn write_primitives(&mut self, guibase: &GUIBase, renderer: &mut Renderer) {
+        let changes = renderer.tessellation.assemble(
+            guibase,
+            self.window_id,
+            &mut renderer.glyph_atlas,
+            &mut self.geometry,
+        );
+        if let (GUIRasterizer::Gpu, Some(gpu), Output::Surface { primitives, .. }) =
+            (renderer.rasterizer, &renderer.gpu, &mut self.output)
+        {
+            primitives.write(
+                gpu,
+                &renderer.glyph_atlas,
+                &self.geometry,
+                changes.as_ref(),
+                &self.content_size,
+            );
+        }
+        self.framebuffer = None;
+        self.content_resized = false;
+    }
+}
 
-            let data = (&self.vertex_buffer, &self.index_buffer, self.num_indices);
-            render_pass.set_vertex_buffer(0, data.0.slice(..));
-            render_pass.set_index_buffer(data.1.slice(..), wgpu::IndexFormat::Uint16);
+/// Re-tessellates the widgets that have changed and writes every window's buffers.
+/// Returns false, without doing anything, if nothing has changed.
+pub fn update_primitives(
+    guibase: &mut GUIBase,
+    renderer: &mut Renderer,
+    states: &mut HashMap<WindowId, State>,
+) -> bool {
+    let changed = renderer.tessellation.update(guibase);
+    if !changed && !states.values().any(|state| state.content_resized) {
+        return false;
+    }
 
-            render_pass.draw_indexed(0..data.2, 0, 0..1);
+    let atlas_generation = renderer.glyph_atlas.generation();
+    for state in states.values_mut() {
+        state.write_primitives(guibase, renderer);
+    }
+    // The atlas grew or was cleared part way through, so the windows that were written
+    // before it was have text coordinates for glyphs that have since moved.
+    if atlas_generation != renderer.glyph_atlas.generation() {
+        for state in states.values_mut() {
+            state.write_primitives(guibase, renderer);
         }
+    }
 
-        self.queue.submit(iter::once(encoder.finish()));
-        output.present();
+    true
+}
 
-        Ok(())
+fn to_gui_key(keycode: VirtualKeyCode) -> GUIKey {
+    use VirtualKeyCode::*;
+
+    match keycode {
+        Tab => GUIKey::Tab,
+        Return | NumpadEnter => GUIKey::Enter,
+        Space => GUIKey::Space,
+        Escape => GUIKey::Escape,
+        Back => GUIKey::Backspace,
+        Delete => GUIKey::Delete,
+        Left => GUIKey::Left,
+        Right => GUIKey::Right,
+        Up => GUIKey::Up,
+        Down => GUIKey::Down,
+        Home => GUIKey::Home,
+        End => GUIKey::End,
+        PageUp => GUIKey::PageUp,
+        PageDown => GUIKey::PageDown,
+        // The letters are declared together and in order, from A to Z.
+        _ => match (keycode as u32).checked_sub(A as u32) {
+            Some(offset) if offset < 26 => GUIKey::Letter((b'a' + offset as u8) as char),
+            _ => GUIKey::Other,
+        },
     }
 }
 
-fn make_wireframe_primitives(guibase: &GUIBase, device: &Device) -> (Vec<LogicalVertex>, Vec<Polygon>, Buffer, Buffer, u32) {
-    let (logical_vertices, indices, polygons) =
-        processing_utils::make_vertices_and_indices(&guibase);
-    let width = guibase.get_base_window().size.width.get_length() as f32;
-    let height = guibase.get_base_window().size.height.get_length() as f32;
-    let vertices: Vec<Vertex> = logical_vertices
-        .iter()
-        .map(|v| v.to_vertex(width, height))
-        .collect();
-    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Vertex Buffer"),
-        // contents: bytemuck::cast_slice(VERTICES),
-        contents: bytemuck::cast_slice(&vertices[..]),
-        usage: wgpu::BufferUsages::VERTEX,
-    });
-    let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-        label: Some("Index Buffer"),
-        // contents: bytemuck::cast_slice(INDICES),
-        contents: bytemuck::cast_slice(&indices[..]),
-        usage: wgpu::BufferUsages::INDEX,
-    });
-    // let num_indices = INDICES.len() as u32;
-    let num_indices = indices.len() as u32;
-
-    (logical_vertices, polygons, vertex_buffer, index_buffer, num_indices)
+fn set_widget_hovered(guibase: &mut GUIBase, widget_id: u128, hovered: bool) {
+    if let Some(gwidget) = guibase.widgets.get_mut(&widget_id) {
+        gwidget.get_widget_mut().set_hovered(hovered);
+        guibase.mark_dirty(widget_id);
+    }
+}
+
+fn set_widget_pressed(guibase: &mut GUIBase, widget_id: u128, pressed: bool) {
+    if let Some(gwidget) = guibase.widgets.get_mut(&widget_id) {
+        gwidget.get_widget_mut().set_pressed(pressed);
+        guibase.mark_dirty(widget_id);
+    }
 }
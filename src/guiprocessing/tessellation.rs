@@ -0,0 +1,622 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+use super::processing_utils::{
+    absolute_position, children_clip, children_position, push_focus_ring, to_index,
+    INDEX_OVERFLOW,
+};
+use super::text::{self, GlyphAtlas, GlyphQuad};
+use super::vertices::{ClipRect, LogicalTextVertex, LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::GUIPosition;
+use crate::guiproperties::guitraits::Widget;
+use crate::guiwidgets::GUIBase;
+
+/// The shapes and laid out text of a single widget.
+/// The indices and polygon are relative to the widget's first vertex.
+struct WidgetGeometry {
+    vertices: Vec<LogicalVertex>,
+    indices: Vec<u32>,
+    polygon: Polygon,
+    glyphs: Vec<GlyphQuad>,
+    /// The area that the widget's parents clip it to.
+    clip: Option<ClipRect>,
+}
+
+/// Keeps the tessellated shapes and text of every widget between frames,
+/// so that only the widgets that have changed are tessellated again.
+#[derive(Default)]
+pub struct TessellationCache {
+    widgets: HashMap<u128, WidgetGeometry>,
+    /// The widgets that were tessellated by the last call to tessellate.
+    changed: HashSet<u128>,
+    /// The logical scale that the text was laid out at.
+    scale: Option<f64>,
+}
+
+impl TessellationCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tessellates the widgets that have changed since the last update.
+    /// Returns false if nothing has changed, in which case the assembled
+    /// vertices and indices would be the same as last time.
+    pub fn update(&mut self, guibase: &mut GUIBase) -> bool {
+        let scale = guibase.logical_scale.unwrap_or(1.);
+        let rescaled = self.scale != Some(scale);
+        let dirty_widgets = match guibase.take_dirty_widgets() {
+            Some(dirty_widgets) => dirty_widgets,
+            None if rescaled => HashSet::new(),
+            None => return false,
+        };
+        if rescaled {
+            // Text is snapped to physical pixels, so it all has to be laid out again.
+            self.widgets.clear();
+            self.scale = Some(scale);
+        }
+        self.tessellate(guibase, &dirty_widgets);
+
+        true
+    }
+
+    /// Tessellates the given widgets along with any that haven't been tessellated yet,
+    /// and forgets widgets that are no longer in the tree.
+    /// Returns the number of widgets that were tessellated.
+    pub fn tessellate(&mut self, guibase: &GUIBase, dirty_widgets: &HashSet<u128>) -> usize {
+        self.widgets
+            .retain(|widget_id, _| guibase.widgets.contains_key(widget_id));
+        self.changed.clear();
+
+        let mut count = 0;
+        for gwindow in guibase.windows.values() {
+            for child_id in gwindow.get_child_ids() {
+                self.tessellate_child(
+                    guibase,
+                    gwindow.get_window().get_position(),
+                    child_id,
+                    None,
+                    dirty_widgets,
+                    &mut count,
+                );
+            }
+        }
+        count
+    }
+
+    fn tessellate_child(
+        &mut self,
+        guibase: &GUIBase,
+        parent_position: &GUIPosition,
+        widget_id: &u128,
+        clip: Option<ClipRect>,
+        dirty_widgets: &HashSet<u128>,
+        count: &mut usize,
+    ) {
+        let gwidget = guibase.widgets.get(widget_id).unwrap();
+        let widget = gwidget.get_widget();
+        if dirty_widgets.contains(widget_id) || !self.widgets.contains_key(widget_id) {
+            let (vertices, indices, polygon) = widget.get_vertices_and_indices(parent_position, 0);
+            let scale = self.scale.unwrap_or(1.);
+            let glyphs = widget
+                .get_text(parent_position)
+                .iter()
+                .flat_map(|text| text::layout_text(&guibase.font, text, scale))
+                .collect();
+            self.widgets.insert(
+                *widget_id,
+                WidgetGeometry {
+                    vertices,
+                    indices,
+                    polygon,
+                    glyphs,
+                    clip,
+                },
+            );
+            self.changed.insert(*widget_id);
+            *count += 1;
+        }
+
+        let position = absolute_position(parent_position, widget.get_position());
+        let child_clip = children_clip(&position, widget.as_ref(), clip);
+        let position = children_position(&position, widget.as_ref());
+        for child_id in gwidget.get_child_ids() {
+            self.tessellate_child(guibase, &position, child_id, child_clip, dirty_widgets, count);
+        }
+    }
+
+    /// Joins the shapes of a window's widgets together in the order that they're drawn,
+    /// the same as processing_utils::make_vertices_and_indices.
+    pub fn vertices_and_indices(
+        &self,
+        guibase: &GUIBase,
+        window_id: u128,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Vec<Polygon>) {
+        let mut all_vertices: Vec<LogicalVertex> = Vec::new();
+        let mut all_indices: Vec<u32> = Vec::new();
+        let mut all_polygons: Vec<Polygon> = Vec::new();
+
+        for widget_id in guibase.get_widget_ids_in_order(window_id) {
+            let geometry = &self.widgets[&widget_id];
+            let offset = to_index(all_vertices.len());
+            all_indices.extend(
+                geometry
+                    .indices
+                    .iter()
+                    .map(|index| index.checked_add(offset).expect(INDEX_OVERFLOW)),
+            );
+            all_polygons.push(Polygon {
+                start_index: geometry.polygon.start_index + all_vertices.len(),
+                end_index: geometry.polygon.end_index + all_vertices.len(),
+                clip: geometry.clip,
+                ..geometry.polygon
+            });
+            all_vertices.extend(geometry.vertices.iter().copied());
+        }
+        push_focus_ring(
+            guibase,
+            window_id,
+            &mut all_vertices,
+            &mut all_indices,
+            &mut all_polygons,
+        );
+
+        (all_vertices, all_indices, all_polygons)
+    }
+
+    /// Joins the text of a window's widgets together in the order that it's drawn,
+    /// adding any glyphs that are missing from the atlas.
+    pub fn text_vertices_and_indices(
+        &self,
+        guibase: &GUIBase,
+        window_id: u128,
+        atlas: &mut GlyphAtlas,
+    ) -> (Vec<LogicalTextVertex>, Vec<u32>) {
+        let mut all_vertices: Vec<LogicalTextVertex> = Vec::new();
+        let mut all_indices: Vec<u32> = Vec::new();
+
+        for widget_id in guibase.get_widget_ids_in_order(window_id) {
+            let geometry = &self.widgets[&widget_id];
+            let (vertices, indices) = text::make_glyph_vertices_and_indices(
+                &geometry.glyphs,
+                &guibase.font,
+                atlas,
+                to_index(all_vertices.len()),
+                geometry.clip.as_ref(),
+            );
+            all_vertices.extend(vertices);
+            all_indices.extend(indices);
+        }
+
+        (all_vertices, all_indices)
+    }
+
+    /// Brings a window's joined shapes and text up to date with the tree.
+    /// If the window has the same widgets in the same order as last time, and the ones
+    /// that have been tessellated since take up as much room as they did, only their parts
+    /// and the focus ring are written over and the parts that were are returned.
+    /// Otherwise everything is joined together again and None is returned.
+    pub fn assemble(
+        &self,
+        guibase: &GUIBase,
+        window_id: u128,
+        atlas: &mut GlyphAtlas,
+        geometry: &mut WindowGeometry,
+    ) -> Option<GeometryChanges> {
+        let widget_ids = guibase.get_widget_ids_in_order(window_id);
+        let generation = atlas.generation();
+        let same_widgets = geometry.widgets.len() == widget_ids.len()
+            && geometry
+                .widgets
+                .iter()
+                .zip(widget_ids.iter())
+                .all(|((widget_id, _), other_id)| widget_id == other_id);
+        if geometry.atlas_generation != Some(generation) || !same_widgets {
+            self.assemble_all(guibase, window_id, &widget_ids, atlas, geometry);
+            return None;
+        }
+
+        let mut changes = GeometryChanges::default();
+        for (position, (widget_id, parts)) in geometry.widgets.iter().enumerate() {
+            if !self.changed.contains(widget_id) {
+                continue;
+            }
+            let widget = &self.widgets[widget_id];
+            let (text_vertices, text_indices) = text::make_glyph_vertices_and_indices(
+                &widget.glyphs,
+                &guibase.font,
+                atlas,
+                to_index(parts.text_vertices.start),
+                widget.clip.as_ref(),
+            );
+            // Glyphs that have already been written have moved if the atlas grew.
+            if widget.vertices.len() != parts.vertices.len()
+                || widget.indices.len() != parts.indices.len()
+                || text_vertices.len() != parts.text_vertices.len()
+                || text_indices.len() != parts.text_indices.len()
+                || atlas.generation() != generation
+            {
+                self.assemble_all(guibase, window_id, &widget_ids, atlas, geometry);
+                return None;
+            }
+
+            let offset = to_index(parts.vertices.start);
+            geometry.vertices[parts.vertices.clone()].copy_from_slice(&widget.vertices);
+            for (index, relative) in geometry.indices[parts.indices.clone()]
+                .iter_mut()
+                .zip(widget.indices.iter())
+            {
+                *index = relative.checked_add(offset).expect(INDEX_OVERFLOW);
+            }
+            let polygon = &mut geometry.polygons[position];
+            changes.clips_changed |= polygon.clip != widget.clip;
+            *polygon = Polygon {
+                start_index: widget.polygon.start_index + parts.vertices.start,
+                end_index: widget.polygon.end_index + parts.vertices.start,
+                clip: widget.clip,
+                ..widget.polygon
+            };
+            geometry.text_vertices[parts.text_vertices.clone()].copy_from_slice(&text_vertices);
+            geometry.text_indices[parts.text_indices.clone()].copy_from_slice(&text_indices);
+
+            changes.vertices.push(parts.vertices.clone());
+            changes.indices.push(parts.indices.clone());
+            changes.text_vertices.push(parts.text_vertices.clone());
+            changes.text_indices.push(parts.text_indices.clone());
+        }
+
+        // The focus ring comes after every widget, so it can grow or shrink in place.
+        let ring_vertices = geometry.vertices.len() - geometry.ring_start.0;
+        let ring_clips: Vec<Option<ClipRect>> = geometry.polygons[geometry.widgets.len()..]
+            .iter()
+            .map(|polygon| polygon.clip)
+            .collect();
+        geometry.vertices.truncate(geometry.ring_start.0);
+        geometry.indices.truncate(geometry.ring_start.1);
+        geometry.polygons.truncate(geometry.widgets.len());
+        push_focus_ring(
+            guibase,
+            window_id,
+            &mut geometry.vertices,
+            &mut geometry.indices,
+            &mut geometry.polygons,
+        );
+        changes.clips_changed |= ring_vertices != geometry.vertices.len() - geometry.ring_start.0
+            || geometry.polygons[geometry.widgets.len()..]
+                .iter()
+                .map(|polygon| polygon.clip)
+                .ne(ring_clips);
+        changes
+            .vertices
+            .push(geometry.ring_start.0..geometry.vertices.len());
+        changes
+            .indices
+            .push(geometry.ring_start.1..geometry.indices.len());
+
+        Some(changes)
+    }
+
+    /// Joins every widget of a window together, remembering where each one's parts are.
+    fn assemble_all(
+        &self,
+        guibase: &GUIBase,
+        window_id: u128,
+        widget_ids: &[u128],
+        atlas: &mut GlyphAtlas,
+        geometry: &mut WindowGeometry,
+    ) {
+        let mut all = WindowGeometry {
+            atlas_generation: Some(atlas.generation()),
+            ..Default::default()
+        };
+
+        for widget_id in widget_ids {
+            let widget = &self.widgets[widget_id];
+            let vertex_start = all.vertices.len();
+            let offset = to_index(vertex_start);
+            let index_start = all.indices.len();
+            all.indices.extend(
+                widget
+                    .indices
+                    .iter()
+                    .map(|index| index.checked_add(offset).expect(INDEX_OVERFLOW)),
+            );
+            all.polygons.push(Polygon {
+                start_index: widget.polygon.start_index + vertex_start,
+                end_index: widget.polygon.end_index + vertex_start,
+                clip: widget.clip,
+                ..widget.polygon
+            });
+            all.vertices.extend(widget.vertices.iter().copied());
+
+            let (text_vertices, text_indices) = text::make_glyph_vertices_and_indices(
+                &widget.glyphs,
+                &guibase.font,
+                atlas,
+                to_index(all.text_vertices.len()),
+                widget.clip.as_ref(),
+            );
+            let text_vertex_start = all.text_vertices.len();
+            let text_index_start = all.text_indices.len();
+            all.text_vertices.extend(text_vertices);
+            all.text_indices.extend(text_indices);
+
+            all.widgets.push((
+                *widget_id,
+                WidgetParts {
+                    vertices: vertex_start..all.vertices.len(),
+                    indices: index_start..all.indices.len(),
+                    text_vertices: text_vertex_start..all.text_vertices.len(),
+                    text_indices: text_index_start..all.text_indices.len(),
+                },
+            ));
+        }
+        all.ring_start = (all.vertices.len(), all.indices.len());
+        push_focus_ring(
+            guibase,
+            window_id,
+            &mut all.vertices,
+            &mut all.indices,
+            &mut all.polygons,
+        );
+
+        *geometry = all;
+    }
+}
+
+/// Where a widget's shapes and text are within its window's joined geometry.
+struct WidgetParts {
+    vertices: Range<usize>,
+    indices: Range<usize>,
+    text_vertices: Range<usize>,
+    text_indices: Range<usize>,
+}
+
+/// The shapes and text of a window's widgets joined together in the order that they're drawn,
+/// kept between frames so that the widgets that change can be written over in place.
+#[derive(Default)]
+pub struct WindowGeometry {
+    pub vertices: Vec<LogicalVertex>,
+    pub indices: Vec<u32>,
+    pub polygons: Vec<Polygon>,
+    pub text_vertices: Vec<LogicalTextVertex>,
+    pub text_indices: Vec<u32>,
+    widgets: Vec<(u128, WidgetParts)>,
+    /// The number of vertices and indices before the focus ring.
+    ring_start: (usize, usize),
+    /// The generation of the atlas that the text vertices point into.
+    atlas_generation: Option<u32>,
+}
+
+/// The ranges of a window's joined geometry that were written over by assemble.
+#[derive(Default, Debug)]
+pub struct GeometryChanges {
+    pub vertices: Vec<Range<usize>>,
+    pub indices: Vec<Range<usize>>,
+    pub text_vertices: Vec<Range<usize>>,
+    pub text_indices: Vec<Range<usize>>,
+    /// Whether any of the shapes are clipped differently, in which case
+    /// the runs of indices that share a clip rect have to be found again.
+    pub clips_changed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiprocessing::processing_utils;
+    use crate::guiwidgets::{GUIButton, GUILabel, GUIWindow};
+
+    fn make_guibase() -> (GUIBase, u128, u128) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let parent_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        guibase.add_child_to_parent(GUIButton::default(), parent_id);
+        guibase.add_child_to_parent(GUILabel::new("Hello"), parent_id);
+        let other_id = guibase.add_child_to_parent(
+            GUIButton {
+                position: GUIPosition::from_pixels(0., 200.),
+                ..Default::default()
+            },
+            window_id,
+        );
+        (guibase, parent_id, other_id)
+    }
+
+    fn positions(vertices: &[LogicalVertex]) -> Vec<[f32; 3]> {
+        vertices.iter().map(|vertex| vertex.position).collect()
+    }
+
+    #[test]
+    fn only_dirty_widgets_are_tessellated() {
+        let (mut guibase, parent_id, other_id) = make_guibase();
+        let mut cache = TessellationCache::new();
+        let dirty = guibase.take_dirty_widgets().unwrap();
+        assert_eq!(cache.tessellate(&guibase, &dirty), 4);
+        assert!(guibase.take_dirty_widgets().is_none());
+
+        guibase
+            .get_widget_as_mut::<GUIButton>(other_id)
+            .unwrap()
+            .enabled = false;
+        let dirty = guibase.take_dirty_widgets().unwrap();
+        assert_eq!(cache.tessellate(&guibase, &dirty), 1);
+
+        // Moving a parent moves its children too.
+        guibase
+            .get_widget_mut(parent_id)
+            .set_position(GUIPosition::from_pixels(10., 10.));
+        let dirty = guibase.take_dirty_widgets().unwrap();
+        assert_eq!(cache.tessellate(&guibase, &dirty), 3);
+    }
+
+    #[test]
+    fn update_skips_unchanged_trees() {
+        let (mut guibase, _, _) = make_guibase();
+        let mut cache = TessellationCache::new();
+        assert!(cache.update(&mut guibase));
+        assert!(!cache.update(&mut guibase));
+
+        guibase.logical_scale = Some(2.);
+        assert!(cache.update(&mut guibase));
+        assert!(!cache.update(&mut guibase));
+    }
+
+    #[test]
+    fn assembles_the_same_as_a_full_rebuild() {
+        let (mut guibase, parent_id, other_id) = make_guibase();
+        let mut cache = TessellationCache::new();
+        cache.update(&mut guibase);
+        guibase
+            .get_widget_mut(parent_id)
+            .set_position(GUIPosition::from_pixels(30., 40.));
+        guibase.remove_widget(other_id);
+        cache.update(&mut guibase);
+
+        let (vertices, indices, polygons) =
+            cache.vertices_and_indices(&guibase, guibase.base_window);
+        let (expected_vertices, expected_indices, expected_polygons) =
+            processing_utils::make_vertices_and_indices(&guibase, guibase.base_window);
+        assert_eq!(positions(&vertices), positions(&expected_vertices));
+        assert_eq!(indices, expected_indices);
+        for (polygon, expected) in polygons.iter().zip(expected_polygons.iter()) {
+            assert_eq!(polygon.start_index, expected.start_index);
+            assert_eq!(polygon.end_index, expected.end_index);
+            assert_eq!(polygon.widget_id, expected.widget_id);
+        }
+        assert_eq!(polygons.len(), expected_polygons.len());
+
+        let mut atlas = GlyphAtlas::default();
+        let (text_vertices, text_indices) =
+            cache.text_vertices_and_indices(&guibase, guibase.base_window, &mut atlas);
+        let mut expected_atlas = GlyphAtlas::default();
+        let (expected_text_vertices, expected_text_indices) =
+            processing_utils::make_text_vertices_and_indices(
+                &guibase,
+                guibase.base_window,
+                &mut expected_atlas,
+            );
+        assert_eq!(text_vertices.len(), expected_text_vertices.len());
+        assert_eq!(text_indices, expected_text_indices);
+    }
+
+    #[test]
+    fn assemble_only_writes_over_changed_widgets() {
+        let (mut guibase, parent_id, other_id) = make_guibase();
+        let window_id = guibase.base_window;
+        let mut cache = TessellationCache::new();
+        let mut atlas = GlyphAtlas::default();
+        let mut geometry = WindowGeometry::default();
+        cache.update(&mut guibase);
+        assert!(cache
+            .assemble(&guibase, window_id, &mut atlas, &mut geometry)
+            .is_none());
+
+        guibase
+            .get_widget_as_mut::<GUIButton>(other_id)
+            .unwrap()
+            .enabled = false;
+        cache.update(&mut guibase);
+        let changes = cache
+            .assemble(&guibase, window_id, &mut atlas, &mut geometry)
+            .unwrap();
+        let polygon = geometry
+            .polygons
+            .iter()
+            .find(|polygon| polygon.widget_id == other_id)
+            .unwrap();
+        // The button and then the empty focus ring.
+        assert_eq!(
+            changes.vertices,
+            vec![
+                polygon.start_index..polygon.end_index,
+                geometry.vertices.len()..geometry.vertices.len()
+            ]
+        );
+        assert!(!changes.clips_changed);
+
+        let mut expected = WindowGeometry::default();
+        cache.assemble(&guibase, window_id, &mut atlas, &mut expected);
+        let colors = |vertices: &[LogicalVertex]| -> Vec<[f32; 4]> {
+            vertices.iter().map(|vertex| vertex.color).collect()
+        };
+        assert_eq!(positions(&geometry.vertices), positions(&expected.vertices));
+        assert_eq!(colors(&geometry.vertices), colors(&expected.vertices));
+        assert_eq!(geometry.indices, expected.indices);
+        assert_eq!(geometry.text_indices, expected.text_indices);
+
+        // Focusing a widget only adds the ring after the others.
+        guibase.set_focus(Some(parent_id));
+        cache.update(&mut guibase);
+        let changes = cache
+            .assemble(&guibase, window_id, &mut atlas, &mut geometry)
+            .unwrap();
+        assert!(changes.clips_changed);
+        let full = processing_utils::make_vertices_and_indices(&guibase, window_id);
+        assert_eq!(positions(&geometry.vertices), positions(&full.0));
+        assert_eq!(geometry.indices, full.1);
+
+        guibase.remove_widget(other_id);
+        cache.update(&mut guibase);
+        assert!(cache
+            .assemble(&guibase, window_id, &mut atlas, &mut geometry)
+            .is_none());
+    }
+
+    #[test]
+    fn layout_only_dirties_widgets_that_move() {
+        let (mut guibase, _, _) = make_guibase();
+        guibase.layout();
+        guibase.take_dirty_widgets();
+        guibase.layout();
+        assert!(guibase.take_dirty_widgets().is_none());
+    }
+
+    #[test]
+    fn windows_are_assembled_separately() {
+        let (mut guibase, _, _) = make_guibase();
+        let palette_id = guibase.add_window(GUIWindow::default());
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), palette_id);
+        let mut cache = TessellationCache::new();
+        cache.update(&mut guibase);
+
+        let (_, _, base_polygons) = cache.vertices_and_indices(&guibase, guibase.base_window);
+        let (vertices, _, polygons) = cache.vertices_and_indices(&guibase, palette_id);
+        assert_eq!(base_polygons.len(), 4);
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].widget_id, button_id);
+        assert_eq!(polygons[0].start_index, 0);
+        assert_eq!(polygons[0].end_index, vertices.len());
+    }
+
+    #[test]
+    fn focus_ring_is_drawn_but_not_hit_tested() {
+        let (mut guibase, _, other_id) = make_guibase();
+        let window_id = guibase.base_window;
+        let mut cache = TessellationCache::new();
+        cache.update(&mut guibase);
+        let (vertices, _, polygons) = cache.vertices_and_indices(&guibase, window_id);
+
+        guibase.set_focus(Some(other_id));
+        assert!(cache.update(&mut guibase));
+        let (focused_vertices, focused_indices, focused_polygons) =
+            cache.vertices_and_indices(&guibase, window_id);
+        assert_eq!(focused_polygons.len(), polygons.len() + 1);
+        assert_eq!(focused_vertices.len(), vertices.len() + 16);
+        assert_eq!(
+            *focused_indices.iter().max().unwrap() as usize,
+            focused_vertices.len() - 1
+        );
+
+        // The ring's top edge is just above the button, which isn't under it.
+        let above_button = GUIPosition::from_pixels(100., 197.);
+        assert_eq!(
+            processing_utils::get_clicked_widget(
+                &focused_polygons,
+                &focused_vertices,
+                &above_button
+            ),
+            None
+        );
+        let full = processing_utils::make_vertices_and_indices(&guibase, window_id);
+        assert_eq!(positions(&full.0), positions(&focused_vertices));
+    }
+}
@@ -0,0 +1,679 @@
+use std::collections::HashMap;
+
+use super::processing_utils::{to_index, INDEX_OVERFLOW};
+use super::vertices::{ClipRect, LogicalTextVertex};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::GUIFont;
+
+/// Blank pixels left around each glyph in the atlas so that
+/// linear filtering doesn't bleed neighboring glyphs together.
+const ATLAS_PADDING: u32 = 1;
+
+/// The tallest that an atlas can grow to unless it's told otherwise,
+/// which is the most that downlevel devices support.
+const DEFAULT_MAX_ATLAS_HEIGHT: u32 = 2048;
+
+/// Appended to text that's been cut short.
+const ELLIPSIS: char = '\u{2026}';
+
+/// A glyph that has been placed by the layout stage.
+/// Positions and sizes are in logical pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphQuad {
+    pub character: char,
+    /// The size in physical pixels that the glyph is rasterized at.
+    pub px: f32,
+    /// The top left corner of the glyph bitmap.
+    pub position: [f32; 2],
+    /// The width and height of the glyph bitmap.
+    pub size: [f32; 2],
+    pub color: [f32; 4],
+    /// The area that the glyph is cut down to, if its text is clipped.
+    pub clip: Option<ClipRect>,
+}
+
+/// Lays out a block of text within its area and returns a quad for every visible glyph.
+/// scale converts between logical and physical pixels. Glyphs are snapped
+/// to the physical pixel grid so that they're drawn crisply.
+pub fn layout_text(font: &GUIFont, text: &GUIText, scale: f64) -> Vec<GlyphQuad> {
+    let scale = scale as f32;
+    let px = text.font_size.get_length() as f32 * scale;
+    let color = text.color.to_f32_array();
+    let mut quads = Vec::new();
+    if px <= 0. {
+        return quads;
+    }
+
+    let line_metrics = font.line_metrics(px);
+    let left = text.position.x.get_length() as f32 * scale;
+    let top = text.position.y.get_length() as f32 * scale;
+    let width = text.size.width.get_length() as f32 * scale;
+    let height = text.size.height.get_length() as f32 * scale;
+
+    let lines = break_lines(font, text, px, width, height);
+    let clip = match text.overflow {
+        GUITextOverflow::Clip => Some(ClipRect::new(&text.position, &text.size)),
+        _ => None,
+    };
+    let block_height = (lines.len() - 1) as f32 * line_metrics.new_line_size + line_metrics.ascent
+        - line_metrics.descent;
+
+    let block_top = match text.vertical_alignment {
+        GUIVerticalAlignment::Top => top,
+        GUIVerticalAlignment::Center => top + (height - block_height) / 2.,
+        GUIVerticalAlignment::Bottom => top + height - block_height,
+    };
+
+    for (line_number, line) in lines.iter().enumerate() {
+        let line_width = font.line_width(line, px);
+        let mut pen_x = match text.horizontal_alignment {
+            GUIHorizontalAlignment::Left => left,
+            GUIHorizontalAlignment::Center => left + (width - line_width) / 2.,
+            GUIHorizontalAlignment::Right => left + width - line_width,
+        };
+        let baseline =
+            (block_top + line_metrics.ascent + line_number as f32 * line_metrics.new_line_size)
+                .round();
+
+        let mut previous: Option<char> = None;
+        for character in line.chars() {
+            if let Some(previous) = previous {
+                pen_x += font.kern(previous, character, px);
+            }
+            let metrics = font.metrics(character, px);
+            if metrics.width > 0 && metrics.height > 0 {
+                let x = pen_x.round() + metrics.xmin as f32;
+                let y = baseline - (metrics.ymin as f32 + metrics.height as f32);
+                quads.push(GlyphQuad {
+                    character,
+                    px,
+                    position: [x / scale, y / scale],
+                    size: [metrics.width as f32 / scale, metrics.height as f32 / scale],
+                    color,
+                    clip,
+                });
+            }
+            pen_x += metrics.advance_width;
+            previous = Some(character);
+        }
+    }
+
+    quads
+}
+
+/// Splits text into the lines that it's drawn as, wrapping and
+/// truncating it to the given width and height (in physical pixels)
+/// as the text's settings call for.
+fn break_lines(font: &GUIFont, text: &GUIText, px: f32, width: f32, height: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.text.split('\n') {
+        if text.wrap {
+            lines.extend(wrap_paragraph(font, paragraph, px, width));
+        } else {
+            lines.push(String::from(paragraph));
+        }
+    }
+
+    if text.overflow == GUITextOverflow::Ellipsis {
+        let line_metrics = font.line_metrics(px);
+        let first_line_height = line_metrics.ascent - line_metrics.descent;
+        let max_lines = if height < first_line_height {
+            1
+        } else {
+            ((height - first_line_height) / line_metrics.new_line_size) as usize + 1
+        };
+        if lines.len() > max_lines {
+            lines.truncate(max_lines);
+            let last_line = lines.last_mut().unwrap();
+            *last_line = truncate_with_ellipsis(font, last_line, px, width, true);
+        }
+        for line in lines.iter_mut() {
+            if font.line_width(line, px) > width {
+                *line = truncate_with_ellipsis(font, line, px, width, false);
+            }
+        }
+    }
+
+    lines
+}
+
+/// Breaks a paragraph into lines no wider than width, breaking between
+/// words where it can and within a word if the word doesn't fit on a line by itself.
+fn wrap_paragraph(font: &GUIFont, paragraph: &str, px: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in paragraph.split(' ') {
+        let candidate = if line.is_empty() {
+            String::from(word)
+        } else {
+            format!("{line} {word}")
+        };
+        if font.line_width(&candidate, px) <= width {
+            line = candidate;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        line = String::new();
+        for character in word.chars() {
+            line.push(character);
+            if font.line_width(&line, px) > width && line.chars().count() > 1 {
+                line.pop();
+                lines.push(line);
+                line = String::from(character);
+            }
+        }
+    }
+    lines.push(line);
+
+    lines
+}
+
+/// Removes characters from the end of a line until it and an ellipsis fit within width.
+/// If always is false, the line is only shortened if it doesn't already fit.
+fn truncate_with_ellipsis(font: &GUIFont, line: &str, px: f32, width: f32, always: bool) -> String {
+    if !always && font.line_width(line, px) <= width {
+        return String::from(line);
+    }
+    let mut truncated = String::from(line.trim_end());
+    loop {
+        let candidate = format!("{truncated}{ELLIPSIS}");
+        if truncated.is_empty() || font.line_width(&candidate, px) <= width {
+            return candidate;
+        }
+        truncated.pop();
+        truncated = String::from(truncated.trim_end());
+    }
+}
+
+/// The location of a rasterized glyph within the atlas, in atlas pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasEntry {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A single channel texture that glyph coverage bitmaps are packed into.
+/// Glyphs are packed in rows (shelves) and the atlas doubles in height when it's full,
+/// up to its max height, after which it's cleared and the glyphs are packed again.
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    max_height: u32,
+    /// Counts the times that glyphs that were already handed out have moved.
+    generation: u32,
+    pixels: Vec<u8>,
+    entries: HashMap<(char, u32), AtlasEntry>,
+    shelf_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+    dirty: bool,
+}
+
+impl GlyphAtlas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            max_height: height.max(DEFAULT_MAX_ATLAS_HEIGHT),
+            generation: 0,
+            pixels: vec![0; (width * height) as usize],
+            entries: HashMap::new(),
+            shelf_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+            dirty: true,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Sets the tallest that the atlas can grow to,
+    /// such as the largest texture that the device can make.
+    pub fn set_max_height(&mut self, max_height: u32) {
+        self.max_height = max_height.max(1);
+        if self.height > self.max_height {
+            self.height = self.max_height;
+            self.clear();
+        }
+    }
+
+    /// Returns a number that changes whenever the atlas is resized or cleared,
+    /// after which text vertices that were made before then need to be made again.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Returns true if glyphs have been added since the atlas was last uploaded.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_clean(&mut self) {
+        self.dirty = false;
+    }
+
+    /// Returns where a glyph is in the atlas, rasterizing and packing it first if need be.
+    pub fn get_glyph(&mut self, font: &GUIFont, character: char, px: f32) -> AtlasEntry {
+        let key = (character, px.to_bits());
+        if let Some(entry) = self.entries.get(&key) {
+            return *entry;
+        }
+
+        let (metrics, bitmap) = font.rasterize(character, px);
+        let entry = self.allocate(metrics.width as u32, metrics.height as u32);
+        for row in 0..entry.height {
+            let source = (row as usize) * metrics.width;
+            let destination = ((entry.y + row) * self.width + entry.x) as usize;
+            self.pixels[destination..destination + entry.width as usize]
+                .copy_from_slice(&bitmap[source..source + entry.width as usize]);
+        }
+        self.entries.insert(key, entry);
+        self.dirty = true;
+
+        entry
+    }
+
+    fn allocate(&mut self, width: u32, height: u32) -> AtlasEntry {
+        let padded_width = (width + ATLAS_PADDING).min(self.width);
+        let padded_height = height + ATLAS_PADDING;
+
+        if self.shelf_x + padded_width > self.width {
+            self.shelf_y += self.shelf_height;
+            self.shelf_x = 0;
+            self.shelf_height = 0;
+        }
+        while self.shelf_y + padded_height > self.height {
+            if self.height < self.max_height {
+                self.grow();
+            } else if self.shelf_y > 0 || self.shelf_x > 0 {
+                self.clear();
+            } else {
+                // The glyph is taller than the atlas can be, so it's cut short.
+                break;
+            }
+        }
+
+        let entry = AtlasEntry {
+            x: self.shelf_x,
+            y: self.shelf_y,
+            width: width.min(self.width),
+            height: height.min(self.height - self.shelf_y),
+        };
+        self.shelf_x += padded_width;
+        self.shelf_height = self.shelf_height.max(padded_height);
+
+        entry
+    }
+
+    fn grow(&mut self) {
+        self.height = (self.height * 2).min(self.max_height);
+        self.pixels.resize((self.width * self.height) as usize, 0);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Throws away every glyph, so that the ones that are still being used
+    /// can be packed again.
+    fn clear(&mut self) {
+        self.pixels = vec![0; (self.width * self.height) as usize];
+        self.entries.clear();
+        self.shelf_x = 0;
+        self.shelf_y = 0;
+        self.shelf_height = 0;
+        self.dirty = true;
+        self.generation = self.generation.wrapping_add(1);
+    }
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new(512, 512)
+    }
+}
+
+/// Turns laid out glyphs into textured quads, adding any missing glyphs to the atlas.
+/// Glyphs are cut down to clip, if there is one, and to their own clip if their text is clipped,
+/// and left out if they're outside of it.
+pub fn make_glyph_vertices_and_indices(
+    quads: &[GlyphQuad],
+    font: &GUIFont,
+    atlas: &mut GlyphAtlas,
+    indice_offset: u32,
+    clip: Option<&ClipRect>,
+) -> (Vec<LogicalTextVertex>, Vec<u32>) {
+    let mut vertices = Vec::with_capacity(quads.len() * 4);
+    let mut indices = Vec::with_capacity(quads.len() * 6);
+
+    for quad in quads {
+        let [mut left, mut top] = quad.position;
+        let mut right = left + quad.size[0];
+        let mut bottom = top + quad.size[1];
+        let (full_left, full_top, full_right, full_bottom) = (left, top, right, bottom);
+        let clip = match (clip, &quad.clip) {
+            (Some(clip), Some(own_clip)) => Some(clip.intersect(own_clip)),
+            (Some(clip), None) => Some(*clip),
+            (None, own_clip) => *own_clip,
+        };
+        if let Some(clip) = clip {
+            left = left.max(clip.left);
+            top = top.max(clip.top);
+            right = right.min(clip.right);
+            bottom = bottom.min(clip.bottom);
+            if right <= left || bottom <= top {
+                continue;
+            }
+        }
+
+        let entry = atlas.get_glyph(font, quad.character, quad.px);
+        let mut tex_left = entry.x as f32;
+        let mut tex_top = entry.y as f32;
+        let mut tex_right = (entry.x + entry.width) as f32;
+        let mut tex_bottom = (entry.y + entry.height) as f32;
+        if clip.is_some() {
+            // The texture is cut down by the same fractions as the quad.
+            let tex_x = |x: f32| {
+                entry.x as f32 + (x - full_left) / (full_right - full_left) * entry.width as f32
+            };
+            let tex_y = |y: f32| {
+                entry.y as f32 + (y - full_top) / (full_bottom - full_top) * entry.height as f32
+            };
+            (tex_left, tex_right) = (tex_x(left), tex_x(right));
+            (tex_top, tex_bottom) = (tex_y(top), tex_y(bottom));
+        }
+
+        let offset = indice_offset
+            .checked_add(to_index(vertices.len()))
+            .expect(INDEX_OVERFLOW);
+        vertices.push(LogicalTextVertex {
+            position: [left, top, 0.],
+            tex_coords: [tex_left, tex_top],
+            color: quad.color,
+        });
+        vertices.push(LogicalTextVertex {
+            position: [left, bottom, 0.],
+            tex_coords: [tex_left, tex_bottom],
+            color: quad.color,
+        });
+        vertices.push(LogicalTextVertex {
+            position: [right, bottom, 0.],
+            tex_coords: [tex_right, tex_bottom],
+            color: quad.color,
+        });
+        vertices.push(LogicalTextVertex {
+            position: [right, top, 0.],
+            tex_coords: [tex_right, tex_top],
+            color: quad.color,
+        });
+        indices.extend(
+            [0, 1, 2, 0, 2, 3]
+                .iter()
+                .map(|index: &u32| index.checked_add(offset).expect(INDEX_OVERFLOW)),
+        );
+    }
+
+    (vertices, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+    use crate::guiproperties::GUIColor;
+
+    fn make_text(text: &str) -> GUIText {
+        GUIText {
+            text: String::from(text),
+            position: GUIPosition::from_pixels(10., 20.),
+            size: GUISize::from_pixels(200., 50.),
+            font_size: GUILength::from_pixels(20.),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn layout_skips_whitespace() {
+        let font = GUIFont::default();
+        let quads = layout_text(&font, &make_text("a b"), 1.);
+        let characters: Vec<char> = quads.iter().map(|quad| quad.character).collect();
+        assert_eq!(characters, vec!['a', 'b']);
+    }
+
+    #[test]
+    fn layout_left_top_starts_at_position() {
+        let font = GUIFont::default();
+        let quads = layout_text(&font, &make_text("Hi"), 1.);
+        assert_eq!(quads.len(), 2);
+        assert!(quads[0].position[0] >= 10. && quads[0].position[0] < 14.);
+        assert!(quads[0].position[1] >= 20.);
+        assert!(quads[1].position[0] > quads[0].position[0]);
+        for quad in quads.iter() {
+            assert!(quad.position[1] + quad.size[1] <= 20. + 25.);
+        }
+    }
+
+    #[test]
+    fn layout_centers_text() {
+        let font = GUIFont::default();
+        let mut text = make_text("Hi");
+        text.horizontal_alignment = GUIHorizontalAlignment::Center;
+        text.vertical_alignment = GUIVerticalAlignment::Center;
+        let quads = layout_text(&font, &text, 1.);
+
+        let left = quads[0].position[0];
+        let right = quads[1].position[0] + quads[1].size[0];
+        let top = quads[0].position[1];
+        let bottom = quads[0].position[1] + quads[0].size[1];
+        assert!(((left - 10.) - (210. - right)).abs() < 3.);
+        assert!(((top - 20.) - (70. - bottom)).abs() < 6.);
+    }
+
+    #[test]
+    fn layout_right_aligns_text() {
+        let font = GUIFont::default();
+        let mut text = make_text("Hi");
+        text.horizontal_alignment = GUIHorizontalAlignment::Right;
+        let quads = layout_text(&font, &text, 1.);
+        let right = quads[1].position[0] + quads[1].size[0];
+        assert!(right <= 210. && right > 205.);
+    }
+
+    #[test]
+    fn layout_scales_to_physical_pixels() {
+        let font = GUIFont::default();
+        let logical = layout_text(&font, &make_text("H"), 1.);
+        let physical = layout_text(&font, &make_text("H"), 2.);
+        assert_eq!(physical[0].px, 2. * logical[0].px);
+        assert!((physical[0].size[1] - logical[0].size[1]).abs() <= 1.);
+    }
+
+    #[test]
+    fn layout_breaks_lines() {
+        let font = GUIFont::default();
+        let quads = layout_text(&font, &make_text("H\nH"), 1.);
+        assert_eq!(quads.len(), 2);
+        assert_eq!(quads[0].position[0], quads[1].position[0]);
+        let line_height = font.line_height(&GUILength::from_pixels(20.)).get_length() as f32;
+        assert!((quads[1].position[1] - quads[0].position[1] - line_height).abs() <= 1.);
+    }
+
+    fn glyph_text(quads: &[GlyphQuad]) -> String {
+        quads.iter().map(|quad| quad.character).collect()
+    }
+
+    #[test]
+    fn layout_wraps_words() {
+        let font = GUIFont::default();
+        let mut text = make_text("one two three four five six");
+        text.size = GUISize::from_pixels(100., 200.);
+        text.wrap = true;
+        let quads = layout_text(&font, &text, 1.);
+        assert_eq!(glyph_text(&quads), "onetwothreefourfivesix");
+
+        let mut line_tops: Vec<f32> = quads.iter().map(|quad| quad.position[1]).collect();
+        line_tops.dedup_by(|a, b| (*a - *b).abs() < 10.);
+        assert!(line_tops.len() > 1);
+        for quad in quads.iter() {
+            assert!(quad.position[0] + quad.size[0] <= 10. + 100.);
+        }
+    }
+
+    #[test]
+    fn layout_wraps_long_words() {
+        let font = GUIFont::default();
+        let mut text = make_text("abcdefghijklmnopqrstuvwxyz");
+        text.size = GUISize::from_pixels(60., 400.);
+        text.wrap = true;
+        let quads = layout_text(&font, &text, 1.);
+        assert_eq!(quads.len(), 26);
+        for quad in quads.iter() {
+            assert!(quad.position[0] + quad.size[0] <= 10. + 60.);
+        }
+    }
+
+    #[test]
+    fn layout_ellipsizes_long_lines() {
+        let font = GUIFont::default();
+        let mut text = make_text("This line is much too long to fit");
+        text.size = GUISize::from_pixels(100., 50.);
+        text.overflow = GUITextOverflow::Ellipsis;
+        let quads = layout_text(&font, &text, 1.);
+        let characters = glyph_text(&quads);
+        assert!(characters.ends_with(ELLIPSIS));
+        assert!(characters.starts_with("This"));
+        let last = quads.last().unwrap();
+        assert!(last.position[0] + last.size[0] <= 10. + 100.);
+    }
+
+    #[test]
+    fn layout_ellipsizes_extra_lines() {
+        let font = GUIFont::default();
+        let mut text = make_text("one two three four five six seven eight nine ten");
+        text.size = GUISize::from_pixels(100., 50.);
+        text.wrap = true;
+        text.overflow = GUITextOverflow::Ellipsis;
+        let quads = layout_text(&font, &text, 1.);
+        assert!(glyph_text(&quads).ends_with(ELLIPSIS));
+        for quad in quads.iter() {
+            assert!(quad.position[1] + quad.size[1] <= 20. + 50.);
+        }
+    }
+
+    #[test]
+    fn layout_leaves_fitting_text_alone() {
+        let font = GUIFont::default();
+        let mut text = make_text("fits");
+        text.wrap = true;
+        text.overflow = GUITextOverflow::Ellipsis;
+        let quads = layout_text(&font, &text, 1.);
+        assert_eq!(glyph_text(&quads), "fits");
+    }
+
+    #[test]
+    fn clipped_text_is_cut_off_at_its_area() {
+        let font = GUIFont::default();
+        let mut text = make_text("This line is much too long to fit");
+        text.size = GUISize::from_pixels(100., 50.);
+        text.overflow = GUITextOverflow::Clip;
+        let quads = layout_text(&font, &text, 1.);
+        assert_eq!(glyph_text(&quads), "Thislineismuchtoolongtofit");
+
+        let mut atlas = GlyphAtlas::default();
+        let (vertices, _) = make_glyph_vertices_and_indices(&quads, &font, &mut atlas, 0, None);
+        assert!(vertices.len() < quads.len() * 4);
+        for vertex in vertices.iter() {
+            assert!(vertex.position[0] <= 10. + 100.);
+        }
+    }
+
+    #[test]
+    fn layout_keeps_text_alpha() {
+        let font = GUIFont::default();
+        let mut text = make_text("a");
+        text.color = GUIColor::from_rgba_u8u8u8u8(255, 0, 0, 51);
+        let quads = layout_text(&font, &text, 1.);
+        let mut atlas = GlyphAtlas::default();
+        let (vertices, _) = make_glyph_vertices_and_indices(&quads, &font, &mut atlas, 0, None);
+        assert_eq!(vertices[0].color, [1., 0., 0., 0.2]);
+        assert_eq!(vertices[0].to_vertex(100., 100., 512., 512.).color[3], 0.2);
+    }
+
+    #[test]
+    fn atlas_reuses_glyphs() {
+        let font = GUIFont::default();
+        let mut atlas = GlyphAtlas::new(64, 64);
+        let first = atlas.get_glyph(&font, 'A', 20.);
+        atlas.mark_clean();
+        let second = atlas.get_glyph(&font, 'A', 20.);
+        assert_eq!(first, second);
+        assert!(!atlas.is_dirty());
+        let other = atlas.get_glyph(&font, 'B', 20.);
+        assert_ne!(first, other);
+        assert!(atlas.is_dirty());
+    }
+
+    #[test]
+    fn atlas_grows_when_full() {
+        let font = GUIFont::default();
+        let mut atlas = GlyphAtlas::new(32, 32);
+        for character in "ABCDEFGHIJ".chars() {
+            atlas.get_glyph(&font, character, 20.);
+        }
+        assert!(atlas.height() > 32);
+        assert_eq!(
+            atlas.pixels().len(),
+            (atlas.width() * atlas.height()) as usize
+        );
+    }
+
+    #[test]
+    fn atlas_clears_instead_of_growing_past_its_max_height() {
+        let font = GUIFont::default();
+        let mut atlas = GlyphAtlas::new(32, 32);
+        atlas.set_max_height(64);
+        let generation = atlas.generation();
+        for character in "ABCDEFGHIJKLMNOPQRSTUVWXYZ".chars() {
+            let entry = atlas.get_glyph(&font, character, 20.);
+            assert!(entry.y + entry.height <= 64);
+        }
+        assert_eq!(atlas.height(), 64);
+        assert_eq!(atlas.pixels().len(), 32 * 64);
+        assert_ne!(atlas.generation(), generation);
+        // The glyphs that were cleared out are packed again when they're next asked for.
+        let entry = atlas.get_glyph(&font, 'A', 20.);
+        assert_eq!(atlas.get_glyph(&font, 'A', 20.), entry);
+    }
+
+    #[test]
+    fn text_vertices_make_two_triangles_per_glyph() {
+        let font = GUIFont::default();
+        let mut atlas = GlyphAtlas::default();
+        let quads = layout_text(&font, &make_text("ab"), 1.);
+        let (vertices, indices) = make_glyph_vertices_and_indices(&quads, &font, &mut atlas, 10, None);
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(
+            indices,
+            vec![10, 11, 12, 10, 12, 13, 14, 15, 16, 14, 16, 17]
+        );
+        assert_eq!(vertices[0].position[0], quads[0].position[0]);
+        assert_eq!(
+            vertices[2].position[1],
+            quads[0].position[1] + quads[0].size[1]
+        );
+    }
+}
@@ -1,8 +1,10 @@
+use crate::guiproperties::guiposition::{GUIPosition, GUISize};
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
     pub position: [f32; 3],
-    pub color: [f32; 3],
+    pub color: [f32; 4],
 }
 
 impl Vertex {
@@ -19,16 +21,18 @@ impl Vertex {
                 wgpu::VertexAttribute {
                     offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
                     shader_location: 1,
-                    format: wgpu::VertexFormat::Float32x3,
+                    format: wgpu::VertexFormat::Float32x4,
                 },
             ],
         }
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct LogicalVertex {
     pub position: [f32; 3],
-    pub color: [f32; 3],
+    /// Red, green, blue and alpha, not premultiplied.
+    pub color: [f32; 4],
 }
 
 impl LogicalVertex {
@@ -36,21 +40,26 @@ impl LogicalVertex {
         let mut position = [0.0_f32; 3];
         position[0] = self.position[0] / width * 2. - 1.;
         position[1] = -self.position[1] / height * 2. + 1.;
-        let mut color = [0.0_f32; 3];
+        let mut color = [0.0_f32; 4];
         color[0] = self.color[0];
         color[1] = self.color[1];
         color[2] = self.color[2];
+        color[3] = self.color[3];
 
         Vertex { position, color }
     }
 }
 
+#[derive(Clone, Copy, Debug)]
 pub struct Polygon {
     pub start_index: usize,
     pub end_index: usize,
     pub widget_id: u128,
     pub convex: bool,
     pub rendered: bool,
+    /// The area that the widget is drawn and clicked within, such as the viewport of
+    /// a scroll view that it's in, or None if it isn't clipped. Set by the gui.
+    pub clip: Option<ClipRect>,
 }
 
 impl Default for Polygon {
@@ -61,6 +70,109 @@ impl Default for Polygon {
             widget_id: 0,
             convex: true,
             rendered: false,
+            clip: None,
+        }
+    }
+}
+
+/// A rectangle in logical pixels that shapes and text are clipped to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipRect {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl ClipRect {
+    /// Returns the rectangle that's size with its top left corner at position.
+    pub fn new(position: &GUIPosition, size: &GUISize) -> Self {
+        let left = position.x.get_length() as f32;
+        let top = position.y.get_length() as f32;
+        Self {
+            left,
+            top,
+            right: left + size.width.get_length() as f32,
+            bottom: top + size.height.get_length() as f32,
+        }
+    }
+
+    /// Returns the area that's within both rectangles, which is empty if they don't overlap.
+    pub fn intersect(&self, other: &ClipRect) -> Self {
+        let left = self.left.max(other.left);
+        let top = self.top.max(other.top);
+        Self {
+            left,
+            top,
+            right: self.right.min(other.right).max(left),
+            bottom: self.bottom.min(other.bottom).max(top),
+        }
+    }
+
+    /// Returns true if the point x, y is within the rectangle.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.left <= x && x < self.right && self.top <= y && y < self.bottom
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TextVertex {
+    pub position: [f32; 3],
+    pub tex_coords: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl TextVertex {
+    pub fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TextVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+/// A corner of a glyph quad in logical pixels.
+/// tex_coords are in atlas pixels so that they stay valid if the atlas grows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LogicalTextVertex {
+    pub position: [f32; 3],
+    pub tex_coords: [f32; 2],
+    /// Red, green, blue and alpha, not premultiplied.
+    pub color: [f32; 4],
+}
+
+impl LogicalTextVertex {
+    pub fn to_vertex(&self, width: f32, height: f32, atlas_width: f32, atlas_height: f32) -> TextVertex {
+        let mut position = [0.0_f32; 3];
+        position[0] = self.position[0] / width * 2. - 1.;
+        position[1] = -self.position[1] / height * 2. + 1.;
+        let tex_coords = [
+            self.tex_coords[0] / atlas_width,
+            self.tex_coords[1] / atlas_height,
+        ];
+
+        TextVertex {
+            position,
+            tex_coords,
+            color: self.color,
         }
     }
 }
@@ -28,4 +28,9 @@ impl GUIColor {
             a: a as f64 / 255.,
         }
     }
+
+    /// Returns the red, green, blue and alpha values as an array of f32s.
+    pub fn to_f32_array(&self) -> [f32; 4] {
+        [self.r as f32, self.g as f32, self.b as f32, self.a as f32]
+    }
 }
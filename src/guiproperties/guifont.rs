@@ -0,0 +1,81 @@
+use fontdue::{Font, FontSettings, LineMetrics, Metrics};
+
+use super::guiposition::GUILength;
+
+/// The font that's used when one isn't otherwise specified (DejaVu Sans).
+const DEFAULT_FONT: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+
+/// Represents a font that text is measured and rasterized with.
+#[derive(Clone)]
+pub struct GUIFont {
+    font: Font,
+}
+
+impl Default for GUIFont {
+    /// Returns the font that's bundled with gui-rs.
+    fn default() -> Self {
+        Self::from_bytes(DEFAULT_FONT).unwrap()
+    }
+}
+
+impl GUIFont {
+    /// Loads a font from the contents of a TrueType (ttf) or OpenType (otf) file.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let font = Font::from_bytes(bytes, FontSettings::default())?;
+        Ok(Self { font })
+    }
+
+    /// Returns the distance between the baselines of two consecutive lines of text.
+    pub fn line_height(&self, font_size: &GUILength) -> GUILength {
+        GUILength::from_pixels(
+            self.line_metrics(font_size.get_length() as f32)
+                .new_line_size as f64,
+        )
+    }
+
+    /// Returns the width of a single line of text.
+    pub fn measure(&self, text: &str, font_size: &GUILength) -> GUILength {
+        GUILength::from_pixels(self.line_width(text, font_size.get_length() as f32) as f64)
+    }
+
+    /// Returns the ascent, descent and line gap of the font at the given pixel size.
+    pub(crate) fn line_metrics(&self, px: f32) -> LineMetrics {
+        self.font
+            .horizontal_line_metrics(px)
+            .unwrap_or(LineMetrics {
+                ascent: px,
+                descent: 0.,
+                line_gap: 0.,
+                new_line_size: px,
+            })
+    }
+
+    /// Returns the bitmap placement and advance of a character at the given pixel size.
+    pub(crate) fn metrics(&self, character: char, px: f32) -> Metrics {
+        self.font.metrics(character, px)
+    }
+
+    /// Returns the horizontal adjustment between two neighboring characters.
+    pub(crate) fn kern(&self, left: char, right: char, px: f32) -> f32 {
+        self.font.horizontal_kern(left, right, px).unwrap_or(0.)
+    }
+
+    /// Returns the coverage bitmap of a character at the given pixel size.
+    pub(crate) fn rasterize(&self, character: char, px: f32) -> (Metrics, Vec<u8>) {
+        self.font.rasterize(character, px)
+    }
+
+    /// Returns the advance of a single line of text, including kerning.
+    pub(crate) fn line_width(&self, text: &str, px: f32) -> f32 {
+        let mut width = 0.;
+        let mut previous: Option<char> = None;
+        for character in text.chars() {
+            if let Some(previous) = previous {
+                width += self.kern(previous, character, px);
+            }
+            width += self.metrics(character, px).advance_width;
+            previous = Some(character);
+        }
+        width
+    }
+}
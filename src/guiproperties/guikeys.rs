@@ -0,0 +1,65 @@
+/// A key on the keyboard, independent of the windowing library.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GUIKey {
+    Tab,
+    Enter,
+    Space,
+    Escape,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    /// A letter key, as its lowercase character, for shortcuts such as ctrl+z.
+    Letter(char),
+    /// Any other key.
+    Other,
+}
+
+/// The modifier keys that are held down.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GUIModifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows key, or command on macOS.
+    pub logo: bool,
+}
+
+impl GUIModifiers {
+    /// Returns whether the platform's shortcut modifier is held down,
+    /// which is command on macOS and ctrl everywhere else.
+    pub fn shortcut(&self) -> bool {
+        if cfg!(target_os = "macos") {
+            self.logo
+        } else {
+            self.ctrl
+        }
+    }
+}
+
+/// A key being pressed or released.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GUIKeyEvent {
+    pub key: GUIKey,
+    /// The modifier keys that were held down at the time.
+    pub modifiers: GUIModifiers,
+    /// True if the key was pressed, or is repeating, and false if it was released.
+    pub pressed: bool,
+}
+
+impl GUIKeyEvent {
+    /// Returns a key press with no modifiers held down.
+    pub fn pressed(key: GUIKey) -> Self {
+        Self {
+            key,
+            modifiers: GUIModifiers::default(),
+            pressed: true,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use super::guiposition::{GUILength, GUIPosition, GUISize};
+
+/// The direction that a container places its children in.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUIAxis {
+    /// Children are placed left to right.
+    #[default]
+    Horizontal,
+    /// Children are placed top to bottom.
+    Vertical,
+}
+
+/// Where children sit across a container's axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUIAlignment {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Children are stretched to fill the container.
+    Stretch,
+}
+
+/// How left over space along a container's axis is distributed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUIJustify {
+    /// Children are packed against the start of the container.
+    #[default]
+    Start,
+    /// Children are packed in the middle of the container.
+    Center,
+    /// Children are packed against the end of the container.
+    End,
+    /// The space goes between children, none before the first or after the last.
+    SpaceBetween,
+    /// Every child gets the same space on either side of it.
+    SpaceAround,
+    /// The space before, between and after children is all the same.
+    SpaceEvenly,
+}
+
+/// Space between the edges of a container and its children.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GUIPadding {
+    pub left: GUILength,
+    pub top: GUILength,
+    pub right: GUILength,
+    pub bottom: GUILength,
+}
+
+impl GUIPadding {
+    /// Returns padding that's the same on all four sides.
+    pub fn from_pixels(pixels: f64) -> Self {
+        Self {
+            left: GUILength::from_pixels(pixels),
+            top: GUILength::from_pixels(pixels),
+            right: GUILength::from_pixels(pixels),
+            bottom: GUILength::from_pixels(pixels),
+        }
+    }
+
+    /// Returns the area that's left inside of size once the padding is removed.
+    /// The position is relative to the top left corner of size.
+    pub fn inset(&self, size: &GUISize) -> (GUIPosition, GUISize) {
+        let width = size.width.subtract(&self.left).subtract(&self.right);
+        let height = size.height.subtract(&self.top).subtract(&self.bottom);
+        (
+            GUIPosition::from_lengths(self.left, self.top),
+            GUISize::from_pixels(width.get_length().max(0.), height.get_length().max(0.)),
+        )
+    }
+}
+
+/// The size of a row or column in a grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GUITrack {
+    /// The track is always the same size.
+    Fixed(GUILength),
+    /// The track fits the largest widget in it.
+    Auto,
+    /// The track takes a share of the space that's left over after fixed and auto
+    /// tracks, in proportion to the other fractional tracks (like css's fr unit).
+    Fraction(f64),
+}
+
+/// The cells of a grid that a widget covers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GUIGridCell {
+    pub row: usize,
+    pub column: usize,
+    /// The number of rows that the widget covers.
+    pub row_span: usize,
+    /// The number of columns that the widget covers.
+    pub column_span: usize,
+}
+
+impl Default for GUIGridCell {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl GUIGridCell {
+    /// Returns a single cell.
+    pub fn new(row: usize, column: usize) -> Self {
+        Self {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+        }
+    }
+
+    /// Returns the cell extended to cover row_span rows and column_span columns.
+    pub fn span(self, row_span: usize, column_span: usize) -> Self {
+        Self {
+            row_span: row_span.max(1),
+            column_span: column_span.max(1),
+            ..self
+        }
+    }
+}
+
+/// How a widget is treated by the container that it's in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GUILayoutItem {
+    /// How much of a container's left over space the widget takes, relative to its siblings.
+    pub grow: f64,
+    /// How much the widget gives up when a container is too small, relative to its siblings.
+    pub shrink: f64,
+    /// Stretches the widget to cover its parent when the parent doesn't arrange its children.
+    pub fill: bool,
+    /// The cells that the widget covers when it's in a grid.
+    pub cell: GUIGridCell,
+}
+
+impl Default for GUILayoutItem {
+    fn default() -> Self {
+        Self {
+            grow: 0.,
+            shrink: 1.,
+            fill: false,
+            cell: GUIGridCell::default(),
+        }
+    }
+}
+
+impl GUILayoutItem {
+    /// Returns the default layout for a widget that's placed in a grid cell.
+    pub fn in_cell(cell: GUIGridCell) -> Self {
+        Self {
+            cell,
+            ..Default::default()
+        }
+    }
+}
+
+/// A cell of a list or a table that needs a widget, because it can be seen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GUICell {
+    /// The row of data that the cell shows.
+    pub row: usize,
+    /// The column that the cell is in, which picks the callback that fills it in.
+    pub column: usize,
+    /// Where the cell goes, relative to its parent before the parent is scrolled.
+    pub position: GUIPosition,
+    /// The size of the cell.
+    pub size: GUISize,
+}
+
+/// A child as it's seen by the container arranging it.
+#[derive(Clone, Copy, Debug)]
+pub struct GUILayoutChild {
+    /// The size the child would like to be.
+    pub preferred_size: GUISize,
+    pub item: GUILayoutItem,
+}
@@ -0,0 +1,24 @@
+use super::guikeys::GUIModifiers;
+use super::guiposition::GUIPosition;
+
+/// What the pointer did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GUIPointerEventKind {
+    /// The left mouse button was pressed on the widget.
+    Pressed,
+    /// The pointer moved while the left mouse button was held down after being pressed
+    /// on the widget, even if the pointer is no longer over the widget.
+    Moved,
+    /// The left mouse button was released after being pressed on the widget.
+    Released,
+}
+
+/// The left mouse button being used on a widget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GUIPointerEvent {
+    pub kind: GUIPointerEventKind,
+    /// Where the pointer is, relative to the widget's top left corner.
+    pub position: GUIPosition,
+    /// The modifier keys that were held down at the time.
+    pub modifiers: GUIModifiers,
+}
@@ -69,7 +69,7 @@ impl GUILength {
 }
 
 /// Represents an area (width and height)
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct GUISize {
     pub width: GUILength,
     pub height: GUILength,
@@ -0,0 +1,76 @@
+use super::guiposition::{GUILength, GUIPosition, GUISize};
+use super::GUIColor;
+
+/// Where text sits horizontally within its bounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUIHorizontalAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where text sits vertically within its bounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUIVerticalAlignment {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+/// What happens to text that doesn't fit within its area.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GUITextOverflow {
+    /// The text is drawn past the edges of its area.
+    #[default]
+    Visible,
+    /// The text is cut short and ends with an ellipsis.
+    Ellipsis,
+    /// The text is cut off at the edges of its area.
+    Clip,
+}
+
+/// Represents a block of text and the area that it's laid out in.
+#[derive(Clone)]
+pub struct GUIText {
+    /// The text to draw. Lines are separated by '\n'.
+    pub text: String,
+    /// The top left corner of the area that the text is laid out in.
+    pub position: GUIPosition,
+    /// The size of the area that the text is laid out in.
+    pub size: GUISize,
+    /// The height of the font.
+    pub font_size: GUILength,
+    /// The color of the text.
+    pub color: GUIColor,
+    /// Where the text sits horizontally within its area.
+    pub horizontal_alignment: GUIHorizontalAlignment,
+    /// Where the text sits vertically within its area.
+    pub vertical_alignment: GUIVerticalAlignment,
+    /// Whether lines that are wider than the area are broken between words.
+    pub wrap: bool,
+    /// What happens to text that doesn't fit within the area.
+    pub overflow: GUITextOverflow,
+}
+
+impl Default for GUIText {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            position: GUIPosition::default(),
+            size: GUISize::default(),
+            font_size: GUILength::from_pixels(16.),
+            color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            horizontal_alignment: GUIHorizontalAlignment::default(),
+            vertical_alignment: GUIVerticalAlignment::default(),
+            wrap: false,
+            overflow: GUITextOverflow::default(),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+use std::ops::Range;
+
+/// The room that's left for insertions whenever the gap has to grow.
+const MIN_GAP: usize = 64;
+
+/// Text that's stored in a gap buffer, so that edits near the last one are cheap
+/// no matter how long the text is. Positions and ranges are counted in characters.
+#[derive(Clone, Debug, Default)]
+pub struct GUITextBuffer {
+    buffer: Vec<char>,
+    /// Where the unused space in buffer starts.
+    gap_start: usize,
+    /// Where the unused space in buffer ends.
+    gap_end: usize,
+}
+
+impl GUITextBuffer {
+    pub fn new(text: &str) -> Self {
+        let buffer: Vec<char> = text.chars().collect();
+        let length = buffer.len();
+        Self {
+            buffer,
+            gap_start: length,
+            gap_end: length,
+        }
+    }
+
+    /// Returns the number of characters in the text.
+    pub fn len(&self) -> usize {
+        self.buffer.len() - (self.gap_end - self.gap_start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the character at position, or None if it's past the end of the text.
+    pub fn get(&self, position: usize) -> Option<char> {
+        if position < self.gap_start {
+            Some(self.buffer[position])
+        } else {
+            self.buffer
+                .get(position + self.gap_end - self.gap_start)
+                .copied()
+        }
+    }
+
+    /// Returns the characters in order.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.buffer[..self.gap_start]
+            .iter()
+            .chain(self.buffer[self.gap_end..].iter())
+            .copied()
+    }
+
+    /// Returns the text within range.
+    pub fn slice(&self, range: Range<usize>) -> String {
+        range.filter_map(|position| self.get(position)).collect()
+    }
+
+    /// Inserts text before the character at position.
+    pub fn insert(&mut self, position: usize, text: &str) {
+        let position = position.min(self.len());
+        let length = text.chars().count();
+        self.move_gap(position);
+        if self.gap_end - self.gap_start < length {
+            self.grow_gap(length);
+        }
+        for character in text.chars() {
+            self.buffer[self.gap_start] = character;
+            self.gap_start += 1;
+        }
+    }
+
+    /// Removes the characters within range and returns them.
+    pub fn remove(&mut self, range: Range<usize>) -> String {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+        let removed = self.slice(start..end);
+        self.move_gap(start);
+        self.gap_end += end - start;
+        removed
+    }
+
+    /// Moves the gap so that it starts at position.
+    fn move_gap(&mut self, position: usize) {
+        if position < self.gap_start {
+            let count = self.gap_start - position;
+            self.buffer
+                .copy_within(position..self.gap_start, self.gap_end - count);
+            self.gap_start -= count;
+            self.gap_end -= count;
+        } else if position > self.gap_start {
+            let count = position - self.gap_start;
+            self.buffer
+                .copy_within(self.gap_end..self.gap_end + count, self.gap_start);
+            self.gap_start += count;
+            self.gap_end += count;
+        }
+    }
+
+    /// Makes the gap big enough for length more characters, with room to spare.
+    fn grow_gap(&mut self, length: usize) {
+        let extra = length.max(MIN_GAP).max(self.buffer.len() / 2);
+        let after_gap = self.buffer.len() - self.gap_end;
+        self.buffer
+            .splice(self.gap_end..self.gap_end, std::iter::repeat_n('\0', extra));
+        self.gap_end = self.buffer.len() - after_gap;
+    }
+}
+
+impl fmt::Display for GUITextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chars()
+            .try_for_each(|character| write!(f, "{character}"))
+    }
+}
+
+impl PartialEq for GUITextBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.chars().eq(other.chars())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edits_anywhere_in_the_text() {
+        let mut text = GUITextBuffer::new("hello world");
+        text.insert(5, ",");
+        text.insert(0, "¡");
+        text.insert(text.len(), "!");
+        assert_eq!(text.to_string(), "¡hello, world!");
+        assert_eq!(text.len(), 14);
+
+        assert_eq!(text.remove(1..7), "hello,");
+        assert_eq!(text.to_string(), "¡ world!");
+        assert_eq!(text.get(2), Some('w'));
+        assert_eq!(text.get(8), None);
+        assert_eq!(text.slice(2..7), "world");
+    }
+
+    #[test]
+    fn grows_past_the_gap() {
+        let mut text = GUITextBuffer::default();
+        let long = "a".repeat(1000);
+        text.insert(0, &long);
+        text.insert(500, "b");
+        assert_eq!(text.len(), 1001);
+        assert_eq!(text.get(500), Some('b'));
+        assert_eq!(text, GUITextBuffer::new(&text.to_string()));
+    }
+}
@@ -1,19 +1,163 @@
 use std::any::Any;
+use std::time::Instant;
 
 use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::GUIKeyEvent;
+use crate::guiproperties::guilayout::{GUICell, GUILayoutChild};
+use crate::guiproperties::guipointer::GUIPointerEvent;
 use crate::guiproperties::guiposition::{GUIPosition, GUISize};
+use crate::guiproperties::guitext::GUIText;
+use crate::guiproperties::GUIFont;
 
 pub trait Widget {
     fn get_vertices_and_indices(
         &self,
         parent_position: &GUIPosition,
-        indice_offset: u16,
-    ) -> (Vec<LogicalVertex>, Vec<u16>, Polygon);
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon);
+
+    /// Returns the text that the widget draws on top of its shapes.
+    fn get_text(&self, _parent_position: &GUIPosition) -> Vec<GUIText> {
+        Vec::new()
+    }
+
+    /// Tells the widget whether the cursor is over it.
+    fn set_hovered(&mut self, _hovered: bool) {}
+
+    /// Tells the widget whether the left mouse button was pressed on it and is still down.
+    fn set_pressed(&mut self, _pressed: bool) {}
+
+    /// Returns false if the widget shouldn't respond to input.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Handles the widget being clicked, or activated with Enter or Space while it has focus.
+    /// Returns true if the widget's value changed, in which case it's tessellated again
+    /// and its change callback is run.
+    fn activate(&mut self) -> bool {
+        false
+    }
+
+    /// Tells the widget whether it's the selected one of its siblings,
+    /// such as the chosen radio button in a group.
+    fn set_selected(&mut self, _selected: bool) {}
+
+    /// Returns true if the widget is selected.
+    fn is_selected(&self) -> bool {
+        false
+    }
+
+    /// Returns what the widget stands for, such as the choice that a radio button makes,
+    /// which is looked up when it's the selected child of its parent.
+    fn get_value(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    /// Returns true if only one of the widget's children can be selected at a time.
+    /// Selecting a child deselects the others, and the arrow keys move the selection
+    /// between them.
+    fn selects_one_child(&self) -> bool {
+        false
+    }
+
+    /// Returns true if the widget can be given keyboard focus,
+    /// by clicking on it or tabbing to it.
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Tells the widget whether it has keyboard focus.
+    fn set_focused(&mut self, _focused: bool) {}
+
+    /// Handles a key being pressed or released while the widget has focus.
+    /// Returns true if the widget used the key, in which case it's tessellated again.
+    fn key_input(&mut self, _event: &GUIKeyEvent) -> bool {
+        false
+    }
+
+    /// Handles a character being typed while the widget has focus.
+    /// Returns true if the widget used the character, in which case it's tessellated again.
+    fn character_input(&mut self, _character: char) -> bool {
+        false
+    }
+
+    /// Handles the left mouse button being pressed on the widget, and the pointer
+    /// moving and the button being released afterwards.
+    /// Returns true if the widget changed, in which case it's tessellated again.
+    fn pointer_input(&mut self, _event: &GUIPointerEvent) -> bool {
+        false
+    }
+
+    /// Returns true, once, if the widget's value has changed since this was last called,
+    /// such as a slider being dragged. It's checked after the widget uses a key or the pointer,
+    /// and its change callback is run if it returns true.
+    fn take_changed(&mut self) -> bool {
+        false
+    }
+
+    /// Lets the widget measure its text with the font that it's drawn in,
+    /// so that it can work out where things like a caret go.
+    /// It's called before the widget is tessellated.
+    fn measure_text(&mut self, _font: &GUIFont) {}
+
+    /// Advances any animation that the widget is running to now.
+    /// Returns when the widget next needs to be drawn, or None if it isn't animating.
+    /// Widgets that return Some are tessellated again, so an animation should
+    /// keep returning Some until its last frame has been drawn.
+    fn animate(&mut self, _now: Instant) -> Option<Instant> {
+        None
+    }
 
     fn get_size(&self) -> &GUISize;
 
     fn get_position(&self) -> &GUIPosition;
 
+    fn set_size(&mut self, size: GUISize);
+
+    /// Sets the position of the widget relative to its parent.
+    fn set_position(&mut self, position: GUIPosition);
+
+    /// Returns where the widget's children go, relative to the widget, in the same
+    /// order as children. Returns None if the children position themselves.
+    fn arrange_children(
+        &self,
+        _children: &[GUILayoutChild],
+    ) -> Option<Vec<(GUIPosition, GUISize)>> {
+        None
+    }
+
+    /// Returns where, relative to the widget's top left corner, the area that its children
+    /// are drawn and clicked within is, and its size, or None if they aren't clipped.
+    fn get_viewport(&self) -> Option<(GUIPosition, GUISize)> {
+        None
+    }
+
+    /// Tells a widget that clips its children how much room its children take up,
+    /// once they've been arranged. Returns true if that changed the widget,
+    /// in which case its children are arranged again.
+    fn set_content_size(&mut self, _size: GUISize) -> bool {
+        false
+    }
+
+    /// Returns how far the widget's children are scrolled, which moves them
+    /// up and to the left.
+    fn get_scroll_offset(&self) -> GUIPosition {
+        GUIPosition::default()
+    }
+
+    /// Scrolls the widget's children to offset, kept within how far they can scroll.
+    /// Returns true if they moved, in which case they're tessellated again.
+    fn set_scroll_offset(&mut self, _offset: GUIPosition) -> bool {
+        false
+    }
+
+    /// Returns the cells that can be seen, for a widget such as a list view whose children
+    /// are made and filled in by the gui as they're scrolled into view.
+    fn get_visible_cells(&self) -> Vec<GUICell> {
+        Vec::new()
+    }
+
     fn get_id(&self) -> &u128;
 
     fn as_any(&self) -> &dyn Any;
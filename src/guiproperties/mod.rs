@@ -4,3 +4,16 @@ pub mod guiposition;
 
 mod guicolor;
 pub use guicolor::GUIColor;
+
+mod guifont;
+pub use guifont::GUIFont;
+
+pub mod guilayout;
+
+pub mod guitext;
+
+pub mod guikeys;
+
+pub mod guipointer;
+
+pub mod guitextbuffer;
@@ -3,7 +3,7 @@ use std::env;
 use wgpu::{Backends, PowerPreference};
 
 /// Represents the backends that gui-rs will use.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GUIBackend {
     /// Supported on Windows, Linux/Android, and macOS/iOS via Vulkan Portability (with the Vulkan feature enabled)
     Vulkan,
@@ -20,15 +20,35 @@ pub enum GUIBackend {
 }
 
 impl Default for GUIBackend {
-    /// Returns an array of Backends in the order in which they
-    /// should be tested for compatability with the Adapter.
+    /// Returns the backend that's tried first on this OS.
     fn default() -> Self {
+        GUIBackend::defaults()[0]
+    }
+}
+
+impl GUIBackend {
+    /// Returns the backends in the order in which they
+    /// should be tested for compatability with the Adapter on this OS.
+    pub fn defaults() -> Vec<Self> {
         //https://doc.rust-lang.org/std/env/consts/constant.OS.html
         match env::consts::OS {
-            "linux" | "android" => Self::Vulkan,
-            "macos" | "ios" => Self::Metal,
-            "windows" => Self::Dx12,
-            _ => Self::Vulkan,
+            "linux" | "android" => vec![Self::Vulkan, Self::Gl],
+            "macos" | "ios" => vec![Self::Metal, Self::Vulkan],
+            "windows" => vec![Self::Dx12, Self::Vulkan, Self::Dx11, Self::Gl],
+            _ => vec![Self::Vulkan, Self::Gl],
+        }
+    }
+
+    /// Returns the wgpu Backends that corrisponds to this GUIBackend.
+    pub fn to_backends(self) -> Backends {
+        use GUIBackend::*;
+        match self {
+            Vulkan => Backends::VULKAN,
+            Metal => Backends::METAL,
+            Dx12 => Backends::DX12,
+            Dx11 => Backends::DX11,
+            Gl => Backends::GL,
+            BrowserWebGpu => Backends::BROWSER_WEBGPU,
         }
     }
 }
@@ -47,24 +67,67 @@ impl Default for GUIPowerPreference {
     }
 }
 
+/// How the widgets are turned into pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GUIRasterizer {
+    /// Draws with wgpu on the adapter.
+    #[default]
+    Gpu,
+    /// Draws on the cpu, for machines such as VMs, remote desktops and containers
+    /// that don't have a working gpu driver.
+    /// On X11 the finished frames are copied straight onto the windows, so no adapter is
+    /// needed. Elsewhere they're shown through wgpu, which any adapter can do,
+    /// including a fallback one.
+    Software,
+}
+
 /// Represents the the resourcess that are to be used while processing the gui.
 pub struct GUIResources {
     /// Power Preference when choosing a physical adapter.
     power_preference: GUIPowerPreference,
-    /// Represents the backends that gui-rs will use.
-    backend: GUIBackend,
+    /// The backends that gui-rs will try, in order, until one has a suitable adapter.
+    backends: Vec<GUIBackend>,
+    /// Whether to only use a software adapter, such as llvmpipe or WARP.
+    force_fallback_adapter: bool,
+    /// How the widgets are turned into pixels.
+    rasterizer: GUIRasterizer,
 }
 
 impl Default for GUIResources {
     fn default() -> Self {
         GUIResources {
             power_preference: GUIPowerPreference::default(),
-            backend: GUIBackend::default(),
+            backends: GUIBackend::defaults(),
+            force_fallback_adapter: false,
+            rasterizer: GUIRasterizer::default(),
         }
     }
 }
 
 impl GUIResources {
+    /// Returns these resources, set to try backends in order.
+    pub fn with_backends(mut self, backends: Vec<GUIBackend>) -> Self {
+        self.backends = backends;
+        self
+    }
+
+    /// Returns these resources, set to only use a software adapter if force_fallback_adapter is true.
+    pub fn with_force_fallback_adapter(mut self, force_fallback_adapter: bool) -> Self {
+        self.force_fallback_adapter = force_fallback_adapter;
+        self
+    }
+
+    /// Returns these resources, set to draw with rasterizer.
+    pub fn with_rasterizer(mut self, rasterizer: GUIRasterizer) -> Self {
+        self.rasterizer = rasterizer;
+        self
+    }
+
+    /// Returns how the widgets are turned into pixels.
+    pub fn rasterizer(&self) -> GUIRasterizer {
+        self.rasterizer
+    }
+
     /// Returns the wgpu PowerPreference that corrisponds to the
     /// previously selected GUIPowerPreference
     pub fn power_preference(&self) -> PowerPreference {
@@ -73,17 +136,51 @@ impl GUIResources {
             GUIPowerPreference::HighPerformance => PowerPreference::HighPerformance,
         }
     }
-    /// Returns the wgpu Backend that corrisponds to the
-    /// previously selected GUIBackend
-    pub fn backend(&self) -> Backends {
+    /// Returns the wgpu Backends that corrispond to the
+    /// previously selected GUIBackends, in the order they're to be tried.
+    pub fn backends(&self) -> Vec<Backends> {
+        self.backends
+            .iter()
+            .map(|backend| backend.to_backends())
+            .collect()
+    }
+
+    /// Returns whether only a software adapter is to be used.
+    pub fn force_fallback_adapter(&self) -> bool {
+        self.force_fallback_adapter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_tried_in_order_for_this_os() {
         use GUIBackend::*;
-        match self.backend {
-            Vulkan => Backends::VULKAN,
-            Metal => Backends::METAL,
-            Dx12 => Backends::DX12,
-            Dx11 => Backends::DX11,
-            Gl => Backends::GL,
-            BrowserWebGpu => Backends::BROWSER_WEBGPU,
+        let defaults = GUIBackend::defaults();
+        assert_eq!(GUIBackend::default(), defaults[0]);
+        if cfg!(target_os = "linux") {
+            assert_eq!(defaults, vec![Vulkan, Gl]);
+        } else if cfg!(target_os = "macos") {
+            assert_eq!(defaults, vec![Metal, Vulkan]);
+        } else if cfg!(target_os = "windows") {
+            assert_eq!(defaults, vec![Dx12, Vulkan, Dx11, Gl]);
         }
+        let backends: Vec<Backends> = defaults
+            .iter()
+            .map(|backend| backend.to_backends())
+            .collect();
+        assert_eq!(GUIResources::default().backends(), backends);
+    }
+
+    #[test]
+    fn backends_keep_the_order_they_are_given_in() {
+        let guiresources =
+            GUIResources::default().with_backends(vec![GUIBackend::Gl, GUIBackend::Vulkan]);
+        assert_eq!(
+            guiresources.backends(),
+            vec![Backends::GL, Backends::VULKAN]
+        );
     }
 }
@@ -1,8 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 // use super::super::guiproperties::Widget;
 use super::GUIWindow;
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent, GUIModifiers};
+use crate::guiproperties::guilayout::{GUICell, GUILayoutChild, GUILayoutItem};
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
 use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::{GUIColor, GUIFont};
+
+/// A callback that's run in response to an event, such as a widget being clicked.
+/// It's given the GUIBase so that it can change the widget tree.
+pub type GUIHandler = Box<dyn FnMut(&mut GUIBase)>;
+
+/// A callback that's run with a row of data in response to an event, such as the value
+/// of a checkbox in a table changing.
+pub type GUIRowHandler = Box<dyn FnMut(&mut GUIBase, usize)>;
+
+/// Fills in a cell widget for a row of data.
+type CellFiller = Box<dyn FnMut(&mut dyn Widget, usize)>;
+
+/// Makes and fills in the widgets of a column of cells in a list view or a table.
+struct CellBuilder {
+    /// Makes a new, empty cell widget.
+    make: Box<dyn Fn() -> Box<dyn Widget>>,
+    /// Fills in a cell widget for a row of data.
+    build: CellFiller,
+}
 
 pub struct GUIBase {
     /// The id number of the base window.
@@ -20,6 +45,39 @@ pub struct GUIBase {
     /// If false, resizing the window changes the size
     /// of the window contents.
     pub fixed_scale: bool,
+    /// The font that all text is drawn with.
+    pub font: GUIFont,
+    /// The color of the ring that's drawn around the widget with keyboard focus.
+    pub focus_ring_color: GUIColor,
+    /// How thick the focus ring is.
+    pub focus_ring_width: GUILength,
+    /// The widget that key events go to.
+    focused_widget: Option<u128>,
+    /// The callbacks to run when a widget is clicked, by widget id.
+    click_handlers: HashMap<u128, GUIHandler>,
+    /// The callbacks to run when a widget's value changes, by widget id.
+    change_handlers: HashMap<u128, GUIHandler>,
+    /// The callbacks that fill in the cell widgets of list views and tables,
+    /// by column, by the id of the list view or table.
+    cell_builders: HashMap<u128, HashMap<usize, CellBuilder>>,
+    /// The callbacks to run when the value of a cell's widget changes,
+    /// by column, by the id of the list view or table.
+    cell_change_handlers: HashMap<u128, HashMap<usize, GUIRowHandler>>,
+    /// The widgets of the cells that can be seen in list views and tables,
+    /// by row and column, by the id of the list view or table.
+    cell_ids: HashMap<u128, BTreeMap<(usize, usize), u128>>,
+    /// The widgets that need to be tessellated again before they're next drawn.
+    dirty_widgets: HashSet<u128>,
+    /// Whether anything in the tree has changed since it was last drawn.
+    changed: bool,
+    /// Whether a redraw has been asked for even though nothing has changed.
+    redraw_requested: bool,
+    /// The earliest time that a redraw has been asked for.
+    redraw_at: Option<Instant>,
+    /// Callbacks to run once at a given time, in the order they were added.
+    timers: Vec<(Instant, GUIHandler)>,
+    /// Whether the gui has been asked to close.
+    exit_requested: bool,
 }
 
 impl GUIBase {
@@ -30,6 +88,26 @@ impl GUIBase {
             widgets: HashMap::new(),
             logical_scale: None,
             fixed_scale: true,
+            font: GUIFont::default(),
+            focus_ring_color: GUIColor {
+                r: 0.1,
+                g: 0.4,
+                b: 0.9,
+                a: 1.0,
+            },
+            focus_ring_width: GUILength::from_pixels(2.),
+            focused_widget: None,
+            click_handlers: HashMap::new(),
+            change_handlers: HashMap::new(),
+            cell_builders: HashMap::new(),
+            cell_change_handlers: HashMap::new(),
+            cell_ids: HashMap::new(),
+            dirty_widgets: HashSet::new(),
+            changed: true,
+            redraw_requested: false,
+            redraw_at: None,
+            timers: Vec::new(),
+            exit_requested: false,
         }
     }
 
@@ -38,9 +116,22 @@ impl GUIBase {
     }
 
     pub fn get_base_window_mut(&mut self) -> &mut GUIWindow {
+        self.changed = true;
         &mut self.windows.get_mut(&self.base_window).unwrap().window
     }
 
+    /// Returns the window with the given id.
+    pub fn get_window(&self, id: u128) -> &GUIWindow {
+        &self.windows.get(&id).unwrap().window
+    }
+
+    /// Returns the window with the given id, marking the tree as changed
+    /// since the window may be changed.
+    pub fn get_window_mut(&mut self, id: u128) -> &mut GUIWindow {
+        self.changed = true;
+        &mut self.windows.get_mut(&id).unwrap().window
+    }
+
     pub fn add_window(&mut self, window: GUIWindow) -> u128 {
         if self.windows.len() == 0 {
             self.base_window = *window.get_id();
@@ -53,6 +144,7 @@ impl GUIBase {
             children: Vec::new(),
         };
         self.windows.insert(window_id, gwindow);
+        self.changed = true;
 
         window_id
     }
@@ -61,13 +153,59 @@ impl GUIBase {
         &self.widgets.get(&id).unwrap().widget
     }
 
+    /// Returns the widget with the given id, marking it as dirty
+    /// since it may be changed.
+    pub fn get_widget_mut(&mut self, id: u128) -> &mut Box<dyn Widget> {
+        self.mark_dirty(id);
+        &mut self.widgets.get_mut(&id).unwrap().widget
+    }
+
+    /// Returns the widget with the given id if it exists and is of type T.
+    pub fn get_widget_as<T: 'static + Widget>(&self, id: u128) -> Option<&T> {
+        self.widgets.get(&id)?.widget.as_any().downcast_ref::<T>()
+    }
+
+    /// Returns the widget with the given id if it exists and is of type T,
+    /// marking it as dirty since it may be changed.
+    pub fn get_widget_as_mut<T: 'static + Widget>(&mut self, id: u128) -> Option<&mut T> {
+        self.mark_dirty(id);
+        self.widgets
+            .get_mut(&id)?
+            .widget
+            .as_any_mut()
+            .downcast_mut::<T>()
+    }
+
     pub fn add_child_to_parent<T: 'static + Widget>(&mut self, child: T, parent_id: u128) -> u128 {
+        self.add_child_to_parent_with_layout(child, parent_id, GUILayoutItem::default())
+    }
+
+    /// Adds a child to a parent, along with how the parent should treat the child
+    /// if the parent arranges its children.
+    pub fn add_child_to_parent_with_layout<T: 'static + Widget>(
+        &mut self,
+        child: T,
+        parent_id: u128,
+        layout: GUILayoutItem,
+    ) -> u128 {
+        self.add_boxed_child(Box::new(child), parent_id, layout)
+    }
+
+    fn add_boxed_child(
+        &mut self,
+        child: Box<dyn Widget>,
+        parent_id: u128,
+        layout: GUILayoutItem,
+    ) -> u128 {
         let child_id = *child.get_id();
 
         let gwidget = GWidget {
-            widget: Box::new(child),
+            preferred_size: *child.get_size(),
+            widget: child,
             parent: parent_id,
             children: Vec::new(),
+            layout,
+            tab_index: None,
         };
 
         if self.windows.contains_key(&parent_id) {
@@ -81,9 +219,1014 @@ impl GUIBase {
         }
 
         self.widgets.insert(child_id, gwidget);
+        self.mark_dirty(child_id);
 
         child_id
     }
+
+    /// Changes how a widget is treated by the container that it's in.
+    pub fn set_layout_item(&mut self, widget_id: u128, layout: GUILayoutItem) {
+        self.widgets.get_mut(&widget_id).unwrap().layout = layout;
+    }
+
+    /// Sets where a widget comes when tabbing between widgets.
+    /// Widgets with a tab index come first, lowest first,
+    /// followed by the widgets without one in the order they're in the tree.
+    pub fn set_tab_index(&mut self, widget_id: u128, tab_index: Option<u32>) {
+        self.widgets.get_mut(&widget_id).unwrap().tab_index = tab_index;
+    }
+
+    /// Changes the size that a widget would like to be, and resizes it.
+    /// Containers grow and shrink widgets from this size.
+    pub fn set_preferred_size(&mut self, widget_id: u128, size: GUISize) {
+        let gwidget = self.widgets.get_mut(&widget_id).unwrap();
+        gwidget.preferred_size = size;
+        gwidget.widget.set_size(size);
+        self.mark_dirty(widget_id);
+    }
+
+    /// Marks a widget and its descendants as needing to be tessellated again.
+    /// Widgets that are changed through get_widget_mut or get_widget_as_mut are
+    /// marked automatically, but ones that are changed through the widgets map aren't.
+    pub fn mark_dirty(&mut self, widget_id: u128) {
+        let gwidget = match self.widgets.get(&widget_id) {
+            Some(gwidget) => gwidget,
+            None => return,
+        };
+        let child_ids = gwidget.children.clone();
+        self.dirty_widgets.insert(widget_id);
+        self.changed = true;
+        for child_id in child_ids {
+            self.mark_dirty(child_id);
+        }
+    }
+
+    /// Marks every widget as needing to be tessellated again,
+    /// such as after the font has been changed.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty_widgets.extend(self.widgets.keys());
+        self.changed = true;
+    }
+
+    /// Returns whether anything in the tree has changed since it was last drawn.
+    pub fn has_changes(&self) -> bool {
+        self.changed
+    }
+
+    /// Returns the widgets that need to be tessellated again and clears them,
+    /// or None if nothing has changed since the last time this was called.
+    /// The widgets get to measure their text first.
+    pub(crate) fn take_dirty_widgets(&mut self) -> Option<HashSet<u128>> {
+        // List views and tables that have been scrolled or changed fill in their cells first.
+        let widget_ids: Vec<u128> = self
+            .cell_builders
+            .keys()
+            .filter(|widget_id| self.dirty_widgets.contains(widget_id))
+            .copied()
+            .collect();
+        for widget_id in widget_ids {
+            self.update_cells(widget_id, false);
+        }
+        if !self.changed {
+            return None;
+        }
+        self.changed = false;
+        let dirty_widgets = std::mem::take(&mut self.dirty_widgets);
+        for widget_id in dirty_widgets.iter() {
+            if let Some(gwidget) = self.widgets.get_mut(widget_id) {
+                gwidget.widget.measure_text(&self.font);
+            }
+        }
+        Some(dirty_widgets)
+    }
+
+    /// Positions and sizes the children of every widget that arranges its children,
+    /// and stretches widgets that fill parents that don't.
+    pub fn layout(&mut self) {
+        let window_ids: Vec<u128> = self.windows.keys().copied().collect();
+        for window_id in window_ids {
+            let gwindow = &self.windows[&window_id];
+            let size = gwindow.window.size;
+            let child_ids = gwindow.children.clone();
+            self.fill_children(&child_ids, size);
+            for child_id in child_ids {
+                self.layout_widget(child_id);
+            }
+        }
+    }
+
+    fn layout_widget(&mut self, widget_id: u128) {
+        self.update_cells(widget_id, false);
+        let child_ids = self.widgets[&widget_id].children.clone();
+        self.arrange_children(widget_id, &child_ids);
+        // A widget that clips its children may need to arrange them again once it knows
+        // how much room they take up, such as to make space for scrollbars.
+        if self.measure_content(widget_id, &child_ids) {
+            self.arrange_children(widget_id, &child_ids);
+        }
+
+        for child_id in child_ids {
+            self.layout_widget(child_id);
+        }
+    }
+
+    fn arrange_children(&mut self, widget_id: u128, child_ids: &[u128]) {
+        let gwidget = &self.widgets[&widget_id];
+        let children: Vec<GUILayoutChild> = child_ids
+            .iter()
+            .map(|child_id| {
+                let child = &self.widgets[child_id];
+                GUILayoutChild {
+                    preferred_size: child.preferred_size,
+                    item: child.layout,
+                }
+            })
+            .collect();
+
+        match gwidget.widget.arrange_children(&children) {
+            Some(areas) => {
+                for (child_id, (position, size)) in child_ids.iter().zip(areas) {
+                    self.place_widget(*child_id, position, size);
+                }
+            }
+            None => {
+                let size = *gwidget.widget.get_size();
+                self.fill_children(child_ids, size);
+            }
+        }
+    }
+
+    /// Tells a widget that clips its children how far they reach, from its top left corner.
+    /// Returns true if that changed the widget.
+    fn measure_content(&mut self, widget_id: u128, child_ids: &[u128]) -> bool {
+        if self.widgets[&widget_id].widget.get_viewport().is_none() {
+            return false;
+        }
+        let (width, height) = child_ids
+            .iter()
+            .map(|child_id| {
+                let child = &self.widgets[child_id].widget;
+                (
+                    child.get_position().x.add(&child.get_size().width).get_length(),
+                    child.get_position().y.add(&child.get_size().height).get_length(),
+                )
+            })
+            .fold((0_f64, 0_f64), |(width, height), (right, bottom)| {
+                (width.max(right), height.max(bottom))
+            });
+        let widget = &mut self.widgets.get_mut(&widget_id).unwrap().widget;
+        let changed = widget.set_content_size(GUISize::from_pixels(width, height));
+        if changed {
+            self.mark_dirty(widget_id);
+        }
+        changed
+    }
+
+    fn fill_children(&mut self, child_ids: &[u128], size: GUISize) {
+        for child_id in child_ids {
+            if self.widgets[child_id].layout.fill {
+                self.place_widget(*child_id, GUIPosition::default(), size);
+            }
+        }
+    }
+
+    /// Moves and resizes a widget, marking it as dirty if it actually changed.
+    fn place_widget(&mut self, widget_id: u128, position: GUIPosition, size: GUISize) {
+        let widget = &mut self.widgets.get_mut(&widget_id).unwrap().widget;
+        if *widget.get_position() == position && *widget.get_size() == size {
+            return;
+        }
+        widget.set_position(position);
+        widget.set_size(size);
+        self.mark_dirty(widget_id);
+    }
+
+    /// Removes a widget and all of its descendants from the tree,
+    /// along with their callbacks.
+    pub fn remove_widget(&mut self, id: u128) {
+        let gwidget = match self.widgets.remove(&id) {
+            Some(gwidget) => gwidget,
+            None => return,
+        };
+        if let Some(window) = self.windows.get_mut(&gwidget.parent) {
+            window.children.retain(|child_id| *child_id != id);
+        } else if let Some(parent) = self.widgets.get_mut(&gwidget.parent) {
+            parent.children.retain(|child_id| *child_id != id);
+        }
+        self.click_handlers.remove(&id);
+        self.change_handlers.remove(&id);
+        self.cell_builders.remove(&id);
+        self.cell_change_handlers.remove(&id);
+        self.cell_ids.remove(&id);
+        if self.focused_widget == Some(id) {
+            self.focused_widget = None;
+        }
+        self.changed = true;
+
+        for child_id in gwidget.children {
+            self.remove_widget(child_id);
+        }
+    }
+
+    /// Removes a window along with all of its widgets, which closes it.
+    /// The base window can't be removed.
+    pub fn remove_window(&mut self, id: u128) {
+        if id == self.base_window {
+            return;
+        }
+        if let Some(gwindow) = self.windows.remove(&id) {
+            for child_id in gwindow.children {
+                self.remove_widget(child_id);
+            }
+            self.changed = true;
+        }
+    }
+
+    /// Returns the window that a widget is in, if the widget exists.
+    pub fn get_window_id_of(&self, widget_id: u128) -> Option<u128> {
+        let mut id = widget_id;
+        loop {
+            if self.windows.contains_key(&id) {
+                return Some(id);
+            }
+            id = self.widgets.get(&id)?.parent;
+        }
+    }
+
+    /// Returns where a widget is within its window.
+    pub fn get_absolute_position(&self, widget_id: u128) -> GUIPosition {
+        let gwidget = &self.widgets[&widget_id];
+        let parent_position = match self.windows.get(&gwidget.parent) {
+            Some(gwindow) => *gwindow.window.get_position(),
+            None => {
+                // Scrolling a parent moves its children back.
+                let parent_position = self.get_absolute_position(gwidget.parent);
+                let offset = self.widgets[&gwidget.parent].widget.get_scroll_offset();
+                GUIPosition::from_lengths(
+                    parent_position.x.subtract(&offset.x),
+                    parent_position.y.subtract(&offset.y),
+                )
+            }
+        };
+        let position = gwidget.widget.get_position();
+        GUIPosition::from_lengths(
+            parent_position.x.add(&position.x),
+            parent_position.y.add(&position.y),
+        )
+    }
+
+    /// Returns the ids of a window's widgets in the order that they're drawn,
+    /// with each widget before its children.
+    pub fn get_widget_ids_in_order(&self, window_id: u128) -> Vec<u128> {
+        let mut widget_ids = Vec::new();
+        let mut stack: Vec<u128> = Vec::new();
+        if let Some(gwindow) = self.windows.get(&window_id) {
+            stack.extend(gwindow.children.iter().rev());
+        }
+        while let Some(widget_id) = stack.pop() {
+            widget_ids.push(widget_id);
+            stack.extend(self.widgets[&widget_id].children.iter().rev());
+        }
+        widget_ids
+    }
+
+    /// Returns the ids of a window's focusable widgets in the order that Tab moves through them.
+    pub fn get_focus_order(&self, window_id: u128) -> Vec<u128> {
+        let mut widget_ids: Vec<u128> = self
+            .get_widget_ids_in_order(window_id)
+            .into_iter()
+            .filter(|widget_id| {
+                self.widgets[widget_id].widget.is_focusable() && self.is_tab_stop(*widget_id)
+            })
+            .collect();
+        // The sort is stable, so widgets with the same tab index stay in tree order.
+        widget_ids.sort_by_key(|widget_id| match self.widgets[widget_id].tab_index {
+            Some(tab_index) => (0, tab_index),
+            None => (1, 0),
+        });
+        widget_ids
+    }
+
+    /// Returns false for the children of a widget that only lets one of them be selected,
+    /// other than the one that Tab lands on, so that a group like radio buttons is a single
+    /// Tab stop. That's the child with focus if there is one, and otherwise the selected
+    /// child, or the first that can be focused if none of them are selected.
+    fn is_tab_stop(&self, widget_id: u128) -> bool {
+        let siblings = match self.widgets.get(&self.widgets[&widget_id].parent) {
+            Some(parent) if parent.widget.selects_one_child() => &parent.children,
+            _ => return true,
+        };
+        let focusable = || {
+            siblings
+                .iter()
+                .copied()
+                .filter(|sibling_id| self.widgets[sibling_id].widget.is_focusable())
+        };
+        let stop = focusable()
+            .find(|sibling_id| Some(*sibling_id) == self.focused_widget)
+            .or_else(|| {
+                focusable().find(|sibling_id| self.widgets[sibling_id].widget.is_selected())
+            })
+            .or_else(|| focusable().next());
+        stop == Some(widget_id)
+    }
+
+    /// Returns the widget that has keyboard focus, if any.
+    pub fn get_focused_widget(&self) -> Option<u128> {
+        self.focused_widget
+    }
+
+    /// Gives a widget keyboard focus, or takes focus away from every widget if widget_id is None.
+    /// Returns false, and leaves the focus where it was, if the widget can't be focused.
+    pub fn set_focus(&mut self, widget_id: Option<u128>) -> bool {
+        if let Some(id) = widget_id {
+            match self.widgets.get(&id) {
+                Some(gwidget) if gwidget.widget.is_focusable() => {}
+                _ => return false,
+            }
+        }
+        if widget_id == self.focused_widget {
+            return true;
+        }
+        if let Some(id) = self.focused_widget.take() {
+            if let Some(gwidget) = self.widgets.get_mut(&id) {
+                gwidget.widget.set_focused(false);
+                self.mark_dirty(id);
+            }
+        }
+        if let Some(id) = widget_id {
+            self.widgets.get_mut(&id).unwrap().widget.set_focused(true);
+            self.mark_dirty(id);
+            self.scroll_into_view(id);
+        }
+        self.focused_widget = widget_id;
+        // The focus ring has moved even if neither widget looks different.
+        self.changed = true;
+        true
+    }
+
+    /// Moves keyboard focus to the next widget in a window's focus order,
+    /// wrapping around to the first.
+    pub fn focus_next(&mut self, window_id: u128) {
+        let order = self.get_focus_order(window_id);
+        let next = match self.focused_position(&order) {
+            Some(position) => order.get((position + 1) % order.len()),
+            None => order.first(),
+        };
+        if let Some(widget_id) = next.copied() {
+            self.set_focus(Some(widget_id));
+        }
+    }
+
+    /// Moves keyboard focus to the previous widget in a window's focus order,
+    /// wrapping around to the last.
+    pub fn focus_previous(&mut self, window_id: u128) {
+        let order = self.get_focus_order(window_id);
+        let previous = match self.focused_position(&order) {
+            Some(position) => order.get((position + order.len() - 1) % order.len()),
+            None => order.last(),
+        };
+        if let Some(widget_id) = previous.copied() {
+            self.set_focus(Some(widget_id));
+        }
+    }
+
+    fn focused_position(&self, order: &[u128]) -> Option<usize> {
+        let focused_widget = self.focused_widget?;
+        order
+            .iter()
+            .position(|widget_id| *widget_id == focused_widget)
+    }
+
+    /// Handles a key event in a window. Keys go to the focused widget first,
+    /// and if it doesn't use them the arrow keys move the selection within a group
+    /// like radio buttons, Tab and Shift+Tab move the focus and Enter and Space click it.
+    /// Ctrl+Tab and Ctrl+Shift+Tab move the focus as well, which is the way out of widgets
+    /// that use Tab themselves, such as text areas.
+    /// Returns true if the key was used.
+    pub fn key_input(&mut self, window_id: u128, event: &GUIKeyEvent) -> bool {
+        let widget_id = match self.focused_widget {
+            Some(id) if self.get_window_id_of(id) == Some(window_id) => Some(id),
+            _ => None,
+        };
+        if let Some(widget_id) = widget_id {
+            if self
+                .widgets
+                .get_mut(&widget_id)
+                .unwrap()
+                .widget
+                .key_input(event)
+            {
+                self.input_used(widget_id);
+                return true;
+            }
+        }
+
+        // The arrow keys move the selection within a group like radio buttons.
+        if let (Some(widget_id), true) = (widget_id, event.pressed) {
+            let forwards = match event.key {
+                GUIKey::Down | GUIKey::Right => Some(true),
+                GUIKey::Up | GUIKey::Left => Some(false),
+                _ => None,
+            };
+            if let Some(sibling_id) =
+                forwards.and_then(|forwards| self.get_selectable_sibling(widget_id, forwards))
+            {
+                self.set_focus(Some(sibling_id));
+                self.click(sibling_id);
+                return true;
+            }
+        }
+
+        let modifiers = event.modifiers;
+        if event.key == GUIKey::Tab && !modifiers.alt && !modifiers.logo {
+            if event.pressed {
+                if modifiers.shift {
+                    self.focus_previous(window_id);
+                } else {
+                    self.focus_next(window_id);
+                }
+            }
+            return true;
+        }
+
+        match widget_id {
+            Some(widget_id)
+                if event.pressed
+                    && matches!(event.key, GUIKey::Enter | GUIKey::Space)
+                    && self.widgets[&widget_id].widget.is_enabled() =>
+            {
+                self.click(widget_id)
+            }
+            _ => false,
+        }
+    }
+
+    /// Sends a typed character to the focused widget, if it's in the window.
+    /// Returns true if the character was used.
+    pub fn character_input(&mut self, window_id: u128, character: char) -> bool {
+        let widget_id = match self.focused_widget {
+            Some(id) if self.get_window_id_of(id) == Some(window_id) => id,
+            _ => return false,
+        };
+        let used = self
+            .widgets
+            .get_mut(&widget_id)
+            .unwrap()
+            .widget
+            .character_input(character);
+        if used {
+            self.input_used(widget_id);
+        }
+        used
+    }
+
+    /// Sends the left mouse button being used to a widget.
+    /// position is where the pointer is within the widget's window.
+    /// Returns true if the widget changed.
+    pub fn pointer_input(
+        &mut self,
+        widget_id: u128,
+        kind: GUIPointerEventKind,
+        position: &GUIPosition,
+        modifiers: GUIModifiers,
+    ) -> bool {
+        if !self.widgets.contains_key(&widget_id) {
+            return false;
+        }
+        let origin = self.get_absolute_position(widget_id);
+        let event = GUIPointerEvent {
+            kind,
+            position: GUIPosition::from_lengths(
+                position.x.subtract(&origin.x),
+                position.y.subtract(&origin.y),
+            ),
+            modifiers,
+        };
+        let used = self
+            .widgets
+            .get_mut(&widget_id)
+            .unwrap()
+            .widget
+            .pointer_input(&event);
+        if used {
+            self.input_used(widget_id);
+        }
+        used
+    }
+
+    /// Marks a widget that's used a key or the pointer to be tessellated again,
+    /// and runs its change callback if that changed its value.
+    fn input_used(&mut self, widget_id: u128) {
+        self.mark_dirty(widget_id);
+        if self.widgets.get_mut(&widget_id).unwrap().widget.take_changed() {
+            self.notify_change(widget_id);
+        }
+    }
+
+    /// Handles the mouse wheel or a trackpad scrolling over a widget, where delta is how far
+    /// to scroll right and down. The widget scrolls if it can, and otherwise the nearest
+    /// of its parents that can still scroll that way does.
+    /// Returns true if anything scrolled.
+    pub fn scroll_input(&mut self, widget_id: u128, delta: &GUIPosition) -> bool {
+        let mut id = widget_id;
+        while let Some(gwidget) = self.widgets.get_mut(&id) {
+            let offset = gwidget.widget.get_scroll_offset();
+            if gwidget.widget.set_scroll_offset(GUIPosition::from_lengths(
+                offset.x.add(&delta.x),
+                offset.y.add(&delta.y),
+            )) {
+                self.mark_dirty(id);
+                return true;
+            }
+            id = gwidget.parent;
+        }
+        false
+    }
+
+    /// Scrolls the parents of a widget, as little as they need to be,
+    /// so that as much of the widget as possible can be seen.
+    pub fn scroll_into_view(&mut self, widget_id: u128) {
+        let gwidget = match self.widgets.get(&widget_id) {
+            Some(gwidget) => gwidget,
+            None => return,
+        };
+        // The widget's area within its parent's children, before they're scrolled.
+        let position = gwidget.widget.get_position();
+        let size = gwidget.widget.get_size();
+        let (mut left, mut top) = (position.x.get_length(), position.y.get_length());
+        let (width, height) = (size.width.get_length(), size.height.get_length());
+
+        let mut id = widget_id;
+        while let Some(parent_id) = self.widgets.get(&id).map(|gwidget| gwidget.parent) {
+            let parent = match self.widgets.get_mut(&parent_id) {
+                Some(parent) => &mut parent.widget,
+                None => break,
+            };
+            if let Some((origin, viewport)) = parent.get_viewport() {
+                let offset = parent.get_scroll_offset();
+                let scrolled = parent.set_scroll_offset(GUIPosition::from_pixels(
+                    scroll_to_show(
+                        left - origin.x.get_length(),
+                        width,
+                        offset.x.get_length(),
+                        viewport.width.get_length(),
+                    ),
+                    scroll_to_show(
+                        top - origin.y.get_length(),
+                        height,
+                        offset.y.get_length(),
+                        viewport.height.get_length(),
+                    ),
+                ));
+                if scrolled {
+                    self.mark_dirty(parent_id);
+                }
+            }
+            let parent = &self.widgets[&parent_id].widget;
+            let offset = parent.get_scroll_offset();
+            left += parent.get_position().x.get_length() - offset.x.get_length();
+            top += parent.get_position().y.get_length() - offset.y.get_length();
+            id = parent_id;
+        }
+    }
+
+    /// Sets the callback that's run when the widget with the given id is clicked,
+    /// replacing any previous one.
+    pub fn on_click(&mut self, widget_id: u128, handler: impl FnMut(&mut GUIBase) + 'static) {
+        self.click_handlers.insert(widget_id, Box::new(handler));
+    }
+
+    /// Sets the callback that's run when the value of the widget with the given id
+    /// is changed through the gui, such as a checkbox being ticked, replacing any previous one.
+    pub fn on_change(&mut self, widget_id: u128, handler: impl FnMut(&mut GUIBase) + 'static) {
+        self.change_handlers.insert(widget_id, Box::new(handler));
+    }
+
+    /// Sets the callback that fills in the row widgets of a list view, replacing any previous
+    /// one and its rows. The list view only has row widgets for the rows that can be seen,
+    /// and reuses them for other rows as it's scrolled, so builder may be given a widget that
+    /// showed another row and should set everything about it that depends on the row.
+    pub fn set_row_builder<W: 'static + Widget + Default>(
+        &mut self,
+        list_id: u128,
+        builder: impl FnMut(&mut W, usize) + 'static,
+    ) {
+        self.set_cell_builder(list_id, 0, builder);
+    }
+
+    /// Sets the callback that fills in the cell widgets of a column of a table,
+    /// which works like a list view's row builder.
+    pub fn set_cell_builder<W: 'static + Widget + Default>(
+        &mut self,
+        widget_id: u128,
+        column: usize,
+        mut builder: impl FnMut(&mut W, usize) + 'static,
+    ) {
+        if !self.widgets.contains_key(&widget_id) {
+            return;
+        }
+        // The column's old widgets may be a different kind of widget, so they're replaced.
+        let cell_ids = self.cell_ids.entry(widget_id).or_default();
+        let (old_ids, kept_ids) = std::mem::take(cell_ids)
+            .into_iter()
+            .partition(|((_, cell_column), _)| *cell_column == column);
+        *cell_ids = kept_ids;
+        for (_, cell_id) in old_ids {
+            self.remove_widget(cell_id);
+        }
+
+        self.cell_builders.entry(widget_id).or_default().insert(
+            column,
+            CellBuilder {
+                make: Box::new(|| Box::new(W::default())),
+                build: Box::new(move |widget, row| {
+                    if let Some(widget) = widget.as_any_mut().downcast_mut::<W>() {
+                        builder(widget, row);
+                    }
+                }),
+            },
+        );
+        self.update_cells(widget_id, false);
+    }
+
+    /// Sets the callback that's run with the row of data when the value of a widget in a column
+    /// of a list view or a table changes, such as a checkbox in a cell being ticked,
+    /// replacing any previous one. A list view's rows are in column 0.
+    pub fn on_cell_change(
+        &mut self,
+        widget_id: u128,
+        column: usize,
+        handler: impl FnMut(&mut GUIBase, usize) + 'static,
+    ) {
+        self.cell_change_handlers
+            .entry(widget_id)
+            .or_default()
+            .insert(column, Box::new(handler));
+    }
+
+    /// Fills in all of a list view's or a table's cell widgets again,
+    /// such as after the data that they show has changed.
+    pub fn rebuild_rows(&mut self, widget_id: u128) {
+        self.update_cells(widget_id, true);
+    }
+
+    /// Returns the widget that shows a row of data in a column of a list view or a table,
+    /// if the row can be seen. A list view's rows are in column 0.
+    pub fn get_cell_widget(&self, widget_id: u128, row: usize, column: usize) -> Option<u128> {
+        self.cell_ids.get(&widget_id)?.get(&(row, column)).copied()
+    }
+
+    /// Gives a list view or a table a widget for each cell that can be seen, reusing the
+    /// widgets of cells that can't be seen anymore. Only the widgets that show a different
+    /// row are filled in again, unless rebuild is true, in which case they all are.
+    fn update_cells(&mut self, widget_id: u128, rebuild: bool) {
+        let cells: Vec<GUICell> = match (
+            self.cell_builders.get(&widget_id),
+            self.widgets.get(&widget_id),
+        ) {
+            (Some(builders), Some(gwidget)) => gwidget
+                .widget
+                .get_visible_cells()
+                .into_iter()
+                .filter(|cell| builders.contains_key(&cell.column))
+                .collect(),
+            _ => return,
+        };
+        let wanted: HashSet<(usize, usize)> =
+            cells.iter().map(|cell| (cell.row, cell.column)).collect();
+        let (mut cell_ids, spare): (BTreeMap<_, _>, BTreeMap<_, _>) = self
+            .cell_ids
+            .remove(&widget_id)
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|(cell, _)| wanted.contains(cell));
+        // A cell that's gone out of view is about to show another one, so it can't keep
+        // the focus. The focus goes to the widget that the cells are in instead.
+        if let Some(focused_id) = self.focused_widget {
+            if spare.values().any(|cell_id| *cell_id == focused_id)
+                && !self.set_focus(Some(widget_id))
+            {
+                self.set_focus(None);
+            }
+        }
+        // Widgets are only reused within their column,
+        // since each column can have a different kind of widget.
+        let mut spare_ids: HashMap<usize, Vec<u128>> = HashMap::new();
+        for ((_, column), cell_id) in spare {
+            spare_ids.entry(column).or_default().push(cell_id);
+        }
+
+        let mut child_ids = Vec::with_capacity(cells.len());
+        for cell in cells {
+            let (cell_id, fill) = match cell_ids.get(&(cell.row, cell.column)) {
+                Some(cell_id) => (*cell_id, rebuild),
+                None => match spare_ids.get_mut(&cell.column).and_then(Vec::pop) {
+                    Some(cell_id) => (cell_id, true),
+                    None => {
+                        let widget = (self.cell_builders[&widget_id][&cell.column].make)();
+                        let cell_id =
+                            self.add_boxed_child(widget, widget_id, GUILayoutItem::default());
+                        (cell_id, true)
+                    }
+                },
+            };
+            if fill {
+                let builder = self
+                    .cell_builders
+                    .get_mut(&widget_id)
+                    .and_then(|builders| builders.get_mut(&cell.column))
+                    .unwrap();
+                let widget = self.widgets.get_mut(&cell_id).unwrap().widget.as_mut();
+                (builder.build)(widget, cell.row);
+                self.mark_dirty(cell_id);
+                cell_ids.insert((cell.row, cell.column), cell_id);
+            }
+            self.place_widget(cell_id, cell.position, cell.size);
+            child_ids.push(cell_id);
+        }
+        for cell_id in spare_ids.into_values().flatten() {
+            self.remove_widget(cell_id);
+        }
+
+        // The cells are kept in the order that they're given in, so that tabbing goes
+        // along the rows.
+        self.widgets.get_mut(&widget_id).unwrap().children = child_ids;
+        self.cell_ids.insert(widget_id, cell_ids);
+    }
+
+    /// Asks for the windows to be drawn again, such as after something that the
+    /// widget tree doesn't know about has changed.
+    /// Changes made through GUIBase's methods already ask for a redraw.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    /// Asks for the windows to be drawn again once time has been reached.
+    pub fn request_redraw_at(&mut self, time: Instant) {
+        self.redraw_at = Some(match self.redraw_at {
+            Some(redraw_at) => redraw_at.min(time),
+            None => time,
+        });
+    }
+
+    /// Runs a callback once after delay has passed. The windows are drawn afterwards.
+    pub fn add_timer(&mut self, delay: Duration, handler: impl FnMut(&mut GUIBase) + 'static) {
+        let time = Instant::now() + delay;
+        self.timers.push((time, Box::new(handler)));
+        self.request_redraw_at(time);
+    }
+
+    /// Asks for the gui to close once it's finished handling the current event,
+    /// the same as closing the base window does.
+    /// GUIRunner::run_return then gives the GUIBase back.
+    pub fn exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    /// Returns whether the gui has been asked to close, and clears the request
+    /// so that the GUIBase can be run again.
+    pub(crate) fn take_exit_request(&mut self) -> bool {
+        std::mem::take(&mut self.exit_requested)
+    }
+
+    /// Runs the timers that are due and advances the widgets' animations to now.
+    pub(crate) fn advance(&mut self, now: Instant) {
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|(time, _)| *time <= now);
+        self.timers = waiting;
+        for (_, mut handler) in due {
+            handler(self);
+            self.request_redraw();
+        }
+
+        let mut animating = Vec::new();
+        for (widget_id, gwidget) in self.widgets.iter_mut() {
+            if let Some(time) = gwidget.widget.animate(now) {
+                animating.push((*widget_id, time));
+            }
+        }
+        for (widget_id, time) in animating {
+            self.mark_dirty(widget_id);
+            self.request_redraw_at(time);
+        }
+    }
+
+    /// Returns whether the windows need to be drawn at now,
+    /// and clears any redraw requests that it satisfies.
+    pub(crate) fn take_redraw_request(&mut self, now: Instant) -> bool {
+        let mut needs_redraw = self.changed || self.redraw_requested;
+        self.redraw_requested = false;
+        if matches!(self.redraw_at, Some(time) if time <= now) {
+            self.redraw_at = None;
+            needs_redraw = true;
+        }
+        needs_redraw
+    }
+
+    /// Returns when the next timer or requested redraw is due, if there is one.
+    pub(crate) fn next_wake_time(&self) -> Option<Instant> {
+        self.timers
+            .iter()
+            .map(|(time, _)| *time)
+            .chain(self.redraw_at)
+            .min()
+    }
+
+    /// Clicks a widget, which lets it change its value, such as a checkbox being ticked,
+    /// and then runs its click callback.
+    /// Returns true if the widget changed or had a callback.
+    pub fn click(&mut self, widget_id: u128) -> bool {
+        let changed = match self.widgets.get_mut(&widget_id) {
+            Some(gwidget) => gwidget.widget.activate(),
+            None => false,
+        };
+        if changed {
+            self.mark_dirty(widget_id);
+            self.notify_change(widget_id);
+            if let Some(parent_id) = self.deselect_siblings(widget_id) {
+                self.notify_change(parent_id);
+            }
+        }
+        self.run_handler(widget_id, |guibase| &mut guibase.click_handlers) || changed
+    }
+
+    /// Selects a widget, such as a radio button, and deselects its siblings if its parent
+    /// only lets one child be selected. Change callbacks aren't run, since the gui didn't
+    /// make the change. Returns false if the widget was already selected or can't be.
+    pub fn select(&mut self, widget_id: u128) -> bool {
+        let widget = match self.widgets.get_mut(&widget_id) {
+            Some(gwidget) if !gwidget.widget.is_selected() => &mut gwidget.widget,
+            _ => return false,
+        };
+        widget.set_selected(true);
+        if !widget.is_selected() {
+            return false;
+        }
+        self.mark_dirty(widget_id);
+        self.deselect_siblings(widget_id);
+        true
+    }
+
+    /// Deselects the siblings of a widget that's just been selected, if its parent
+    /// only lets one child be selected. Returns the parent if it does.
+    fn deselect_siblings(&mut self, widget_id: u128) -> Option<u128> {
+        let parent_id = match self.widgets.get(&widget_id) {
+            Some(gwidget) if gwidget.widget.is_selected() => gwidget.parent,
+            _ => return None,
+        };
+        let sibling_ids = match self.widgets.get(&parent_id) {
+            Some(parent) if parent.widget.selects_one_child() => parent.children.clone(),
+            _ => return None,
+        };
+        for sibling_id in sibling_ids {
+            let sibling = &mut self.widgets.get_mut(&sibling_id).unwrap().widget;
+            if sibling_id != widget_id && sibling.is_selected() {
+                sibling.set_selected(false);
+                self.mark_dirty(sibling_id);
+            }
+        }
+        Some(parent_id)
+    }
+
+    /// Returns the selected child of a widget, such as the chosen radio button in a group.
+    pub fn get_selected_child(&self, parent_id: u128) -> Option<u128> {
+        self.widgets
+            .get(&parent_id)?
+            .children
+            .iter()
+            .copied()
+            .find(|child_id| self.widgets[child_id].widget.is_selected())
+    }
+
+    /// Returns the value of the selected child of a widget, such as the chosen radio button
+    /// in a group, or None if none of them are selected or its value isn't of type T.
+    pub fn get_selected_value<T: Clone + 'static>(&self, group_id: u128) -> Option<T> {
+        let child_id = self.get_selected_child(group_id)?;
+        self.widgets[&child_id]
+            .widget
+            .get_value()?
+            .downcast_ref::<T>()
+            .cloned()
+    }
+
+    /// Sets the callback that's run with the value of the newly selected radio button
+    /// whenever the selection in a group is changed through the gui, replacing any previous one.
+    /// It's the group's change callback, so it replaces one set with on_change.
+    pub fn on_select<T: Clone + 'static>(
+        &mut self,
+        group_id: u128,
+        mut handler: impl FnMut(&mut GUIBase, T) + 'static,
+    ) {
+        self.on_change(group_id, move |guibase| {
+            if let Some(value) = guibase.get_selected_value::<T>(group_id) {
+                handler(guibase, value);
+            }
+        });
+    }
+
+    /// Returns the next or previous enabled sibling of a widget whose parent only lets
+    /// one child be selected, wrapping around at the ends.
+    fn get_selectable_sibling(&self, widget_id: u128, forwards: bool) -> Option<u128> {
+        let parent = self.widgets.get(&self.widgets.get(&widget_id)?.parent)?;
+        if !parent.widget.selects_one_child() {
+            return None;
+        }
+        let siblings: Vec<u128> = parent
+            .children
+            .iter()
+            .copied()
+            .filter(|sibling_id| {
+                *sibling_id == widget_id || self.widgets[sibling_id].widget.is_focusable()
+            })
+            .collect();
+        let position = siblings.iter().position(|id| *id == widget_id)?;
+        let next = if forwards {
+            (position + 1) % siblings.len()
+        } else {
+            (position + siblings.len() - 1) % siblings.len()
+        };
+        Some(siblings[next])
+    }
+
+    /// Runs the change callback for a widget whose value has changed.
+    /// Returns true if the widget had a callback.
+    pub fn notify_change(&mut self, widget_id: u128) -> bool {
+        let handled = self.run_handler(widget_id, |guibase| &mut guibase.change_handlers);
+        self.notify_cell_change(widget_id) || handled
+    }
+
+    /// Runs the change callback of the column of a list view or a table
+    /// that a changed widget is a cell in, if it is one.
+    /// Returns true if the column had a callback.
+    fn notify_cell_change(&mut self, widget_id: u128) -> bool {
+        let parent_id = match self.widgets.get(&widget_id) {
+            Some(gwidget) => gwidget.parent,
+            None => return false,
+        };
+        let (row, column) = match self.cell_ids.get(&parent_id).and_then(|cell_ids| {
+            cell_ids
+                .iter()
+                .find(|(_, cell_id)| **cell_id == widget_id)
+                .map(|(cell, _)| *cell)
+        }) {
+            Some(cell) => cell,
+            None => return false,
+        };
+        let mut handler = match self
+            .cell_change_handlers
+            .get_mut(&parent_id)
+            .and_then(|handlers| handlers.remove(&column))
+        {
+            Some(handler) => handler,
+            None => return false,
+        };
+        handler(self, row);
+        // The callback may have removed its list or replaced itself.
+        if self.widgets.contains_key(&parent_id) {
+            self.cell_change_handlers
+                .entry(parent_id)
+                .or_default()
+                .entry(column)
+                .or_insert(handler);
+        }
+
+        true
+    }
+
+    /// Runs a widget's callback from one of the maps of handlers.
+    /// Returns true if the widget had a callback.
+    fn run_handler(
+        &mut self,
+        widget_id: u128,
+        handlers: fn(&mut GUIBase) -> &mut HashMap<u128, GUIHandler>,
+    ) -> bool {
+        let mut handler = match handlers(self).remove(&widget_id) {
+            Some(handler) => handler,
+            None => return false,
+        };
+        handler(self);
+        // The callback may have removed its widget or replaced itself.
+        if self.widgets.contains_key(&widget_id) && !handlers(self).contains_key(&widget_id) {
+            handlers(self).insert(widget_id, handler);
+        }
+
+        true
+    }
+}
+
+/// Returns the scroll offset along one axis that shows start..start + length within a
+/// viewport that's view long, moving offset as little as it can. If it doesn't fit,
+/// its start is shown.
+pub(super) fn scroll_to_show(start: f64, length: f64, offset: f64, view: f64) -> f64 {
+    if start < offset || length > view {
+        start
+    } else if start + length > offset + view {
+        start + length - view
+    } else {
+        offset
+    }
 }
 
 // #[derive(Clone, Copy)]
@@ -111,6 +1254,12 @@ pub struct GWidget {
     widget: Box<dyn Widget>,
     parent: u128,
     children: Vec<u128>,
+    /// How the widget is treated by the container that it's in.
+    layout: GUILayoutItem,
+    /// The size the widget would like to be before a container grows or shrinks it.
+    preferred_size: GUISize,
+    /// Where the widget comes when tabbing between widgets.
+    tab_index: Option<u32>,
 }
 
 impl GWidget {
@@ -129,4 +1278,303 @@ impl GWidget {
     pub fn get_child_ids(&self) -> &Vec<u128> {
         &self.children
     }
+
+    pub fn get_layout_item(&self) -> &GUILayoutItem {
+        &self.layout
+    }
+
+    pub fn get_preferred_size(&self) -> &GUISize {
+        &self.preferred_size
+    }
+
+    pub fn get_tab_index(&self) -> Option<u32> {
+        self.tab_index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiwidgets::{GUIButton, GUICheckbox, GUILabel, GUISlider, GUITextArea};
+
+    fn make_guibase() -> (GUIBase, u128) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        (guibase, window_id)
+    }
+
+    #[test]
+    fn click_runs_handler() {
+        let (mut guibase, window_id) = make_guibase();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        guibase.on_click(button_id, move |_| counter.set(counter.get() + 1));
+
+        assert!(guibase.click(button_id));
+        assert!(guibase.click(button_id));
+        assert_eq!(clicks.get(), 2);
+    }
+
+    #[test]
+    fn click_without_handler_does_nothing() {
+        let (mut guibase, window_id) = make_guibase();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        assert!(!guibase.click(button_id));
+    }
+
+    #[test]
+    fn handler_can_change_tree() {
+        let (mut guibase, window_id) = make_guibase();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        let label_id = guibase.add_child_to_parent(GUILabel::new("Before"), window_id);
+        guibase.on_click(button_id, move |guibase| {
+            guibase
+                .get_widget_as_mut::<GUILabel>(label_id)
+                .unwrap()
+                .set_text("After");
+            guibase.add_child_to_parent(GUILabel::new("New"), window_id);
+        });
+
+        guibase.click(button_id);
+        assert_eq!(
+            guibase.get_widget_as::<GUILabel>(label_id).unwrap().text,
+            "After"
+        );
+        assert_eq!(guibase.windows[&window_id].get_child_ids().len(), 3);
+    }
+
+    #[test]
+    fn handler_can_remove_its_widget() {
+        let (mut guibase, window_id) = make_guibase();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        guibase.on_click(button_id, move |guibase| guibase.remove_widget(button_id));
+
+        assert!(guibase.click(button_id));
+        assert!(!guibase.widgets.contains_key(&button_id));
+        assert!(guibase.windows[&window_id].get_child_ids().is_empty());
+        assert!(!guibase.click(button_id));
+    }
+
+    #[test]
+    fn remove_widget_removes_descendants() {
+        let (mut guibase, window_id) = make_guibase();
+        let parent_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        let child_id = guibase.add_child_to_parent(GUIButton::default(), parent_id);
+        guibase.on_click(child_id, |_| {});
+
+        guibase.remove_widget(parent_id);
+        assert!(guibase.widgets.is_empty());
+        assert!(!guibase.click(child_id));
+    }
+
+    #[test]
+    fn get_widget_as_checks_type() {
+        let (mut guibase, window_id) = make_guibase();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        assert!(guibase.get_widget_as::<GUIButton>(button_id).is_some());
+        assert!(guibase.get_widget_as::<GUILabel>(button_id).is_none());
+    }
+
+    #[test]
+    fn idle_trees_dont_redraw() {
+        let (mut guibase, window_id) = make_guibase();
+        guibase.add_child_to_parent(GUIButton::default(), window_id);
+        let now = Instant::now();
+        assert!(guibase.take_redraw_request(now));
+        guibase.take_dirty_widgets();
+        assert!(!guibase.take_redraw_request(now));
+        assert!(guibase.next_wake_time().is_none());
+
+        guibase.request_redraw();
+        assert!(guibase.take_redraw_request(now));
+        assert!(!guibase.take_redraw_request(now));
+    }
+
+    #[test]
+    fn redraws_wait_until_they_are_due() {
+        let (mut guibase, _) = make_guibase();
+        guibase.take_dirty_widgets();
+        let now = Instant::now();
+        let later = now + Duration::from_millis(100);
+        guibase.request_redraw_at(later + Duration::from_millis(100));
+        guibase.request_redraw_at(later);
+        assert_eq!(guibase.next_wake_time(), Some(later));
+        assert!(!guibase.take_redraw_request(now));
+        assert!(guibase.take_redraw_request(later));
+        assert!(guibase.next_wake_time().is_none());
+    }
+
+    #[test]
+    fn timers_run_once_when_due() {
+        let (mut guibase, window_id) = make_guibase();
+        let label_id = guibase.add_child_to_parent(GUILabel::new("Before"), window_id);
+        guibase.take_dirty_widgets();
+        guibase.add_timer(Duration::from_millis(50), move |guibase| {
+            guibase
+                .get_widget_as_mut::<GUILabel>(label_id)
+                .unwrap()
+                .set_text("After");
+        });
+        let due = guibase.next_wake_time().unwrap();
+
+        guibase.advance(due - Duration::from_millis(1));
+        assert_eq!(guibase.get_widget_as::<GUILabel>(label_id).unwrap().text, "Before");
+        guibase.advance(due);
+        assert_eq!(guibase.get_widget_as::<GUILabel>(label_id).unwrap().text, "After");
+        assert!(guibase.take_redraw_request(due));
+        assert!(guibase.next_wake_time().is_none());
+    }
+
+    fn make_focus_guibase() -> (GUIBase, u128, Vec<u128>) {
+        let (mut guibase, window_id) = make_guibase();
+        let first_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        guibase.add_child_to_parent(GUILabel::new("Not focusable"), window_id);
+        let second_id = guibase.add_child_to_parent(GUIButton::default(), first_id);
+        let third_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        (guibase, window_id, vec![first_id, second_id, third_id])
+    }
+
+    #[test]
+    fn tab_moves_focus_in_tree_order() {
+        let (mut guibase, window_id, button_ids) = make_focus_guibase();
+        let tab = GUIKeyEvent::pressed(GUIKey::Tab);
+        let mut shift_tab = GUIKeyEvent::pressed(GUIKey::Tab);
+        shift_tab.modifiers.shift = true;
+
+        assert_eq!(guibase.get_focused_widget(), None);
+        for button_id in button_ids.iter().chain(button_ids.first()) {
+            assert!(guibase.key_input(window_id, &tab));
+            assert_eq!(guibase.get_focused_widget(), Some(*button_id));
+        }
+        assert!(guibase.key_input(window_id, &shift_tab));
+        assert_eq!(guibase.get_focused_widget(), Some(button_ids[2]));
+    }
+
+    #[test]
+    fn ctrl_tab_moves_focus_out_of_a_text_area() {
+        let (mut guibase, window_id, button_ids) = make_focus_guibase();
+        let text_area_id = guibase.add_child_to_parent(GUITextArea::default(), window_id);
+        guibase.set_focus(Some(text_area_id));
+
+        // Tab is typed into the text area.
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Tab)));
+        assert_eq!(guibase.get_focused_widget(), Some(text_area_id));
+        let text_area = guibase.get_widget_as::<GUITextArea>(text_area_id).unwrap();
+        assert_eq!(text_area.text.to_string(), "\t");
+
+        let mut ctrl_tab = GUIKeyEvent::pressed(GUIKey::Tab);
+        ctrl_tab.modifiers.ctrl = true;
+        assert!(guibase.key_input(window_id, &ctrl_tab));
+        assert_eq!(guibase.get_focused_widget(), Some(button_ids[0]));
+
+        guibase.set_focus(Some(text_area_id));
+        ctrl_tab.modifiers.shift = true;
+        assert!(guibase.key_input(window_id, &ctrl_tab));
+        assert_eq!(guibase.get_focused_widget(), Some(button_ids[2]));
+        let text_area = guibase.get_widget_as::<GUITextArea>(text_area_id).unwrap();
+        assert_eq!(text_area.text.to_string(), "\t");
+    }
+
+    #[test]
+    fn tab_indices_come_first() {
+        let (mut guibase, window_id, button_ids) = make_focus_guibase();
+        guibase.set_tab_index(button_ids[2], Some(1));
+        guibase.set_tab_index(button_ids[1], Some(2));
+        guibase
+            .get_widget_as_mut::<GUIButton>(button_ids[0])
+            .unwrap()
+            .enabled = false;
+
+        assert_eq!(
+            guibase.get_focus_order(window_id),
+            vec![button_ids[2], button_ids[1]]
+        );
+        assert!(!guibase.set_focus(Some(button_ids[0])));
+        assert_eq!(guibase.get_focused_widget(), None);
+    }
+
+    #[test]
+    fn keys_go_to_the_focused_widget() {
+        let (mut guibase, window_id, button_ids) = make_focus_guibase();
+        let clicks = Rc::new(Cell::new(0));
+        let counter = clicks.clone();
+        guibase.on_click(button_ids[1], move |_| counter.set(counter.get() + 1));
+
+        let enter = GUIKeyEvent::pressed(GUIKey::Enter);
+        assert!(!guibase.key_input(window_id, &enter));
+        assert!(guibase.set_focus(Some(button_ids[1])));
+        assert!(guibase.key_input(window_id, &enter));
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Space)));
+        assert!(!guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Escape)));
+        assert_eq!(clicks.get(), 2);
+
+        // Keys only go to the focused widget from its own window.
+        let other_window_id = guibase.add_window(GUIWindow::default());
+        assert!(!guibase.key_input(other_window_id, &enter));
+        assert_eq!(clicks.get(), 2);
+    }
+
+    #[test]
+    fn removing_the_focused_widget_clears_focus() {
+        let (mut guibase, _, button_ids) = make_focus_guibase();
+        guibase.set_focus(Some(button_ids[1]));
+        guibase.take_dirty_widgets();
+
+        guibase.remove_widget(button_ids[0]);
+        assert_eq!(guibase.get_focused_widget(), None);
+        assert!(guibase.has_changes());
+    }
+
+    #[test]
+    fn clicking_a_checkbox_runs_its_change_callback() {
+        let (mut guibase, window_id) = make_guibase();
+        let checkbox_id = guibase.add_child_to_parent(GUICheckbox::new("Check"), window_id);
+        // Records whether the checkbox was checked each time it changed.
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let recorder = changes.clone();
+        guibase.on_change(checkbox_id, move |guibase| {
+            let checkbox = guibase.get_widget(checkbox_id).as_any();
+            let checked = checkbox.downcast_ref::<GUICheckbox>().unwrap().is_checked();
+            recorder.borrow_mut().push(checked);
+        });
+        guibase.take_dirty_widgets();
+
+        assert!(guibase.click(checkbox_id));
+        assert_eq!(*changes.borrow(), vec![true]);
+        assert!(guibase.take_dirty_widgets().unwrap().contains(&checkbox_id));
+
+        // Space flips it back while it has focus.
+        guibase.set_focus(Some(checkbox_id));
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Space)));
+        assert_eq!(*changes.borrow(), vec![true, false]);
+    }
+
+    #[test]
+    fn dragging_a_slider_runs_its_change_callback() {
+        let (mut guibase, window_id) = make_guibase();
+        let slider = GUISlider {
+            position: GUIPosition::from_pixels(100., 50.),
+            ..GUISlider::new(0., 10.)
+        };
+        let slider_id = guibase.add_child_to_parent(slider, window_id);
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        guibase.on_change(slider_id, move |_| counter.set(counter.get() + 1));
+
+        let modifiers = GUIModifiers::default();
+        let middle = GUIPosition::from_pixels(200., 60.);
+        assert!(guibase.pointer_input(slider_id, GUIPointerEventKind::Pressed, &middle, modifiers));
+        assert_eq!(guibase.get_widget_as::<GUISlider>(slider_id).unwrap().value, 5.);
+        // Moving without changing the value doesn't run the callback again.
+        guibase.pointer_input(slider_id, GUIPointerEventKind::Moved, &middle, modifiers);
+        assert_eq!(changes.get(), 1);
+
+        guibase.set_focus(Some(slider_id));
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::End)));
+        assert_eq!(changes.get(), 2);
+    }
 }
@@ -4,8 +4,12 @@ use uuid::Uuid;
 
 use super::widget_utils;
 use super::widget_utils::arcs;
+use crate::guiprocessing::processing_utils::{to_index, INDEX_OVERFLOW};
 use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
 use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
 use crate::guiproperties::guitraits::Widget;
 use crate::guiproperties::GUIColor;
 
@@ -20,12 +24,59 @@ pub struct GUIButton {
     pub radius: GUILength,
     /// The background color for the button.
     pub background_color: GUIColor,
+    /// The background color while the cursor is over the button.
+    pub hover_color: GUIColor,
+    /// The background color while the button is being pressed.
+    pub pressed_color: GUIColor,
+    /// The background color while the button is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the button responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// Whether the cursor is over the button. Set by the gui.
+    pub hovered: bool,
+    /// Whether the button is being pressed. Set by the gui.
+    pub pressed: bool,
+    /// The color of the button's title.
+    pub text_color: GUIColor,
+    /// The height of the button's title.
+    pub font_size: GUILength,
     /// The human readable name of the button
     // pub name: &'static str,
     pub id: u128,
 }
 
+impl GUIButton {
+    /// Returns the background color that matches the button's current state.
+    pub fn current_background_color(&self) -> GUIColor {
+        if !self.enabled {
+            self.disabled_color
+        } else if self.pressed && self.hovered {
+            self.pressed_color
+        } else if self.hovered {
+            self.hover_color
+        } else {
+            self.background_color
+        }
+    }
+}
+
 impl Widget for GUIButton {
+    fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    fn set_pressed(&mut self, pressed: bool) {
+        self.pressed = pressed;
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
     fn get_size(&self) -> &GUISize {
         &self.size
     }
@@ -34,6 +85,14 @@ impl Widget for GUIButton {
         &self.position
     }
 
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
     fn get_id(&self) -> &u128 {
         &self.id
     }
@@ -46,11 +105,28 @@ impl Widget for GUIButton {
         self
     }
 
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        Vec::from([GUIText {
+            text: String::from(self.text),
+            position: GUIPosition::from_lengths(
+                self.position.x.add(&parent_position.x),
+                self.position.y.add(&parent_position.y),
+            ),
+            size: self.size,
+            font_size: self.font_size,
+            color: self.text_color,
+            horizontal_alignment: GUIHorizontalAlignment::Center,
+            vertical_alignment: GUIVerticalAlignment::Center,
+            wrap: false,
+            overflow: GUITextOverflow::Ellipsis,
+        }])
+    }
+
     fn get_vertices_and_indices(
         &self,
         parent_position: &GUIPosition,
-        indice_offset: u16,
-    ) -> (Vec<LogicalVertex>, Vec<u16>, Polygon) {
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
         const FASCET_COUNT: usize = 7;
         // const FASCET_COUNT: usize = 1;
         let mut top_left_radius = arcs::make_top_left_arc(self.radius, FASCET_COUNT);
@@ -93,6 +169,7 @@ impl Widget for GUIButton {
         top_left_radius.extend(bottom_right_radius);
         top_left_radius.extend(top_right_radius);
 
+        let background_color = self.current_background_color();
         let mut vertices = Vec::with_capacity(top_left_radius.len());
         for position in top_left_radius.iter() {
             vertices.push(LogicalVertex {
@@ -101,20 +178,18 @@ impl Widget for GUIButton {
                     (position.y.get_length() + parent_position.y.get_length()) as f32,
                     0.,
                 ],
-                color: [
-                    self.background_color.r as f32,
-                    self.background_color.g as f32,
-                    self.background_color.b as f32,
-                ],
+                color: background_color.to_f32_array(),
             });
         }
         let number_of_triangles = vertices.len() - 2;
         let mut indices = Vec::with_capacity(number_of_triangles * 3);
         let mut widget_id = Vec::with_capacity(number_of_triangles);
         for i in 0..number_of_triangles {
-            indices.push(indice_offset + 0);
-            indices.push(indice_offset + (i + 1) as u16);
-            indices.push(indice_offset + (i + 2) as u16);
+            indices.extend([0, i + 1, i + 2].iter().map(|index| {
+                to_index(*index)
+                    .checked_add(indice_offset)
+                    .expect(INDEX_OVERFLOW)
+            }));
             widget_id.push(self.id);
         }
 
@@ -124,6 +199,7 @@ impl Widget for GUIButton {
             widget_id: self.id,
             convex: true,
             rendered: true,
+            clip: None,
         };
 
         (vertices, indices, polygon)
@@ -147,7 +223,86 @@ impl Default for GUIButton {
                 b: 0.4,
                 a: 1.0,
             },
+            hover_color: GUIColor {
+                r: 0.8,
+                g: 0.2,
+                b: 0.5,
+                a: 1.0,
+            },
+            pressed_color: GUIColor {
+                r: 0.5,
+                g: 0.05,
+                b: 0.3,
+                a: 1.0,
+            },
+            disabled_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.0,
+            },
+            enabled: true,
+            hovered: false,
+            pressed: false,
+            text_color: GUIColor {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 1.0,
+            },
+            font_size: GUILength::from_pixels(20.),
             id: Uuid::new_v4().as_u128(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex_color(button: &GUIButton) -> [f32; 4] {
+        let (vertices, _, _) = button.get_vertices_and_indices(&GUIPosition::default(), 0);
+        vertices[0].color
+    }
+
+    fn color_array(color: GUIColor) -> [f32; 4] {
+        color.to_f32_array()
+    }
+
+    #[test]
+    fn tessellation_uses_state_color() {
+        let mut button = GUIButton::default();
+        assert_eq!(vertex_color(&button), color_array(button.background_color));
+
+        button.set_hovered(true);
+        assert_eq!(vertex_color(&button), color_array(button.hover_color));
+
+        button.set_pressed(true);
+        assert_eq!(vertex_color(&button), color_array(button.pressed_color));
+
+        button.set_hovered(false);
+        assert_eq!(vertex_color(&button), color_array(button.background_color));
+
+        button.enabled = false;
+        assert_eq!(vertex_color(&button), color_array(button.disabled_color));
+        assert!(!button.is_enabled());
+    }
+
+    #[test]
+    fn translucent_color_reaches_vertex_data() {
+        let button = GUIButton {
+            background_color: GUIColor {
+                r: 0.2,
+                g: 0.4,
+                b: 0.6,
+                a: 0.5,
+            },
+            ..Default::default()
+        };
+        let (vertices, _, _) = button.get_vertices_and_indices(&GUIPosition::default(), 0);
+        for vertex in vertices.iter() {
+            assert_eq!(vertex.color[3], 0.5);
+            assert_eq!(vertex.to_vertex(500., 500.).color, [0.2, 0.4, 0.6, 0.5]);
+        }
+    }
+}
@@ -0,0 +1,350 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// Whether a checkbox is ticked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GUICheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    /// Neither checked nor unchecked, such as a checkbox for a group of options
+    /// where only some are checked. It's only ever set by the program, never by clicking.
+    Indeterminate,
+}
+
+/// A box that's ticked and unticked by clicking on it or its label.
+pub struct GUICheckbox {
+    /// The text next to the box.
+    pub label: String,
+    /// Whether the box is ticked.
+    pub state: GUICheckState,
+    /// The size of the checkbox, including its label.
+    pub size: GUISize,
+    /// The location of the checkbox.
+    pub position: GUIPosition,
+    /// The width and height of the box.
+    pub box_size: GUILength,
+    /// Radius of the box corners.
+    pub radius: GUILength,
+    /// How thick the outline of the box is while it's unchecked.
+    pub border_width: GUILength,
+    /// The space between the box and the label.
+    pub spacing: GUILength,
+    /// The color inside the box while it's unchecked.
+    pub background_color: GUIColor,
+    /// The color of the outline of the box while it's unchecked.
+    pub border_color: GUIColor,
+    /// The color of the box while it's checked or indeterminate.
+    pub checked_color: GUIColor,
+    /// The color of the tick, or the bar while it's indeterminate.
+    pub mark_color: GUIColor,
+    /// The color of the box while the checkbox is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the checkbox responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// The color of the label.
+    pub text_color: GUIColor,
+    /// The height of the label.
+    pub font_size: GUILength,
+    pub id: u128,
+}
+
+impl GUICheckbox {
+    /// Returns an unchecked checkbox with the default values and label next to it.
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: String::from(label),
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the box is ticked.
+    pub fn is_checked(&self) -> bool {
+        self.state == GUICheckState::Checked
+    }
+}
+
+impl Widget for GUICheckbox {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn activate(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        self.state = match self.state {
+            GUICheckState::Checked => GUICheckState::Unchecked,
+            GUICheckState::Unchecked | GUICheckState::Indeterminate => GUICheckState::Checked,
+        };
+        true
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        let text_left = self.box_size.add(&self.spacing);
+        Vec::from([GUIText {
+            text: self.label.clone(),
+            position: GUIPosition::from_lengths(
+                self.position.x.add(&parent_position.x).add(&text_left),
+                self.position.y.add(&parent_position.y),
+            ),
+            size: GUISize::from_lengths(self.size.width.subtract(&text_left), self.size.height),
+            font_size: self.font_size,
+            color: self.text_color,
+            horizontal_alignment: GUIHorizontalAlignment::Left,
+            vertical_alignment: GUIVerticalAlignment::Center,
+            wrap: false,
+            overflow: GUITextOverflow::Ellipsis,
+        }])
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        // The label can be clicked as well as the box, so the whole checkbox
+        // is covered by an invisible rectangle that's hit tested.
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            &GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 0.,
+            },
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        let box_size = self.box_size.get_length();
+        let box_position = GUIPosition::from_pixels(
+            origin.x.get_length(),
+            origin.y.get_length() + (self.size.height.get_length() - box_size) / 2.,
+        );
+        let box_extent = GUISize::from_lengths(self.box_size, self.box_size);
+        let fill_color = if !self.enabled {
+            self.disabled_color
+        } else if self.state == GUICheckState::Unchecked {
+            self.border_color
+        } else {
+            self.checked_color
+        };
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &box_position,
+            &box_extent,
+            self.radius,
+            &fill_color,
+        );
+
+        let left = box_position.x.get_length() as f32;
+        let top = box_position.y.get_length() as f32;
+        let side = box_size as f32;
+        let mark_width = side * 0.14;
+        match self.state {
+            GUICheckState::Unchecked => {
+                // Hollow the box out to leave its outline.
+                let border = self.border_width.get_length();
+                widget_utils::push_rounded_rectangle(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    &GUIPosition::from_pixels(
+                        box_position.x.get_length() + border,
+                        box_position.y.get_length() + border,
+                    ),
+                    &GUISize::from_pixels(box_size - 2. * border, box_size - 2. * border),
+                    self.radius.subtract(&self.border_width),
+                    &self.background_color,
+                );
+            }
+            GUICheckState::Checked => {
+                let corner = [left + side * 0.42, top + side * 0.72];
+                widget_utils::push_line(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    [left + side * 0.2, top + side * 0.5],
+                    corner,
+                    mark_width,
+                    &self.mark_color,
+                );
+                widget_utils::push_line(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    corner,
+                    [left + side * 0.8, top + side * 0.28],
+                    mark_width,
+                    &self.mark_color,
+                );
+            }
+            GUICheckState::Indeterminate => {
+                widget_utils::push_line(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    [left + side * 0.25, top + side * 0.5],
+                    [left + side * 0.75, top + side * 0.5],
+                    mark_width,
+                    &self.mark_color,
+                );
+            }
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUICheckbox {
+    /// Returns an unchecked checkbox with all of the default values.
+    fn default() -> Self {
+        Self {
+            label: String::new(),
+            state: GUICheckState::default(),
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(24.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            box_size: GUILength::from_pixels(18.),
+            radius: GUILength::from_pixels(3.),
+            border_width: GUILength::from_pixels(2.),
+            spacing: GUILength::from_pixels(8.),
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            border_color: GUIColor {
+                r: 0.45,
+                g: 0.45,
+                b: 0.45,
+                a: 1.,
+            },
+            checked_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            mark_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.,
+            },
+            enabled: true,
+            text_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            font_size: GUILength::from_pixels(16.),
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicking_cycles_between_checked_and_unchecked() {
+        let mut checkbox = GUICheckbox {
+            state: GUICheckState::Indeterminate,
+            ..Default::default()
+        };
+        assert!(checkbox.activate());
+        assert!(checkbox.is_checked());
+        assert!(checkbox.activate());
+        assert_eq!(checkbox.state, GUICheckState::Unchecked);
+
+        checkbox.enabled = false;
+        assert!(!checkbox.activate());
+        assert_eq!(checkbox.state, GUICheckState::Unchecked);
+    }
+
+    #[test]
+    fn the_label_is_hit_tested_with_the_box() {
+        let checkbox = GUICheckbox::new("Label");
+        let (vertices, indices, polygon) =
+            checkbox.get_vertices_and_indices(&GUIPosition::from_pixels(10., 20.), 5);
+        assert_eq!(polygon.end_index - polygon.start_index, 4);
+        assert_eq!(vertices[0].position, [10., 20., 0.]);
+        assert_eq!(vertices[2].position, [210., 44., 0.]);
+        assert!(indices
+            .iter()
+            .all(|index| (*index as usize) < vertices.len() + 5));
+    }
+}
@@ -0,0 +1,379 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guilayout::{GUILayoutChild, GUIPadding, GUITrack};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::{Parent, Widget};
+
+/// A container that places its children into the cells of a grid.
+/// Children say which cells they cover through their GUILayoutItem,
+/// and are stretched to fill those cells.
+pub struct GUIGrid {
+    /// The heights of the rows, top to bottom.
+    /// Rows that children are placed in past the end of this are Auto.
+    pub rows: Vec<GUITrack>,
+    /// The widths of the columns, left to right.
+    /// Columns that children are placed in past the end of this are Auto.
+    pub columns: Vec<GUITrack>,
+    /// The space between neighboring rows.
+    pub row_spacing: GUILength,
+    /// The space between neighboring columns.
+    pub column_spacing: GUILength,
+    /// The space between the edges of the grid and its cells.
+    pub padding: GUIPadding,
+    /// The size of the grid.
+    pub size: GUISize,
+    /// The location of the grid.
+    pub position: GUIPosition,
+    pub id: u128,
+}
+
+impl GUIGrid {
+    /// Returns a grid with the default values and the given rows and columns.
+    pub fn new(rows: Vec<GUITrack>, columns: Vec<GUITrack>) -> Self {
+        Self {
+            rows,
+            columns,
+            ..Default::default()
+        }
+    }
+}
+
+/// A child's extent along one direction of the grid.
+struct Span {
+    start: usize,
+    count: usize,
+    preferred: f64,
+}
+
+/// Returns the start and length of every track along one direction of the grid.
+fn size_tracks(
+    tracks: &[GUITrack],
+    spans: &[Span],
+    available: f64,
+    spacing: f64,
+) -> Vec<(f64, f64)> {
+    let count = spans
+        .iter()
+        .map(|span| span.start + span.count)
+        .max()
+        .unwrap_or(0)
+        .max(tracks.len());
+    let track = |index: usize| tracks.get(index).copied().unwrap_or(GUITrack::Auto);
+
+    let mut lengths = vec![0.; count];
+    for (index, length) in lengths.iter_mut().enumerate() {
+        if let GUITrack::Fixed(fixed) = track(index) {
+            *length = fixed.get_length();
+        }
+    }
+
+    // Auto tracks fit the widgets that are only in them, then any widget
+    // that spans several tracks makes up what it's missing from its auto tracks.
+    for span in spans.iter().filter(|span| span.count == 1) {
+        if track(span.start) == GUITrack::Auto {
+            lengths[span.start] = lengths[span.start].max(span.preferred);
+        }
+    }
+    for span in spans.iter().filter(|span| span.count > 1) {
+        let range = span.start..span.start + span.count;
+        let auto_tracks: Vec<usize> = range
+            .clone()
+            .filter(|index| track(*index) == GUITrack::Auto)
+            .collect();
+        let covered = lengths[range].iter().sum::<f64>() + spacing * (span.count - 1) as f64;
+        if covered < span.preferred && !auto_tracks.is_empty() {
+            let extra = (span.preferred - covered) / auto_tracks.len() as f64;
+            for index in auto_tracks {
+                lengths[index] += extra;
+            }
+        }
+    }
+
+    let total_fraction: f64 = (0..count)
+        .filter_map(|index| match track(index) {
+            GUITrack::Fraction(fraction) => Some(fraction),
+            _ => None,
+        })
+        .sum();
+    if total_fraction > 0. {
+        let used = lengths.iter().sum::<f64>() + spacing * count.saturating_sub(1) as f64;
+        let left_over = (available - used).max(0.);
+        for (index, length) in lengths.iter_mut().enumerate() {
+            if let GUITrack::Fraction(fraction) = track(index) {
+                *length = left_over * fraction / total_fraction;
+            }
+        }
+    }
+
+    let mut start = 0.;
+    lengths
+        .iter()
+        .map(|length| {
+            let track = (start, *length);
+            start += length + spacing;
+            track
+        })
+        .collect()
+}
+
+/// Returns the start and length of the area covered by a span of tracks.
+fn span_area(tracks: &[(f64, f64)], span: &Span) -> (f64, f64) {
+    let (start, _) = tracks[span.start];
+    let (last_start, last_length) = tracks[span.start + span.count - 1];
+    (start, last_start + last_length - start)
+}
+
+impl Widget for GUIGrid {
+    fn get_vertices_and_indices(
+        &self,
+        _parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize,
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        (Vec::new(), Vec::new(), polygon)
+    }
+
+    fn arrange_children(&self, children: &[GUILayoutChild]) -> Option<Vec<(GUIPosition, GUISize)>> {
+        let (inner_position, inner_size) = self.padding.inset(&self.size);
+
+        let row_spans: Vec<Span> = children
+            .iter()
+            .map(|child| Span {
+                start: child.item.cell.row,
+                count: child.item.cell.row_span.max(1),
+                preferred: child.preferred_size.height.get_length(),
+            })
+            .collect();
+        let column_spans: Vec<Span> = children
+            .iter()
+            .map(|child| Span {
+                start: child.item.cell.column,
+                count: child.item.cell.column_span.max(1),
+                preferred: child.preferred_size.width.get_length(),
+            })
+            .collect();
+
+        let rows = size_tracks(
+            &self.rows,
+            &row_spans,
+            inner_size.height.get_length(),
+            self.row_spacing.get_length(),
+        );
+        let columns = size_tracks(
+            &self.columns,
+            &column_spans,
+            inner_size.width.get_length(),
+            self.column_spacing.get_length(),
+        );
+
+        let areas = row_spans
+            .iter()
+            .zip(column_spans.iter())
+            .map(|(row_span, column_span)| {
+                let (y, height) = span_area(&rows, row_span);
+                let (x, width) = span_area(&columns, column_span);
+                (
+                    GUIPosition::from_pixels(
+                        inner_position.x.get_length() + x,
+                        inner_position.y.get_length() + y,
+                    ),
+                    GUISize::from_pixels(width, height),
+                )
+            })
+            .collect();
+
+        Some(areas)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Parent for GUIGrid {}
+
+impl Default for GUIGrid {
+    /// Returns a grid with all of the default values.
+    fn default() -> Self {
+        Self {
+            rows: Vec::new(),
+            columns: Vec::new(),
+            row_spacing: GUILength::from_pixels(0.),
+            column_spacing: GUILength::from_pixels(0.),
+            padding: GUIPadding::default(),
+            size: GUISize {
+                width: GUILength::from_pixels(300.),
+                height: GUILength::from_pixels(300.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guilayout::{GUIGridCell, GUILayoutItem};
+    use crate::guiwidgets::{GUIBase, GUIButton, GUIWindow};
+
+    fn child(width: f64, height: f64, cell: GUIGridCell) -> GUILayoutChild {
+        GUILayoutChild {
+            preferred_size: GUISize::from_pixels(width, height),
+            item: GUILayoutItem::in_cell(cell),
+        }
+    }
+
+    fn xywh(area: &(GUIPosition, GUISize)) -> [f64; 4] {
+        [
+            area.0.x.get_length(),
+            area.0.y.get_length(),
+            area.1.width.get_length(),
+            area.1.height.get_length(),
+        ]
+    }
+
+    #[test]
+    fn sizes_fixed_auto_and_fraction_tracks() {
+        let grid = GUIGrid {
+            columns: vec![
+                GUITrack::Fixed(GUILength::from_pixels(50.)),
+                GUITrack::Auto,
+                GUITrack::Fraction(1.),
+                GUITrack::Fraction(3.),
+            ],
+            rows: vec![GUITrack::Auto],
+            size: GUISize::from_pixels(400., 100.),
+            ..Default::default()
+        };
+        let areas = grid
+            .arrange_children(&[
+                child(10., 20., GUIGridCell::new(0, 0)),
+                child(70., 30., GUIGridCell::new(0, 1)),
+                child(10., 10., GUIGridCell::new(0, 2)),
+                child(10., 10., GUIGridCell::new(0, 3)),
+            ])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 0., 50., 30.]);
+        assert_eq!(xywh(&areas[1]), [50., 0., 70., 30.]);
+        assert_eq!(xywh(&areas[2]), [120., 0., 70., 30.]);
+        assert_eq!(xywh(&areas[3]), [190., 0., 210., 30.]);
+    }
+
+    #[test]
+    fn applies_spacing_and_padding() {
+        let grid = GUIGrid {
+            columns: vec![GUITrack::Fraction(1.), GUITrack::Fraction(1.)],
+            rows: vec![GUITrack::Fixed(GUILength::from_pixels(40.)); 2],
+            column_spacing: GUILength::from_pixels(10.),
+            row_spacing: GUILength::from_pixels(5.),
+            padding: GUIPadding::from_pixels(10.),
+            size: GUISize::from_pixels(230., 200.),
+            ..Default::default()
+        };
+        let areas = grid
+            .arrange_children(&[child(10., 10., GUIGridCell::new(1, 1))])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [120., 55., 100., 40.]);
+    }
+
+    #[test]
+    fn spans_cover_several_tracks() {
+        let grid = GUIGrid {
+            columns: vec![GUITrack::Fixed(GUILength::from_pixels(50.)); 3],
+            rows: vec![GUITrack::Fixed(GUILength::from_pixels(20.)); 3],
+            column_spacing: GUILength::from_pixels(10.),
+            ..Default::default()
+        };
+        let areas = grid
+            .arrange_children(&[child(10., 10., GUIGridCell::new(1, 0).span(2, 3))])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 20., 170., 40.]);
+    }
+
+    #[test]
+    fn spanning_children_widen_auto_tracks() {
+        let grid = GUIGrid {
+            columns: vec![GUITrack::Auto, GUITrack::Auto],
+            rows: vec![GUITrack::Auto, GUITrack::Auto],
+            ..Default::default()
+        };
+        let areas = grid
+            .arrange_children(&[
+                child(40., 10., GUIGridCell::new(0, 0)),
+                child(100., 10., GUIGridCell::new(1, 0).span(1, 2)),
+            ])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 0., 70., 10.]);
+        assert_eq!(xywh(&areas[1]), [0., 10., 100., 10.]);
+    }
+
+    #[test]
+    fn extra_tracks_are_auto() {
+        let grid = GUIGrid::default();
+        let areas = grid
+            .arrange_children(&[
+                child(40., 15., GUIGridCell::new(0, 0)),
+                child(60., 25., GUIGridCell::new(1, 1)),
+            ])
+            .unwrap();
+        assert_eq!(xywh(&areas[1]), [40., 15., 60., 25.]);
+    }
+
+    #[test]
+    fn cell_is_set_when_child_is_added() {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let grid_id = guibase.add_child_to_parent(
+            GUIGrid::new(
+                vec![GUITrack::Fraction(1.); 2],
+                vec![GUITrack::Fraction(1.); 2],
+            ),
+            window_id,
+        );
+        let button_id = guibase.add_child_to_parent_with_layout(
+            GUIButton::default(),
+            grid_id,
+            GUILayoutItem::in_cell(GUIGridCell::new(1, 1)),
+        );
+        guibase.layout();
+
+        let button = guibase.get_widget(button_id);
+        assert_eq!(button.get_position().x.get_length(), 150.);
+        assert_eq!(button.get_position().y.get_length(), 150.);
+        assert_eq!(button.get_size().width.get_length(), 150.);
+    }
+}
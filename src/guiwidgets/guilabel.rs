@@ -0,0 +1,137 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// A widget that displays text.
+pub struct GUILabel {
+    /// The text that's displayed. Lines are separated by '\n'.
+    pub text: String,
+    /// The size of the area that the text is laid out in.
+    pub size: GUISize,
+    /// The location of the label.
+    pub position: GUIPosition,
+    /// The height of the font.
+    pub font_size: GUILength,
+    /// The color of the text.
+    pub text_color: GUIColor,
+    /// Where the text sits horizontally within the label.
+    pub horizontal_alignment: GUIHorizontalAlignment,
+    /// Where the text sits vertically within the label.
+    pub vertical_alignment: GUIVerticalAlignment,
+    /// Whether lines that are wider than the label are broken between words.
+    pub wrap: bool,
+    /// What happens to text that doesn't fit within the label.
+    pub overflow: GUITextOverflow,
+    pub id: u128,
+}
+
+impl GUILabel {
+    /// Returns a label with the default values that displays text.
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: String::from(text),
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the text that's displayed.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = String::from(text);
+    }
+}
+
+impl Widget for GUILabel {
+    fn get_vertices_and_indices(
+        &self,
+        _parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize,
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        (Vec::new(), Vec::new(), polygon)
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        Vec::from([GUIText {
+            text: self.text.clone(),
+            position: GUIPosition::from_lengths(
+                self.position.x.add(&parent_position.x),
+                self.position.y.add(&parent_position.y),
+            ),
+            size: self.size,
+            font_size: self.font_size,
+            color: self.text_color,
+            horizontal_alignment: self.horizontal_alignment,
+            vertical_alignment: self.vertical_alignment,
+            wrap: self.wrap,
+            overflow: self.overflow,
+        }])
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Default for GUILabel {
+    /// Returns a label with all of the default values.
+    fn default() -> Self {
+        Self {
+            text: String::from("Label"),
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(30.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            font_size: GUILength::from_pixels(16.),
+            text_color: GUIColor {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            },
+            horizontal_alignment: GUIHorizontalAlignment::Left,
+            vertical_alignment: GUIVerticalAlignment::Center,
+            wrap: true,
+            overflow: GUITextOverflow::Ellipsis,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
@@ -0,0 +1,729 @@
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use uuid::Uuid;
+
+use super::guibase::scroll_to_show;
+use super::guiscrollview::{thumb_offset, thumb_span};
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent, GUIModifiers};
+use crate::guiproperties::guilayout::GUICell;
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::{Parent, Widget};
+use crate::guiproperties::GUIColor;
+
+/// How many rows of a list can be selected at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GUISelectionMode {
+    /// Rows can't be selected, though the keyboard still moves between them.
+    None,
+    /// Selecting a row deselects the others.
+    Single,
+    /// Ctrl clicking a row adds it to the selection, and shift clicking selects
+    /// every row from the last one that was clicked.
+    Multiple,
+}
+
+/// A scrolling list of rows that are all the same height, which can hold far more
+/// rows than there are widgets. It only has row widgets for the rows that can be seen,
+/// which are made and filled in by the callback that's given to GUIBase::set_row_builder,
+/// and the widgets are reused for other rows as the list is scrolled.
+pub struct GUIListView {
+    /// How many rows there are.
+    pub row_count: usize,
+    /// How tall each row is.
+    pub row_height: GUILength,
+    /// How many rows can be selected at once.
+    pub selection_mode: GUISelectionMode,
+    /// The rows that are selected.
+    pub selected_rows: BTreeSet<usize>,
+    /// The row that the keyboard moves from, which is outlined while the list has focus.
+    pub cursor_row: Option<usize>,
+    /// The row that shift clicking selects from. Set by the gui.
+    pub anchor_row: Option<usize>,
+    /// The size of the list, including its scrollbar.
+    pub size: GUISize,
+    /// The location of the list.
+    pub position: GUIPosition,
+    /// How far the rows are scrolled down.
+    pub scroll_offset: GUIPosition,
+    /// How thick the scrollbar is.
+    pub scrollbar_thickness: GUILength,
+    /// The shortest that the scrollbar's thumb gets, however many rows there are.
+    pub min_thumb_length: GUILength,
+    /// The color behind the rows.
+    pub background_color: GUIColor,
+    /// The color behind the selected rows.
+    pub selection_color: GUIColor,
+    /// The color of the outline around the cursor row.
+    pub cursor_color: GUIColor,
+    /// The color of the scrollbar's track.
+    pub track_color: GUIColor,
+    /// The color of the scrollbar's thumb.
+    pub thumb_color: GUIColor,
+    /// Whether the list has keyboard focus. Set by the gui.
+    pub focused: bool,
+    /// How far along the scrollbar's thumb it was grabbed, while it's being dragged.
+    /// Set by the gui.
+    pub dragging: Option<f64>,
+    /// Whether the selection has changed since it was last checked. Set by the gui.
+    pub selection_changed: bool,
+    pub id: u128,
+}
+
+impl GUIListView {
+    /// Returns a list with the default values that has row_count rows.
+    pub fn new(row_count: usize) -> Self {
+        Self {
+            row_count,
+            ..Default::default()
+        }
+    }
+
+    /// Changes how many rows there are, dropping the selection of rows that are gone.
+    pub fn set_row_count(&mut self, row_count: usize) {
+        self.row_count = row_count;
+        let removed = self.selected_rows.split_off(&row_count);
+        self.selection_changed |= !removed.is_empty();
+        let last = row_count.checked_sub(1);
+        self.cursor_row = self
+            .cursor_row
+            .and_then(|row| last.map(|last| row.min(last)));
+        self.anchor_row = self
+            .anchor_row
+            .and_then(|row| last.map(|last| row.min(last)));
+        self.set_scroll_offset(self.scroll_offset);
+    }
+
+    /// Returns the rows that can be seen, some of which may only be partly in view.
+    pub fn visible_rows(&self) -> Range<usize> {
+        let row_height = self.row_height.get_length();
+        if row_height <= 0. {
+            return 0..0;
+        }
+        let offset = self.scroll_offset.y.get_length();
+        let bottom = offset + self.size.height.get_length();
+        let first = ((offset / row_height).floor() as usize).min(self.row_count);
+        let end = ((bottom / row_height).ceil() as usize).min(self.row_count);
+        first..end.max(first)
+    }
+
+    /// Returns how tall all of the rows are together.
+    fn content_height(&self) -> f64 {
+        self.row_count as f64 * self.row_height.get_length()
+    }
+
+    /// Returns whether the scrollbar is shown, which it is when the rows don't fit.
+    fn shows_scrollbar(&self) -> bool {
+        self.content_height() > self.size.height.get_length()
+    }
+
+    /// Returns the size of the area that the rows are shown in.
+    fn viewport(&self) -> GUISize {
+        let thickness = if self.shows_scrollbar() {
+            self.scrollbar_thickness.get_length()
+        } else {
+            0.
+        };
+        GUISize::from_pixels(
+            (self.size.width.get_length() - thickness).max(0.),
+            self.size.height.get_length(),
+        )
+    }
+
+    /// Returns how far the rows can be scrolled down.
+    fn max_scroll_offset(&self) -> f64 {
+        (self.content_height() - self.size.height.get_length()).max(0.)
+    }
+
+    /// Returns how many whole rows fit in the list, which is how far Page Up and
+    /// Page Down move.
+    fn rows_per_page(&self) -> usize {
+        let row_height = self.row_height.get_length();
+        if row_height <= 0. {
+            return 1;
+        }
+        ((self.size.height.get_length() / row_height).floor() as usize).max(1)
+    }
+
+    /// Returns where the scrollbar's thumb starts and how long it is.
+    fn thumb(&self) -> (f64, f64) {
+        thumb_span(
+            self.size.height.get_length(),
+            self.content_height(),
+            self.scroll_offset.y.get_length(),
+            self.max_scroll_offset(),
+            self.min_thumb_length.get_length(),
+        )
+    }
+
+    /// Scrolls so that the scrollbar's thumb starts at thumb_start.
+    /// Returns true if the rows moved.
+    fn drag_thumb_to(&mut self, thumb_start: f64) -> bool {
+        let (_, length) = self.thumb();
+        let offset = thumb_offset(
+            self.size.height.get_length(),
+            length,
+            thumb_start,
+            self.max_scroll_offset(),
+        );
+        self.set_scroll_offset(GUIPosition::from_pixels(0., offset))
+    }
+
+    /// Draws the selected rows, the cursor and the scrollbar of a list whose top left
+    /// corner is at origin, on top of its background. A table draws its rows with this
+    /// below its header.
+    pub(super) fn push_selection_and_scrollbar(
+        &self,
+        vertices: &mut Vec<LogicalVertex>,
+        indices: &mut Vec<u32>,
+        indice_offset: u32,
+        origin: &GUIPosition,
+    ) {
+        let (left, top) = (origin.x.get_length(), origin.y.get_length());
+        let width = self.viewport().width.get_length();
+        let height = self.size.height.get_length();
+        let row_height = self.row_height.get_length();
+        let offset = self.scroll_offset.y.get_length();
+        // Only the rows' widgets are clipped to the list, so the list trims
+        // what it draws behind the rows that are partly scrolled out of view.
+        let mut push_band = |vertices: &mut Vec<LogicalVertex>,
+                             row: usize,
+                             inset: f64,
+                             thickness: f64,
+                             color: &GUIColor| {
+            let row_top = row as f64 * row_height - offset;
+            let band_top = (row_top + inset).max(0.);
+            let band_bottom = (row_top + inset + thickness).min(height);
+            if band_bottom > band_top {
+                widget_utils::push_rectangle(
+                    vertices,
+                    indices,
+                    indice_offset,
+                    &GUIPosition::from_pixels(left, top + band_top),
+                    &GUISize::from_pixels(width, band_bottom - band_top),
+                    color,
+                );
+            }
+        };
+        let visible = self.visible_rows();
+        for row in self.selected_rows.range(visible.clone()) {
+            push_band(vertices, *row, 0., row_height, &self.selection_color);
+        }
+        if let Some(row) = self
+            .cursor_row
+            .filter(|row| self.focused && visible.contains(row))
+        {
+            push_band(vertices, row, 0., 1., &self.cursor_color);
+            push_band(vertices, row, row_height - 1., 1., &self.cursor_color);
+        }
+
+        if self.shows_scrollbar() {
+            let thickness = self.scrollbar_thickness;
+            let (start, length) = self.thumb();
+            widget_utils::push_rectangle(
+                vertices,
+                indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + width, top),
+                &GUISize::from_lengths(thickness, self.size.height),
+                &self.track_color,
+            );
+            widget_utils::push_rounded_rectangle(
+                vertices,
+                indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + width, top + start),
+                &GUISize::from_lengths(thickness, GUILength::from_pixels(length)),
+                GUILength::from_pixels(thickness.get_length() / 2.),
+                &self.thumb_color,
+            );
+        }
+    }
+
+    /// Scrolls as little as possible to show all of a row.
+    /// Returns true if the rows moved.
+    pub fn scroll_to_row(&mut self, row: usize) -> bool {
+        let row_height = self.row_height.get_length();
+        let offset = scroll_to_show(
+            row as f64 * row_height,
+            row_height,
+            self.scroll_offset.y.get_length(),
+            self.size.height.get_length(),
+        );
+        self.set_scroll_offset(GUIPosition::from_pixels(0., offset))
+    }
+
+    /// Moves the cursor to a row and selects it the way that clicking it with
+    /// modifiers held down would. Returns true if the cursor or the selection changed.
+    pub fn select_row(&mut self, row: usize, modifiers: &GUIModifiers) -> bool {
+        if row >= self.row_count {
+            return false;
+        }
+        let selected_rows = match self.selection_mode {
+            GUISelectionMode::None => BTreeSet::new(),
+            GUISelectionMode::Single => BTreeSet::from([row]),
+            GUISelectionMode::Multiple => {
+                let mut selected_rows = if modifiers.shortcut() {
+                    self.selected_rows.clone()
+                } else {
+                    BTreeSet::new()
+                };
+                match self.anchor_row {
+                    Some(anchor) if modifiers.shift => {
+                        selected_rows.extend(anchor.min(row)..=anchor.max(row));
+                    }
+                    _ => {
+                        if !selected_rows.insert(row) {
+                            selected_rows.remove(&row);
+                        }
+                        self.anchor_row = Some(row);
+                    }
+                }
+                selected_rows
+            }
+        };
+        if self.selection_mode != GUISelectionMode::Multiple {
+            self.anchor_row = Some(row);
+        }
+
+        let changed = selected_rows != self.selected_rows;
+        self.selected_rows = selected_rows;
+        self.selection_changed |= changed;
+        let moved = self.cursor_row != Some(row);
+        self.cursor_row = Some(row);
+        changed || moved
+    }
+
+    /// Returns the row that's at y, relative to the top of the list.
+    fn row_at(&self, y: f64) -> Option<usize> {
+        let row_height = self.row_height.get_length();
+        let position = y + self.scroll_offset.y.get_length();
+        if row_height <= 0. || position < 0. {
+            return None;
+        }
+        let row = (position / row_height).floor() as usize;
+        (row < self.row_count).then_some(row)
+    }
+}
+
+impl Widget for GUIListView {
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        if !event.pressed || self.row_count == 0 {
+            return false;
+        }
+        let last = self.row_count - 1;
+        let cursor = self.cursor_row.unwrap_or(self.visible_rows().start);
+        let page = self.rows_per_page();
+        let modifiers = event.modifiers;
+        let target = match event.key {
+            // Until there's a cursor, moving starts from the first row that can be seen.
+            GUIKey::Up | GUIKey::Down | GUIKey::PageUp | GUIKey::PageDown
+                if self.cursor_row.is_none() =>
+            {
+                cursor
+            }
+            GUIKey::Up => cursor.saturating_sub(1),
+            GUIKey::Down => (cursor + 1).min(last),
+            GUIKey::PageUp => cursor.saturating_sub(page),
+            GUIKey::PageDown => (cursor + page).min(last),
+            GUIKey::Home => 0,
+            GUIKey::End => last,
+            GUIKey::Space => {
+                // Space toggles the cursor row, like ctrl clicking it.
+                let toggle = GUIModifiers {
+                    ctrl: true,
+                    logo: true,
+                    ..Default::default()
+                };
+                self.select_row(cursor, &toggle);
+                return true;
+            }
+            GUIKey::Letter('a')
+                if modifiers.shortcut() && self.selection_mode == GUISelectionMode::Multiple =>
+            {
+                let all: BTreeSet<usize> = (0..self.row_count).collect();
+                self.selection_changed |= all != self.selected_rows;
+                self.selected_rows = all;
+                return true;
+            }
+            _ => return false,
+        };
+
+        if modifiers.shortcut() && self.selection_mode == GUISelectionMode::Multiple {
+            // Moving with ctrl held down moves the cursor without changing the selection,
+            // so that Space can add rows that aren't next to each other.
+            self.cursor_row = Some(target);
+        } else {
+            let modifiers = GUIModifiers {
+                shift: modifiers.shift,
+                ..Default::default()
+            };
+            self.select_row(target, &modifiers);
+        }
+        self.scroll_to_row(target);
+        true
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        let (x, y) = (event.position.x.get_length(), event.position.y.get_length());
+        match event.kind {
+            GUIPointerEventKind::Pressed => {
+                if self.shows_scrollbar() && x >= self.viewport().width.get_length() {
+                    let (start, length) = self.thumb();
+                    return if (start..start + length).contains(&y) {
+                        self.dragging = Some(y - start);
+                        false
+                    } else {
+                        // Clicking the track jumps the thumb to the pointer, ready to be dragged.
+                        self.dragging = Some(length / 2.);
+                        self.drag_thumb_to(y - length / 2.)
+                    };
+                }
+                match self.row_at(y) {
+                    Some(row) => self.select_row(row, &event.modifiers),
+                    None => false,
+                }
+            }
+            GUIPointerEventKind::Moved => match self.dragging {
+                Some(grab) => self.drag_thumb_to(y - grab),
+                None => false,
+            },
+            GUIPointerEventKind::Released => {
+                self.dragging = None;
+                false
+            }
+        }
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.selection_changed)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+        self.set_scroll_offset(self.scroll_offset);
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_viewport(&self) -> Option<(GUIPosition, GUISize)> {
+        Some((GUIPosition::default(), self.viewport()))
+    }
+
+    fn get_scroll_offset(&self) -> GUIPosition {
+        self.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: GUIPosition) -> bool {
+        let offset = GUIPosition::from_pixels(
+            0.,
+            offset.y.get_length().clamp(0., self.max_scroll_offset()),
+        );
+        if offset == self.scroll_offset {
+            return false;
+        }
+        self.scroll_offset = offset;
+        true
+    }
+
+    fn get_visible_cells(&self) -> Vec<GUICell> {
+        let width = self.viewport().width;
+        let row_height = self.row_height.get_length();
+        self.visible_rows()
+            .map(|row| GUICell {
+                row,
+                column: 0,
+                position: GUIPosition::from_pixels(0., row as f64 * row_height),
+                size: GUISize::from_lengths(width, self.row_height),
+            })
+            .collect()
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            &self.background_color,
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        self.push_selection_and_scrollbar(&mut vertices, &mut indices, indice_offset, &origin);
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Parent for GUIListView {}
+
+impl Default for GUIListView {
+    /// Returns an empty list with all of the default values.
+    fn default() -> Self {
+        Self {
+            row_count: 0,
+            row_height: GUILength::from_pixels(24.),
+            selection_mode: GUISelectionMode::Single,
+            selected_rows: BTreeSet::new(),
+            cursor_row: None,
+            anchor_row: None,
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(200.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            scroll_offset: GUIPosition::from_pixels(0., 0.),
+            scrollbar_thickness: GUILength::from_pixels(10.),
+            min_thumb_length: GUILength::from_pixels(24.),
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            selection_color: GUIColor {
+                r: 0.85,
+                g: 0.75,
+                b: 0.8,
+                a: 1.,
+            },
+            cursor_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            track_color: GUIColor {
+                r: 0.9,
+                g: 0.9,
+                b: 0.9,
+                a: 1.,
+            },
+            thumb_color: GUIColor {
+                r: 0.6,
+                g: 0.6,
+                b: 0.6,
+                a: 1.,
+            },
+            focused: false,
+            dragging: None,
+            selection_changed: false,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiwidgets::{GUIBase, GUICheckState, GUICheckbox, GUILabel, GUIWindow};
+
+    /// Returns a 200 by 100 list of a hundred thousand 20 high rows of labels.
+    fn make_list(selection_mode: GUISelectionMode) -> (GUIBase, u128, u128) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let list = GUIListView {
+            row_count: 100_000,
+            row_height: GUILength::from_pixels(20.),
+            selection_mode,
+            size: GUISize::from_pixels(200., 100.),
+            ..Default::default()
+        };
+        let list_id = guibase.add_child_to_parent(list, window_id);
+        guibase.set_row_builder(list_id, |label: &mut GUILabel, row| {
+            label.set_text(&format!("Line {}", row))
+        });
+        guibase.layout();
+        (guibase, window_id, list_id)
+    }
+
+    fn row_text(guibase: &GUIBase, list_id: u128, row: usize) -> String {
+        let row_id = guibase.get_cell_widget(list_id, row, 0).unwrap();
+        guibase
+            .get_widget_as::<GUILabel>(row_id)
+            .unwrap()
+            .text
+            .clone()
+    }
+
+    fn row_ids(guibase: &GUIBase, list_id: u128) -> BTreeSet<u128> {
+        guibase.widgets[&list_id]
+            .get_child_ids()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn only_the_visible_rows_have_widgets() {
+        let (mut guibase, _, list_id) = make_list(GUISelectionMode::Single);
+        let first_ids = row_ids(&guibase, list_id);
+        assert_eq!(first_ids.len(), 5);
+        assert_eq!(guibase.widgets.len(), 6);
+        assert_eq!(row_text(&guibase, list_id, 4), "Line 4");
+        assert_eq!(guibase.get_cell_widget(list_id, 5, 0), None);
+
+        // Scrolling reuses the widgets of the rows that went out of view.
+        assert!(guibase.scroll_input(list_id, &GUIPosition::from_pixels(0., 50.)));
+        guibase.take_dirty_widgets();
+        let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+        assert_eq!(list.visible_rows(), 2..8);
+        assert!(row_ids(&guibase, list_id).is_superset(&first_ids));
+        assert_eq!(guibase.widgets.len(), 7);
+        assert_eq!(row_text(&guibase, list_id, 7), "Line 7");
+        let row_id = guibase.get_cell_widget(list_id, 2, 0).unwrap();
+        assert_eq!(
+            guibase.get_absolute_position(row_id),
+            GUIPosition::from_pixels(0., -10.)
+        );
+
+        guibase.scroll_input(list_id, &GUIPosition::from_pixels(0., 1e9));
+        guibase.take_dirty_widgets();
+        assert_eq!(row_text(&guibase, list_id, 99_999), "Line 99999");
+        assert_eq!(guibase.widgets.len(), 6);
+    }
+
+    #[test]
+    fn a_focused_cell_scrolled_out_of_view_gives_the_focus_to_the_list() {
+        let (mut guibase, window_id, list_id) = make_list(GUISelectionMode::Single);
+        guibase.set_row_builder(list_id, |checkbox: &mut GUICheckbox, _| {
+            checkbox.state = GUICheckState::Unchecked
+        });
+        guibase.take_dirty_widgets();
+        let checkbox_id = guibase.get_cell_widget(list_id, 0, 0).unwrap();
+        assert!(guibase.set_focus(Some(checkbox_id)));
+
+        guibase.scroll_input(list_id, &GUIPosition::from_pixels(0., 1000.));
+        guibase.take_dirty_widgets();
+        assert_eq!(guibase.get_focused_widget(), Some(list_id));
+        // Space no longer ticks whichever row the checkbox's widget shows now.
+        guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Space));
+        let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+        for row in list.visible_rows() {
+            let cell_id = guibase.get_cell_widget(list_id, row, 0).unwrap();
+            let checkbox = guibase.get_widget_as::<GUICheckbox>(cell_id).unwrap();
+            assert_eq!(checkbox.state, GUICheckState::Unchecked);
+        }
+    }
+
+    #[test]
+    fn clicking_selects_rows() {
+        let (mut guibase, _, list_id) = make_list(GUISelectionMode::Multiple);
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        guibase.on_change(list_id, move |_| counter.set(counter.get() + 1));
+        let click = |guibase: &mut GUIBase, x: f64, y: f64, ctrl: bool, shift: bool| {
+            let modifiers = GUIModifiers {
+                shift,
+                ctrl,
+                logo: ctrl,
+                ..Default::default()
+            };
+            let position = GUIPosition::from_pixels(x, y);
+            guibase.pointer_input(list_id, GUIPointerEventKind::Pressed, &position, modifiers);
+            guibase.pointer_input(list_id, GUIPointerEventKind::Released, &position, modifiers);
+        };
+        let selected_rows = |guibase: &GUIBase| {
+            let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+            list.selected_rows.iter().copied().collect::<Vec<_>>()
+        };
+
+        click(&mut guibase, 50., 30., false, false);
+        click(&mut guibase, 50., 70., true, false);
+        assert_eq!(selected_rows(&guibase), vec![1, 3]);
+        click(&mut guibase, 50., 10., false, true);
+        assert_eq!(selected_rows(&guibase), vec![0, 1, 2, 3]);
+        click(&mut guibase, 50., 30., true, false);
+        assert_eq!(selected_rows(&guibase), vec![0, 2, 3]);
+        assert_eq!(changes.get(), 4);
+
+        // Clicking the scrollbar scrolls rather than selecting.
+        click(&mut guibase, 195., 90., false, false);
+        assert_eq!(selected_rows(&guibase), vec![0, 2, 3]);
+        let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+        assert!(list.scroll_offset.y.get_length() > 0.);
+    }
+
+    #[test]
+    fn the_keyboard_moves_the_selection() {
+        let (mut guibase, window_id, list_id) = make_list(GUISelectionMode::Multiple);
+        guibase.set_focus(Some(list_id));
+        let press = |guibase: &mut GUIBase, key: GUIKey, shift: bool| {
+            let mut event = GUIKeyEvent::pressed(key);
+            event.modifiers.shift = shift;
+            assert!(guibase.key_input(window_id, &event));
+        };
+
+        press(&mut guibase, GUIKey::Down, false);
+        press(&mut guibase, GUIKey::Down, false);
+        press(&mut guibase, GUIKey::PageDown, true);
+        let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+        assert_eq!(list.cursor_row, Some(6));
+        assert_eq!(list.selected_rows, BTreeSet::from([1, 2, 3, 4, 5, 6]));
+        // The cursor row is scrolled to the bottom of the list.
+        assert_eq!(list.scroll_offset, GUIPosition::from_pixels(0., 40.));
+
+        press(&mut guibase, GUIKey::End, false);
+        let list = guibase.get_widget_as::<GUIListView>(list_id).unwrap();
+        assert_eq!(list.selected_rows, BTreeSet::from([99_999]));
+        assert_eq!(
+            list.scroll_offset,
+            GUIPosition::from_pixels(0., 2_000_000. - 100.)
+        );
+    }
+}
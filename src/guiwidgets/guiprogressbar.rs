@@ -0,0 +1,231 @@
+use std::any::Any;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// How often animated progress indicators are drawn, which is about 60 times a second.
+pub(super) const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+
+/// Returns how far through its period an animation that started at start is at now,
+/// from 0 up to, but not including, 1.
+pub(super) fn animation_phase(start: Instant, now: Instant, period: Duration) -> f64 {
+    if period.is_zero() {
+        return 0.;
+    }
+    (now.saturating_duration_since(start).as_secs_f64() / period.as_secs_f64()).fract()
+}
+
+/// A bar that fills up as a long task progresses. While how far along the task is
+/// isn't known, a segment slides along the bar instead.
+pub struct GUIProgressBar {
+    /// How much of the task is done, from 0 to 1, or None if that isn't known.
+    pub progress: Option<f64>,
+    /// The size of the bar.
+    pub size: GUISize,
+    /// The location of the bar.
+    pub position: GUIPosition,
+    /// Radius of the bar corners.
+    pub radius: GUILength,
+    /// How much of the bar the sliding segment covers while the progress isn't known.
+    pub segment_fraction: f64,
+    /// How long the sliding segment takes to cross the bar.
+    pub period: Duration,
+    /// The color of the part of the bar that isn't filled.
+    pub track_color: GUIColor,
+    /// The color of the filled part of the bar, and of the sliding segment.
+    pub fill_color: GUIColor,
+    /// When the sliding segment started to move. Set by the gui.
+    pub animation_start: Option<Instant>,
+    /// How far the sliding segment is through crossing the bar, from 0 to 1. Set by the gui.
+    pub phase: f64,
+    pub id: u128,
+}
+
+impl GUIProgressBar {
+    /// Returns a bar with the default values that's filled to progress,
+    /// or that slides a segment along it if progress is None.
+    pub fn new(progress: Option<f64>) -> Self {
+        Self {
+            progress,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the left and right of the filled part of the bar,
+    /// as fractions of its width.
+    fn filled_span(&self) -> (f64, f64) {
+        match self.progress {
+            Some(progress) => (0., progress.clamp(0., 1.)),
+            None => {
+                // The segment enters from the left and leaves off of the right.
+                let segment = self.segment_fraction.clamp(0., 1.);
+                let left = self.phase * (1. + segment) - segment;
+                (left.max(0.), (left + segment).min(1.))
+            }
+        }
+    }
+}
+
+impl Widget for GUIProgressBar {
+    fn animate(&mut self, now: Instant) -> Option<Instant> {
+        if self.progress.is_some() {
+            self.animation_start = None;
+            return None;
+        }
+        let start = *self.animation_start.get_or_insert(now);
+        self.phase = animation_phase(start, now, self.period);
+        Some(now + FRAME_INTERVAL)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            self.radius,
+            &self.track_color,
+        );
+        // The bar only shows progress, so clicks go through it.
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        let (left, right) = self.filled_span();
+        if right > left {
+            let width = self.size.width.get_length();
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(
+                    origin.x.get_length() + left * width,
+                    origin.y.get_length(),
+                ),
+                &GUISize::from_lengths(
+                    GUILength::from_pixels((right - left) * width),
+                    self.size.height,
+                ),
+                self.radius,
+                &self.fill_color,
+            );
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUIProgressBar {
+    /// Returns an empty bar with all of the default values.
+    fn default() -> Self {
+        Self {
+            progress: Some(0.),
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(8.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            radius: GUILength::from_pixels(4.),
+            segment_fraction: 0.3,
+            period: Duration::from_millis(1500),
+            track_color: GUIColor {
+                r: 0.8,
+                g: 0.8,
+                b: 0.8,
+                a: 1.,
+            },
+            fill_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            animation_start: None,
+            phase: 0.,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_fill_follows_the_progress() {
+        let mut bar = GUIProgressBar::new(Some(0.25));
+        assert_eq!(bar.animate(Instant::now()), None);
+        assert_eq!(bar.filled_span(), (0., 0.25));
+        bar.progress = Some(1.5);
+        assert_eq!(bar.filled_span(), (0., 1.));
+        bar.progress = Some(0.);
+        let (vertices, _, polygon) = bar.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert_eq!(vertices.len(), polygon.end_index);
+        assert!(!polygon.rendered);
+    }
+
+    #[test]
+    fn the_segment_slides_while_the_progress_is_unknown() {
+        let mut bar = GUIProgressBar::new(None);
+        let start = Instant::now();
+        assert_eq!(bar.animate(start), Some(start + FRAME_INTERVAL));
+        assert_eq!(bar.filled_span(), (0., 0.));
+
+        bar.animate(start + Duration::from_millis(750));
+        let (left, right) = bar.filled_span();
+        assert!((left - 0.35).abs() < 1e-9 && (right - 0.65).abs() < 1e-9);
+
+        // Once the progress is known the animation stops.
+        bar.progress = Some(0.5);
+        assert_eq!(bar.animate(start + Duration::from_millis(800)), None);
+        assert_eq!(bar.animation_start, None);
+    }
+}
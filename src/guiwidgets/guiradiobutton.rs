@@ -0,0 +1,271 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// One of the choices in a GUIRadioGroup. Selecting it deselects the others in the group.
+pub struct GUIRadioButton<T> {
+    /// The text next to the circle.
+    pub label: String,
+    /// What the choice stands for, which is given to the group's on_select callback.
+    pub value: T,
+    /// Whether this is the chosen one of its group.
+    pub selected: bool,
+    /// The size of the radio button, including its label.
+    pub size: GUISize,
+    /// The location of the radio button.
+    pub position: GUIPosition,
+    /// The width and height of the circle.
+    pub circle_size: GUILength,
+    /// How thick the outline of the circle is.
+    pub border_width: GUILength,
+    /// The space between the circle and the label.
+    pub spacing: GUILength,
+    /// The color inside the circle.
+    pub background_color: GUIColor,
+    /// The color of the outline of the circle while it isn't selected.
+    pub border_color: GUIColor,
+    /// The color of the outline and the dot while it's selected.
+    pub selected_color: GUIColor,
+    /// The color of the outline and the dot while the radio button is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the radio button responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// The color of the label.
+    pub text_color: GUIColor,
+    /// The height of the label.
+    pub font_size: GUILength,
+    pub id: u128,
+}
+
+impl<T> GUIRadioButton<T> {
+    /// Returns a radio button that stands for value and isn't selected,
+    /// with all of the default values.
+    pub fn new(label: &str, value: T) -> Self {
+        Self {
+            label: String::from(label),
+            value,
+            selected: false,
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(24.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            circle_size: GUILength::from_pixels(18.),
+            border_width: GUILength::from_pixels(2.),
+            spacing: GUILength::from_pixels(8.),
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            border_color: GUIColor {
+                r: 0.45,
+                g: 0.45,
+                b: 0.45,
+                a: 1.,
+            },
+            selected_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.,
+            },
+            enabled: true,
+            text_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            font_size: GUILength::from_pixels(16.),
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+impl<T: 'static> Widget for GUIRadioButton<T> {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_selected(&mut self, selected: bool) {
+        self.selected = selected;
+    }
+
+    fn is_selected(&self) -> bool {
+        self.selected
+    }
+
+    fn get_value(&self) -> Option<&dyn Any> {
+        Some(&self.value)
+    }
+
+    fn activate(&mut self) -> bool {
+        // Clicking the selected choice again leaves it selected.
+        if !self.enabled || self.selected {
+            return false;
+        }
+        self.selected = true;
+        true
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        let text_left = self.circle_size.add(&self.spacing);
+        Vec::from([GUIText {
+            text: self.label.clone(),
+            position: GUIPosition::from_lengths(
+                self.position.x.add(&parent_position.x).add(&text_left),
+                self.position.y.add(&parent_position.y),
+            ),
+            size: GUISize::from_lengths(self.size.width.subtract(&text_left), self.size.height),
+            font_size: self.font_size,
+            color: self.text_color,
+            horizontal_alignment: GUIHorizontalAlignment::Left,
+            vertical_alignment: GUIVerticalAlignment::Center,
+            wrap: false,
+            overflow: GUITextOverflow::Ellipsis,
+        }])
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        // The label can be clicked as well as the circle, so the whole radio button
+        // is covered by an invisible rectangle that's hit tested.
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            &GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 0.,
+            },
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        let mark_color = if !self.enabled {
+            self.disabled_color
+        } else if self.selected {
+            self.selected_color
+        } else {
+            self.border_color
+        };
+        let left = origin.x.get_length();
+        let top = origin.y.get_length()
+            + (self.size.height.get_length() - self.circle_size.get_length()) / 2.;
+        // Circles are squares rounded by half of their size, inset from the outline.
+        let mut push_circle = |inset: f64, color: &GUIColor| {
+            let diameter = (self.circle_size.get_length() - 2. * inset).max(0.);
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + inset, top + inset),
+                &GUISize::from_pixels(diameter, diameter),
+                GUILength::from_pixels(diameter / 2.),
+                color,
+            );
+        };
+        push_circle(0., &mark_color);
+        push_circle(self.border_width.get_length(), &self.background_color);
+        if self.selected {
+            push_circle(self.circle_size.get_length() / 4., &mark_color);
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl<T: Default> Default for GUIRadioButton<T> {
+    /// Returns a radio button with no label that stands for T's default value
+    /// and isn't selected, with all of the default values.
+    fn default() -> Self {
+        Self::new("", T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clicking_selects_but_never_deselects() {
+        let mut radio = GUIRadioButton::new("One", 1);
+        let (unselected, _, _) = radio.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert!(radio.activate());
+        assert!(radio.is_selected());
+        assert!(!radio.activate());
+        assert!(radio.is_selected());
+
+        // The dot is drawn while it's selected.
+        let (selected, _, _) = radio.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert!(selected.len() > unselected.len());
+    }
+}
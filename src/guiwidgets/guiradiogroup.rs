@@ -0,0 +1,247 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::GUIStack;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guilayout::{
+    GUIAlignment, GUIAxis, GUIJustify, GUILayoutChild, GUIPadding,
+};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::{Parent, Widget};
+
+/// A container for GUIRadioButtons that lets only one of them be selected at a time.
+/// The buttons are placed one after another along an axis. The group is a single
+/// Tab stop, which lands on the selected button, and the arrow keys move the selection
+/// between them.
+pub struct GUIRadioGroup {
+    /// The direction that the radio buttons are placed in.
+    pub axis: GUIAxis,
+    /// The space between neighboring radio buttons.
+    pub spacing: GUILength,
+    /// The space between the edges of the group and its radio buttons.
+    pub padding: GUIPadding,
+    /// The size of the group.
+    pub size: GUISize,
+    /// The location of the group.
+    pub position: GUIPosition,
+    pub id: u128,
+}
+
+impl GUIRadioGroup {
+    /// Returns a group with the default values that places radio buttons along axis.
+    pub fn new(axis: GUIAxis) -> Self {
+        Self {
+            axis,
+            ..Default::default()
+        }
+    }
+}
+
+impl Widget for GUIRadioGroup {
+    fn get_vertices_and_indices(
+        &self,
+        _parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize,
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        (Vec::new(), Vec::new(), polygon)
+    }
+
+    fn selects_one_child(&self) -> bool {
+        true
+    }
+
+    fn arrange_children(&self, children: &[GUILayoutChild]) -> Option<Vec<(GUIPosition, GUISize)>> {
+        // The radio buttons are laid out like a stack that keeps their sizes.
+        GUIStack {
+            axis: self.axis,
+            spacing: self.spacing,
+            padding: self.padding,
+            alignment: GUIAlignment::Start,
+            justify: GUIJustify::Start,
+            size: self.size,
+            position: self.position,
+            id: self.id,
+        }
+        .arrange_children(children)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Parent for GUIRadioGroup {}
+
+impl Default for GUIRadioGroup {
+    /// Returns a vertical group with all of the default values.
+    fn default() -> Self {
+        Self {
+            axis: GUIAxis::Vertical,
+            spacing: GUILength::from_pixels(4.),
+            padding: GUIPadding::default(),
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(100.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent};
+    use crate::guiwidgets::{GUIBase, GUIButton, GUIRadioButton, GUIWindow};
+
+    fn make_group() -> (GUIBase, u128, u128, Vec<u128>) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let group_id = guibase.add_child_to_parent(GUIRadioGroup::default(), window_id);
+        let radio_ids = ["small", "medium", "large"]
+            .iter()
+            .map(|size| guibase.add_child_to_parent(GUIRadioButton::new(size, *size), group_id))
+            .collect();
+        (guibase, window_id, group_id, radio_ids)
+    }
+
+    #[test]
+    fn selecting_a_radio_button_deselects_the_others() {
+        let (mut guibase, _, group_id, radio_ids) = make_group();
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let recorder = selections.clone();
+        guibase.on_select(group_id, move |_, size: &'static str| {
+            recorder.borrow_mut().push(size)
+        });
+
+        guibase.click(radio_ids[0]);
+        guibase.click(radio_ids[2]);
+        // Clicking the selected one again doesn't change anything.
+        guibase.click(radio_ids[2]);
+        assert_eq!(*selections.borrow(), vec!["small", "large"]);
+        assert_eq!(guibase.get_selected_child(group_id), Some(radio_ids[2]));
+        assert_eq!(guibase.get_selected_value(group_id), Some("large"));
+        assert!(!guibase.get_widget(radio_ids[0]).is_selected());
+    }
+
+    #[test]
+    fn arrow_keys_move_the_selection() {
+        let (mut guibase, window_id, group_id, radio_ids) = make_group();
+        guibase
+            .get_widget_as_mut::<GUIRadioButton<&str>>(radio_ids[1])
+            .unwrap()
+            .enabled = false;
+        guibase.set_focus(Some(radio_ids[0]));
+
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Down)));
+        assert_eq!(guibase.get_focused_widget(), Some(radio_ids[2]));
+        assert_eq!(guibase.get_selected_value(group_id), Some("large"));
+
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Right)));
+        assert_eq!(guibase.get_selected_value(group_id), Some("small"));
+        assert!(guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Up)));
+        assert_eq!(guibase.get_selected_child(group_id), Some(radio_ids[2]));
+    }
+
+    #[test]
+    fn the_group_is_one_tab_stop_at_the_selected_button() {
+        let (mut guibase, window_id, group_id, radio_ids) = make_group();
+        let button_id = guibase.add_child_to_parent(GUIButton::default(), window_id);
+        assert_eq!(
+            guibase.get_focus_order(window_id),
+            vec![radio_ids[0], button_id]
+        );
+
+        guibase.click(radio_ids[1]);
+        assert_eq!(
+            guibase.get_focus_order(window_id),
+            vec![radio_ids[1], button_id]
+        );
+        guibase.set_focus(Some(button_id));
+        guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Tab));
+        assert_eq!(guibase.get_focused_widget(), Some(radio_ids[1]));
+        guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Down));
+        assert_eq!(guibase.get_focused_widget(), Some(radio_ids[2]));
+        guibase.key_input(window_id, &GUIKeyEvent::pressed(GUIKey::Tab));
+        assert_eq!(guibase.get_focused_widget(), Some(button_id));
+        assert_eq!(guibase.get_selected_child(group_id), Some(radio_ids[2]));
+    }
+
+    #[test]
+    fn select_deselects_the_others_without_callbacks() {
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Size {
+            Small,
+            Large,
+        }
+
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let group_id = guibase.add_child_to_parent(GUIRadioGroup::default(), window_id);
+        let small_id =
+            guibase.add_child_to_parent(GUIRadioButton::new("Small", Size::Small), group_id);
+        let large_id =
+            guibase.add_child_to_parent(GUIRadioButton::new("Large", Size::Large), group_id);
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let recorder = selections.clone();
+        guibase.on_select(group_id, move |_, size: Size| {
+            recorder.borrow_mut().push(size)
+        });
+
+        assert!(guibase.select(small_id));
+        assert!(guibase.select(large_id));
+        assert!(!guibase.select(large_id));
+        assert_eq!(guibase.get_selected_value(group_id), Some(Size::Large));
+        assert!(!guibase.get_widget(small_id).is_selected());
+        assert!(selections.borrow().is_empty());
+        // A widget that can't be selected isn't.
+        assert!(!guibase.select(group_id));
+    }
+
+    #[test]
+    fn radio_buttons_are_stacked() {
+        let (mut guibase, _, _, radio_ids) = make_group();
+        guibase.layout();
+        let positions: Vec<f64> = radio_ids
+            .iter()
+            .map(|id| guibase.get_widget(*id).get_position().y.get_length())
+            .collect();
+        assert_eq!(positions, vec![0., 28., 56.]);
+    }
+}
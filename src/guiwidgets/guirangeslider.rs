@@ -0,0 +1,398 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::guislider;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent};
+use crate::guiproperties::guilayout::GUIAxis;
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// One of the two thumbs of a GUIRangeSlider.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GUIRangeThumb {
+    /// The thumb that picks the start of the range.
+    #[default]
+    Low,
+    /// The thumb that picks the end of the range.
+    High,
+}
+
+/// Two thumbs that are dragged along a track to pick a range between min and max.
+/// The keyboard moves one thumb at a time. Tab goes from the low thumb to the high one
+/// and Shift+Tab goes back, before they move the focus on to the other widgets.
+pub struct GUIRangeSlider {
+    /// The start of the range that's picked.
+    pub low: f64,
+    /// The end of the range that's picked, which is never less than low.
+    pub high: f64,
+    /// The number at the start of the track, which is the left or the bottom.
+    pub min: f64,
+    /// The number at the end of the track, which is the right or the top.
+    pub max: f64,
+    /// The values are kept to a multiple of step from min, or anywhere if step is 0.
+    pub step: f64,
+    /// The direction that the track runs in.
+    pub axis: GUIAxis,
+    /// The thumb that the keyboard moves, which is the last one that was dragged
+    /// or tabbed to. It's the low one when the slider is given focus.
+    pub active_thumb: GUIRangeThumb,
+    /// The size of the slider.
+    pub size: GUISize,
+    /// The location of the slider.
+    pub position: GUIPosition,
+    /// How thick the track is.
+    pub track_thickness: GUILength,
+    /// The width and height of the thumbs.
+    pub thumb_size: GUILength,
+    /// The color of the track.
+    pub track_color: GUIColor,
+    /// The color of the track between the thumbs.
+    pub fill_color: GUIColor,
+    /// The color of the thumbs.
+    pub thumb_color: GUIColor,
+    /// The color of the fill and the thumbs while the slider is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the slider responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// Whether the range has changed since the change callback was last run. Set by the gui.
+    pub value_changed: bool,
+    pub id: u128,
+}
+
+impl GUIRangeSlider {
+    /// Returns a slider with the default values that picks a range between min and max,
+    /// starting with all of it.
+    pub fn new(min: f64, max: f64) -> Self {
+        Self {
+            low: min,
+            high: max,
+            min,
+            max,
+            ..Default::default()
+        }
+    }
+
+    /// Moves one of the thumbs to value, snapped to step and kept between min and max.
+    /// A thumb can't be moved past the other one.
+    /// Returns true if the range changed.
+    pub fn set_thumb_value(&mut self, thumb: GUIRangeThumb, value: f64) -> bool {
+        let value = guislider::snap(value, self.min, self.max, self.step);
+        let (current, value) = match thumb {
+            GUIRangeThumb::Low => (&mut self.low, value.min(self.high)),
+            GUIRangeThumb::High => (&mut self.high, value.max(self.low)),
+        };
+        if value == *current {
+            return false;
+        }
+        *current = value;
+        true
+    }
+
+    /// Moves a thumb the way that set_thumb_value does, noting that the gui moved it
+    /// so that the change callback is run.
+    fn input_thumb_value(&mut self, thumb: GUIRangeThumb, value: f64) -> bool {
+        let changed = self.set_thumb_value(thumb, value);
+        self.value_changed |= changed;
+        changed
+    }
+
+    fn thumb_value(&self, thumb: GUIRangeThumb) -> f64 {
+        match thumb {
+            GUIRangeThumb::Low => self.low,
+            GUIRangeThumb::High => self.high,
+        }
+    }
+}
+
+impl Widget for GUIRangeSlider {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        if focused {
+            self.active_thumb = GUIRangeThumb::Low;
+        }
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        if !event.pressed {
+            return false;
+        }
+        let modifiers = &event.modifiers;
+        if event.key == GUIKey::Tab && !modifiers.ctrl && !modifiers.alt && !modifiers.logo {
+            // Tab only moves between the thumbs, and leaves moving the focus
+            // past the last one to the gui.
+            let next = match (self.active_thumb, modifiers.shift) {
+                (GUIRangeThumb::Low, false) => GUIRangeThumb::High,
+                (GUIRangeThumb::High, true) => GUIRangeThumb::Low,
+                _ => return false,
+            };
+            self.active_thumb = next;
+            return true;
+        }
+        let thumb = self.active_thumb;
+        match guislider::key_target(
+            event.key,
+            self.thumb_value(thumb),
+            self.min,
+            self.max,
+            self.step,
+        ) {
+            Some(value) => {
+                self.input_thumb_value(thumb, value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        let fraction = guislider::fraction_at(
+            self.axis,
+            &self.size,
+            self.thumb_size.get_length(),
+            &event.position,
+        );
+        let value = self.min + fraction * (self.max - self.min);
+        match event.kind {
+            // Grab whichever thumb is closer, or the one that moves the right way
+            // when they're on top of each other.
+            GUIPointerEventKind::Pressed => {
+                let low_distance = (value - self.low).abs();
+                let high_distance = (value - self.high).abs();
+                self.active_thumb = if low_distance < high_distance
+                    || (low_distance == high_distance && value < self.low)
+                {
+                    GUIRangeThumb::Low
+                } else {
+                    GUIRangeThumb::High
+                };
+            }
+            GUIPointerEventKind::Moved => {}
+            GUIPointerEventKind::Released => return false,
+        }
+        self.input_thumb_value(self.active_thumb, value);
+        true
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.value_changed)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let polygon = guislider::push_hit_area(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            self.id,
+        );
+
+        let (fill_color, thumb_color) = if self.enabled {
+            (&self.fill_color, &self.thumb_color)
+        } else {
+            (&self.disabled_color, &self.disabled_color)
+        };
+        let low = guislider::fraction_of(self.low, self.min, self.max);
+        let high = guislider::fraction_of(self.high, self.min, self.max);
+        guislider::push_track_and_thumbs(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            self.axis,
+            &self.size,
+            self.track_thickness.get_length(),
+            self.thumb_size.get_length(),
+            (low, high),
+            &[low, high],
+            [&self.track_color, fill_color, thumb_color],
+        );
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUIRangeSlider {
+    /// Returns a horizontal slider from 0 to 1, with all of it picked,
+    /// with all of the default values.
+    fn default() -> Self {
+        Self {
+            low: 0.,
+            high: 1.,
+            min: 0.,
+            max: 1.,
+            step: 0.,
+            axis: GUIAxis::Horizontal,
+            active_thumb: GUIRangeThumb::default(),
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(24.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            track_thickness: GUILength::from_pixels(4.),
+            thumb_size: GUILength::from_pixels(18.),
+            track_color: GUIColor {
+                r: 0.8,
+                g: 0.8,
+                b: 0.8,
+                a: 1.,
+            },
+            fill_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            thumb_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.,
+            },
+            enabled: true,
+            value_changed: false,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guikeys::{GUIKey, GUIModifiers};
+
+    fn pointer(kind: GUIPointerEventKind, x: f64) -> GUIPointerEvent {
+        GUIPointerEvent {
+            kind,
+            position: GUIPosition::from_pixels(x, 12.),
+            modifiers: GUIModifiers::default(),
+        }
+    }
+
+    #[test]
+    fn the_closer_thumb_is_dragged_but_not_past_the_other() {
+        // The thumbs' centers travel from 9 to 191.
+        let mut slider = GUIRangeSlider::new(0., 10.);
+        slider.pointer_input(&pointer(GUIPointerEventKind::Pressed, 172.8));
+        assert_eq!(slider.active_thumb, GUIRangeThumb::High);
+        assert!((slider.high - 9.).abs() < 1e-9);
+
+        slider.pointer_input(&pointer(GUIPointerEventKind::Pressed, 27.2));
+        assert_eq!(slider.active_thumb, GUIRangeThumb::Low);
+        slider.pointer_input(&pointer(GUIPointerEventKind::Moved, 500.));
+        assert_eq!(slider.low, slider.high);
+        assert!(slider.take_changed());
+
+        // With the thumbs together, dragging down takes the low one.
+        slider.pointer_input(&pointer(GUIPointerEventKind::Pressed, 9.));
+        assert_eq!(slider.active_thumb, GUIRangeThumb::Low);
+        assert_eq!(slider.low, 0.);
+    }
+
+    #[test]
+    fn keys_move_the_active_thumb() {
+        let mut slider = GUIRangeSlider {
+            step: 1.,
+            ..GUIRangeSlider::new(0., 10.)
+        };
+        assert!(slider.key_input(&GUIKeyEvent::pressed(GUIKey::Right)));
+        assert_eq!((slider.low, slider.high), (1., 10.));
+        slider.active_thumb = GUIRangeThumb::High;
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        assert_eq!((slider.low, slider.high), (1., 1.));
+    }
+
+    #[test]
+    fn tab_moves_between_the_thumbs_before_leaving() {
+        let mut slider = GUIRangeSlider::new(0., 10.);
+        slider.active_thumb = GUIRangeThumb::High;
+        slider.set_focused(true);
+        assert_eq!(slider.active_thumb, GUIRangeThumb::Low);
+
+        let shift_tab = GUIKeyEvent {
+            key: GUIKey::Tab,
+            modifiers: GUIModifiers {
+                shift: true,
+                ..Default::default()
+            },
+            pressed: true,
+        };
+        assert!(!slider.key_input(&shift_tab));
+        assert!(slider.key_input(&GUIKeyEvent::pressed(GUIKey::Tab)));
+        assert_eq!(slider.active_thumb, GUIRangeThumb::High);
+        assert!(!slider.key_input(&GUIKeyEvent::pressed(GUIKey::Tab)));
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        assert_eq!((slider.low, slider.high), (0., 0.));
+        assert!(slider.key_input(&shift_tab));
+        assert_eq!(slider.active_thumb, GUIRangeThumb::Low);
+    }
+
+    #[test]
+    fn only_the_gui_changing_the_range_runs_the_change_callback() {
+        let mut slider = GUIRangeSlider::new(0., 10.);
+        assert!(slider.set_thumb_value(GUIRangeThumb::Low, 5.));
+        assert!(!slider.take_changed());
+        // A key that doesn't move the thumb isn't a change either.
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        assert!(slider.take_changed());
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::Tab));
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        assert!(!slider.take_changed());
+    }
+}
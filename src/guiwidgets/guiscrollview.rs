@@ -0,0 +1,613 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guilayout::{GUIAxis, GUILayoutChild, GUIPadding};
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::{Parent, Widget};
+use crate::guiproperties::GUIColor;
+
+/// Returns where a scrollbar's thumb starts along a track that's track long, and how long
+/// the thumb is, for children that are content long and have been scrolled offset out of max.
+pub(super) fn thumb_span(
+    track: f64,
+    content: f64,
+    offset: f64,
+    max: f64,
+    min_length: f64,
+) -> (f64, f64) {
+    let length = if content > 0. {
+        (track * track / content).max(min_length).min(track)
+    } else {
+        track
+    };
+    let start = if max > 0. {
+        (track - length) * offset / max
+    } else {
+        0.
+    };
+    (start, length)
+}
+
+/// Returns the scroll offset, out of max, that a thumb that's length long
+/// is at when it starts at thumb_start along a track that's track long.
+pub(super) fn thumb_offset(track: f64, length: f64, thumb_start: f64, max: f64) -> f64 {
+    if track > length {
+        thumb_start / (track - length) * max
+    } else {
+        0.
+    }
+}
+
+/// A container whose children can be bigger than it is. Only the part of the children
+/// within its viewport is shown, and they're scrolled with the mouse wheel, a trackpad,
+/// or by dragging the scrollbars along its right and bottom edges.
+/// Children are placed at the top left of the padding, so a scroll view usually
+/// holds a single container, such as a GUIStack, with the rest of the widgets in it.
+pub struct GUIScrollView {
+    /// Whether the children can be scrolled left and right.
+    pub horizontal: bool,
+    /// Whether the children can be scrolled up and down.
+    pub vertical: bool,
+    /// The size of the scroll view, including its scrollbars.
+    pub size: GUISize,
+    /// The location of the scroll view.
+    pub position: GUIPosition,
+    /// The space between the edges of the children's area and the children.
+    pub padding: GUIPadding,
+    /// How far the children are scrolled right and down.
+    pub scroll_offset: GUIPosition,
+    /// How much room the children take up, including the padding. Measured by the gui.
+    pub content_size: GUISize,
+    /// How thick the scrollbars are.
+    pub scrollbar_thickness: GUILength,
+    /// The shortest that a scrollbar's thumb gets, however long the children are.
+    pub min_thumb_length: GUILength,
+    /// The color behind the children.
+    pub background_color: GUIColor,
+    /// The color of the scrollbars' tracks.
+    pub track_color: GUIColor,
+    /// The color of the scrollbars' thumbs.
+    pub thumb_color: GUIColor,
+    /// The scrollbar whose thumb is being dragged, and how far along the thumb
+    /// it was grabbed. Set by the gui.
+    pub dragging: Option<(GUIAxis, f64)>,
+    pub id: u128,
+}
+
+impl GUIScrollView {
+    /// Returns a scroll view with the default values, which scrolls up and down.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the horizontal and vertical scrollbars are shown,
+    /// which they are when the children don't fit along an axis that scrolls.
+    fn scrollbars(&self) -> (bool, bool) {
+        let thickness = self.scrollbar_thickness.get_length();
+        let (width, height) = (self.size.width.get_length(), self.size.height.get_length());
+        let (content_width, content_height) = (
+            self.content_size.width.get_length(),
+            self.content_size.height.get_length(),
+        );
+        let mut vertical = self.vertical && content_height > height;
+        let horizontal =
+            self.horizontal && content_width > width - if vertical { thickness } else { 0. };
+        // The horizontal scrollbar takes room from the children, which may mean
+        // that they no longer fit vertically.
+        vertical =
+            self.vertical && content_height > height - if horizontal { thickness } else { 0. };
+        (horizontal, vertical)
+    }
+
+    /// Returns the size of the area that the children are shown in.
+    fn viewport(&self) -> GUISize {
+        let thickness = self.scrollbar_thickness.get_length();
+        let (horizontal, vertical) = self.scrollbars();
+        GUISize::from_pixels(
+            (self.size.width.get_length() - if vertical { thickness } else { 0. }).max(0.),
+            (self.size.height.get_length() - if horizontal { thickness } else { 0. }).max(0.),
+        )
+    }
+
+    /// Returns how far the children can be scrolled right and down.
+    fn max_scroll_offset(&self) -> (f64, f64) {
+        let viewport = self.viewport();
+        let max = |scrolls: bool, content: GUILength, view: GUILength| {
+            if scrolls {
+                (content.get_length() - view.get_length()).max(0.)
+            } else {
+                0.
+            }
+        };
+        (
+            max(self.horizontal, self.content_size.width, viewport.width),
+            max(self.vertical, self.content_size.height, viewport.height),
+        )
+    }
+
+    /// Returns the length of a scrollbar's track along axis, along with where
+    /// its thumb starts and how long the thumb is.
+    fn thumb(&self, axis: GUIAxis) -> (f64, f64, f64) {
+        let viewport = self.viewport();
+        let (max_x, max_y) = self.max_scroll_offset();
+        let (track, content, offset, max) = match axis {
+            GUIAxis::Horizontal => (
+                viewport.width.get_length(),
+                self.content_size.width.get_length(),
+                self.scroll_offset.x.get_length(),
+                max_x,
+            ),
+            GUIAxis::Vertical => (
+                viewport.height.get_length(),
+                self.content_size.height.get_length(),
+                self.scroll_offset.y.get_length(),
+                max_y,
+            ),
+        };
+        let (start, length) = thumb_span(
+            track,
+            content,
+            offset,
+            max,
+            self.min_thumb_length.get_length(),
+        );
+        (track, start, length)
+    }
+
+    /// Scrolls so that the thumb along axis starts at thumb_start.
+    /// Returns true if the children moved.
+    fn drag_thumb_to(&mut self, axis: GUIAxis, thumb_start: f64) -> bool {
+        let (track, _, length) = self.thumb(axis);
+        let (max_x, max_y) = self.max_scroll_offset();
+        let offset = match axis {
+            GUIAxis::Horizontal => GUIPosition::from_lengths(
+                GUILength::from_pixels(thumb_offset(track, length, thumb_start, max_x)),
+                self.scroll_offset.y,
+            ),
+            GUIAxis::Vertical => GUIPosition::from_lengths(
+                self.scroll_offset.x,
+                GUILength::from_pixels(thumb_offset(track, length, thumb_start, max_y)),
+            ),
+        };
+        self.set_scroll_offset(offset)
+    }
+
+    /// Returns the scrollbar that a point, relative to the scroll view, is on, if any.
+    fn scrollbar_at(&self, x: f64, y: f64) -> Option<GUIAxis> {
+        let viewport = self.viewport();
+        let (horizontal, vertical) = self.scrollbars();
+        let (view_width, view_height) = (viewport.width.get_length(), viewport.height.get_length());
+        if vertical && x >= view_width && y < view_height {
+            Some(GUIAxis::Vertical)
+        } else if horizontal && y >= view_height && x < view_width {
+            Some(GUIAxis::Horizontal)
+        } else {
+            None
+        }
+    }
+}
+
+impl Widget for GUIScrollView {
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        let (x, y) = (event.position.x.get_length(), event.position.y.get_length());
+        match event.kind {
+            GUIPointerEventKind::Pressed => {
+                let axis = match self.scrollbar_at(x, y) {
+                    Some(axis) => axis,
+                    None => return false,
+                };
+                let along = match axis {
+                    GUIAxis::Horizontal => x,
+                    GUIAxis::Vertical => y,
+                };
+                let (_, start, length) = self.thumb(axis);
+                if (start..start + length).contains(&along) {
+                    self.dragging = Some((axis, along - start));
+                    false
+                } else {
+                    // Clicking the track jumps the thumb to the pointer, ready to be dragged.
+                    self.dragging = Some((axis, length / 2.));
+                    self.drag_thumb_to(axis, along - length / 2.)
+                }
+            }
+            GUIPointerEventKind::Moved => match self.dragging {
+                Some((GUIAxis::Horizontal, grab)) => {
+                    self.drag_thumb_to(GUIAxis::Horizontal, x - grab)
+                }
+                Some((GUIAxis::Vertical, grab)) => self.drag_thumb_to(GUIAxis::Vertical, y - grab),
+                None => false,
+            },
+            GUIPointerEventKind::Released => {
+                self.dragging = None;
+                false
+            }
+        }
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+        self.set_scroll_offset(self.scroll_offset);
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn arrange_children(&self, children: &[GUILayoutChild]) -> Option<Vec<(GUIPosition, GUISize)>> {
+        let (inner_position, inner_size) = self.padding.inset(&self.viewport());
+        let fit = |scrolls: bool, preferred: GUILength, available: GUILength| {
+            if !scrolls || preferred.get_length() < available.get_length() {
+                available
+            } else {
+                preferred
+            }
+        };
+        Some(
+            children
+                .iter()
+                .map(|child| {
+                    let size = if child.item.fill {
+                        // Filling children take up the viewport along the axes that don't scroll,
+                        // and at least the viewport along the ones that do.
+                        GUISize::from_lengths(
+                            fit(
+                                self.horizontal,
+                                child.preferred_size.width,
+                                inner_size.width,
+                            ),
+                            fit(
+                                self.vertical,
+                                child.preferred_size.height,
+                                inner_size.height,
+                            ),
+                        )
+                    } else {
+                        child.preferred_size
+                    };
+                    (inner_position, size)
+                })
+                .collect(),
+        )
+    }
+
+    fn get_viewport(&self) -> Option<(GUIPosition, GUISize)> {
+        Some((GUIPosition::default(), self.viewport()))
+    }
+
+    fn set_content_size(&mut self, size: GUISize) -> bool {
+        let content_size = GUISize::from_lengths(
+            size.width.add(&self.padding.right),
+            size.height.add(&self.padding.bottom),
+        );
+        if content_size == self.content_size {
+            return false;
+        }
+        self.content_size = content_size;
+        self.set_scroll_offset(self.scroll_offset);
+        true
+    }
+
+    fn get_scroll_offset(&self) -> GUIPosition {
+        self.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: GUIPosition) -> bool {
+        let (max_x, max_y) = self.max_scroll_offset();
+        let offset = GUIPosition::from_pixels(
+            offset.x.get_length().clamp(0., max_x),
+            offset.y.get_length().clamp(0., max_y),
+        );
+        if offset == self.scroll_offset {
+            return false;
+        }
+        self.scroll_offset = offset;
+        true
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        // The background covers the whole scroll view so that the mouse wheel
+        // works anywhere over it, even where there aren't any children.
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            &self.background_color,
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        let thickness = self.scrollbar_thickness;
+        let viewport = self.viewport();
+        let (horizontal, vertical) = self.scrollbars();
+        let (left, top) = (origin.x.get_length(), origin.y.get_length());
+        let (view_width, view_height) = (viewport.width.get_length(), viewport.height.get_length());
+        if vertical {
+            let (track, start, length) = self.thumb(GUIAxis::Vertical);
+            widget_utils::push_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + view_width, top),
+                &GUISize::from_lengths(thickness, GUILength::from_pixels(track)),
+                &self.track_color,
+            );
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + view_width, top + start),
+                &GUISize::from_lengths(thickness, GUILength::from_pixels(length)),
+                GUILength::from_pixels(thickness.get_length() / 2.),
+                &self.thumb_color,
+            );
+        }
+        if horizontal {
+            let (track, start, length) = self.thumb(GUIAxis::Horizontal);
+            widget_utils::push_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left, top + view_height),
+                &GUISize::from_lengths(GUILength::from_pixels(track), thickness),
+                &self.track_color,
+            );
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + start, top + view_height),
+                &GUISize::from_lengths(GUILength::from_pixels(length), thickness),
+                GUILength::from_pixels(thickness.get_length() / 2.),
+                &self.thumb_color,
+            );
+        }
+        if horizontal && vertical {
+            // The corner where the scrollbars meet.
+            widget_utils::push_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(left + view_width, top + view_height),
+                &GUISize::from_lengths(thickness, thickness),
+                &self.track_color,
+            );
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Parent for GUIScrollView {}
+
+impl Default for GUIScrollView {
+    /// Returns a scroll view that scrolls up and down, with all of the default values.
+    fn default() -> Self {
+        Self {
+            horizontal: false,
+            vertical: true,
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(200.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            padding: GUIPadding::default(),
+            scroll_offset: GUIPosition::from_pixels(0., 0.),
+            content_size: GUISize::from_pixels(0., 0.),
+            scrollbar_thickness: GUILength::from_pixels(10.),
+            min_thumb_length: GUILength::from_pixels(24.),
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 0.,
+            },
+            track_color: GUIColor {
+                r: 0.9,
+                g: 0.9,
+                b: 0.9,
+                a: 1.,
+            },
+            thumb_color: GUIColor {
+                r: 0.6,
+                g: 0.6,
+                b: 0.6,
+                a: 1.,
+            },
+            dragging: None,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiprocessing::processing_utils;
+    use crate::guiproperties::guikeys::GUIModifiers;
+    use crate::guiwidgets::{GUIBase, GUIButton, GUIStack, GUIWindow};
+
+    /// Returns a 200 by 100 scroll view holding a column of ten 40 high buttons.
+    fn make_scroll_view() -> (GUIBase, u128, u128, Vec<u128>) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let mut scroll_view = GUIScrollView::new();
+        scroll_view.size = GUISize::from_pixels(200., 100.);
+        let scroll_view_id = guibase.add_child_to_parent(scroll_view, window_id);
+        let mut column = GUIStack::new(GUIAxis::Vertical);
+        column.size = GUISize::from_pixels(100., 400.);
+        let column_id = guibase.add_child_to_parent(column, scroll_view_id);
+        let button_ids = (0..10)
+            .map(|_| {
+                let button = GUIButton {
+                    size: GUISize::from_pixels(100., 40.),
+                    ..Default::default()
+                };
+                guibase.add_child_to_parent(button, column_id)
+            })
+            .collect();
+        guibase.layout();
+        (guibase, window_id, scroll_view_id, button_ids)
+    }
+
+    #[test]
+    fn scrolling_is_kept_within_the_children() {
+        let (mut guibase, _, scroll_view_id, button_ids) = make_scroll_view();
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(scroll_view.content_size, GUISize::from_pixels(100., 400.));
+        assert_eq!(scroll_view.scrollbars(), (false, true));
+        assert_eq!(
+            scroll_view.get_viewport(),
+            Some((GUIPosition::default(), GUISize::from_pixels(190., 100.)))
+        );
+
+        // Scrolling over a child scrolls the scroll view that it's in.
+        assert!(guibase.scroll_input(button_ids[0], &GUIPosition::from_pixels(0., 120.)));
+        assert_eq!(
+            guibase.get_absolute_position(button_ids[1]),
+            GUIPosition::from_pixels(0., -80.)
+        );
+        assert!(guibase.scroll_input(button_ids[0], &GUIPosition::from_pixels(50., 1000.)));
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(
+            scroll_view.scroll_offset,
+            GUIPosition::from_pixels(0., 300.)
+        );
+        assert!(!guibase.scroll_input(button_ids[0], &GUIPosition::from_pixels(0., 10.)));
+
+        // Focusing a child that's out of view scrolls back to it.
+        guibase.set_focus(Some(button_ids[2]));
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(scroll_view.scroll_offset, GUIPosition::from_pixels(0., 80.));
+    }
+
+    #[test]
+    fn children_cant_be_clicked_outside_of_the_viewport() {
+        let (mut guibase, window_id, scroll_view_id, button_ids) = make_scroll_view();
+        let (vertices, _, polygons) =
+            processing_utils::make_vertices_and_indices(&guibase, window_id);
+        // The fourth button is below the scroll view, where the window is.
+        let below = GUIPosition::from_pixels(50., 130.);
+        assert_eq!(
+            processing_utils::get_clicked_widget(&polygons, &vertices, &below),
+            None
+        );
+        let inside = GUIPosition::from_pixels(50., 90.);
+        assert_eq!(
+            processing_utils::get_clicked_widget(&polygons, &vertices, &inside),
+            Some(button_ids[2])
+        );
+        // The scrollbar isn't covered by the children.
+        let scrollbar = GUIPosition::from_pixels(195., 50.);
+        assert_eq!(
+            processing_utils::get_clicked_widget(&polygons, &vertices, &scrollbar),
+            Some(scroll_view_id)
+        );
+
+        guibase.scroll_input(scroll_view_id, &GUIPosition::from_pixels(0., 60.));
+        let (vertices, _, polygons) =
+            processing_utils::make_vertices_and_indices(&guibase, window_id);
+        assert_eq!(
+            processing_utils::get_clicked_widget(&polygons, &vertices, &inside),
+            Some(button_ids[3])
+        );
+    }
+
+    #[test]
+    fn dragging_the_thumb_scrolls() {
+        let (mut guibase, _, scroll_view_id, _) = make_scroll_view();
+        let modifiers = GUIModifiers::default();
+        // The thumb is a quarter of the track, so the track has 75 to drag it along.
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(scroll_view.thumb(GUIAxis::Vertical), (100., 0., 25.));
+        guibase.pointer_input(
+            scroll_view_id,
+            GUIPointerEventKind::Pressed,
+            &GUIPosition::from_pixels(195., 10.),
+            modifiers,
+        );
+        assert!(guibase.pointer_input(
+            scroll_view_id,
+            GUIPointerEventKind::Moved,
+            &GUIPosition::from_pixels(195., 47.5),
+            modifiers,
+        ));
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(
+            scroll_view.scroll_offset,
+            GUIPosition::from_pixels(0., 150.)
+        );
+        guibase.pointer_input(
+            scroll_view_id,
+            GUIPointerEventKind::Released,
+            &GUIPosition::from_pixels(195., 47.5),
+            modifiers,
+        );
+
+        // Clicking the track below the thumb centers the thumb on the pointer.
+        assert!(guibase.pointer_input(
+            scroll_view_id,
+            GUIPointerEventKind::Pressed,
+            &GUIPosition::from_pixels(195., 87.5),
+            modifiers,
+        ));
+        let scroll_view = guibase
+            .get_widget_as::<GUIScrollView>(scroll_view_id)
+            .unwrap();
+        assert_eq!(
+            scroll_view.scroll_offset,
+            GUIPosition::from_pixels(0., 300.)
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent};
+use crate::guiproperties::guilayout::GUIAxis;
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// A thumb that's dragged along a track to pick a number between min and max.
+pub struct GUISlider {
+    /// The number that's picked.
+    pub value: f64,
+    /// The number at the start of the track, which is the left or the bottom.
+    pub min: f64,
+    /// The number at the end of the track, which is the right or the top.
+    pub max: f64,
+    /// The value is kept to a multiple of step from min, or anywhere if step is 0.
+    pub step: f64,
+    /// The direction that the track runs in.
+    pub axis: GUIAxis,
+    /// The size of the slider.
+    pub size: GUISize,
+    /// The location of the slider.
+    pub position: GUIPosition,
+    /// How thick the track is.
+    pub track_thickness: GUILength,
+    /// The width and height of the thumb.
+    pub thumb_size: GUILength,
+    /// The color of the track.
+    pub track_color: GUIColor,
+    /// The color of the track between min and the thumb.
+    pub fill_color: GUIColor,
+    /// The color of the thumb.
+    pub thumb_color: GUIColor,
+    /// The color of the fill and the thumb while the slider is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the slider responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// Whether the value has changed since the change callback was last run. Set by the gui.
+    pub value_changed: bool,
+    pub id: u128,
+}
+
+impl GUISlider {
+    /// Returns a slider with the default values that picks a number between min and max.
+    pub fn new(min: f64, max: f64) -> Self {
+        Self {
+            value: min,
+            min,
+            max,
+            ..Default::default()
+        }
+    }
+
+    /// Sets the value, snapped to step and kept between min and max.
+    /// Returns true if the value changed.
+    pub fn set_value(&mut self, value: f64) -> bool {
+        let value = snap(value, self.min, self.max, self.step);
+        if value == self.value {
+            return false;
+        }
+        self.value = value;
+        true
+    }
+
+    /// Sets the value the way that set_value does, noting that the gui changed it
+    /// so that the change callback is run.
+    fn input_value(&mut self, value: f64) -> bool {
+        let changed = self.set_value(value);
+        self.value_changed |= changed;
+        changed
+    }
+}
+
+/// Snaps value to the nearest multiple of step from min, and keeps it between min and max.
+pub(super) fn snap(value: f64, min: f64, max: f64, step: f64) -> f64 {
+    let value = if step > 0. {
+        min + ((value - min) / step).round() * step
+    } else {
+        value
+    };
+    value.max(min).min(max)
+}
+
+/// Returns how far along the track value is, from 0 at min to 1 at max.
+pub(super) fn fraction_of(value: f64, min: f64, max: f64) -> f64 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0., 1.)
+    } else {
+        0.
+    }
+}
+
+/// Returns where the center of a thumb that's fraction of the way along the track is,
+/// relative to the top left corner of the slider.
+pub(super) fn thumb_center(
+    axis: GUIAxis,
+    size: &GUISize,
+    thumb_size: f64,
+    fraction: f64,
+) -> [f64; 2] {
+    let (width, height) = (size.width.get_length(), size.height.get_length());
+    match axis {
+        GUIAxis::Horizontal => [
+            thumb_size / 2. + fraction * (width - thumb_size),
+            height / 2.,
+        ],
+        GUIAxis::Vertical => [
+            width / 2.,
+            height - thumb_size / 2. - fraction * (height - thumb_size),
+        ],
+    }
+}
+
+/// Returns how far along the track the point closest to position is, from 0 to 1.
+/// position is relative to the top left corner of the slider.
+pub(super) fn fraction_at(
+    axis: GUIAxis,
+    size: &GUISize,
+    thumb_size: f64,
+    position: &GUIPosition,
+) -> f64 {
+    let (along, length) = match axis {
+        GUIAxis::Horizontal => (position.x.get_length(), size.width.get_length()),
+        // The track starts at the bottom.
+        GUIAxis::Vertical => (
+            size.height.get_length() - position.y.get_length(),
+            size.height.get_length(),
+        ),
+    };
+    let usable = length - thumb_size;
+    if usable > 0. {
+        ((along - thumb_size / 2.) / usable).clamp(0., 1.)
+    } else {
+        0.
+    }
+}
+
+/// Returns where a key moves a value to, or None if the key doesn't move it.
+/// The arrow keys move it by a step, or by a hundredth of the range if there isn't a step,
+/// Page Up and Page Down by ten times that, and Home and End to the ends.
+pub(super) fn key_target(key: GUIKey, value: f64, min: f64, max: f64, step: f64) -> Option<f64> {
+    let increment = if step > 0. { step } else { (max - min) / 100. };
+    match key {
+        GUIKey::Right | GUIKey::Up => Some(value + increment),
+        GUIKey::Left | GUIKey::Down => Some(value - increment),
+        GUIKey::PageUp => Some(value + 10. * increment),
+        GUIKey::PageDown => Some(value - 10. * increment),
+        GUIKey::Home => Some(min),
+        GUIKey::End => Some(max),
+        _ => None,
+    }
+}
+
+/// Adds the track of a slider, with the part between the start and end fractions filled in,
+/// followed by a thumb at each of thumb_fractions.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn push_track_and_thumbs(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    origin: &GUIPosition,
+    axis: GUIAxis,
+    size: &GUISize,
+    track_thickness: f64,
+    thumb_size: f64,
+    fill: (f64, f64),
+    thumb_fractions: &[f64],
+    colors: [&GUIColor; 3],
+) {
+    let [track_color, fill_color, thumb_color] = colors;
+    let (left, top) = (origin.x.get_length(), origin.y.get_length());
+    let mut push_segment = |start: f64, end: f64, color: &GUIColor| {
+        let [x0, y0] = thumb_center(axis, size, thumb_size, start);
+        let [x1, y1] = thumb_center(axis, size, thumb_size, end);
+        // Reach out to the ends of the track, which are round.
+        let (position, extent) = match axis {
+            GUIAxis::Horizontal => (
+                [x0 - track_thickness / 2., y0 - track_thickness / 2.],
+                [x1 - x0 + track_thickness, track_thickness],
+            ),
+            GUIAxis::Vertical => (
+                [x1 - track_thickness / 2., y1 - track_thickness / 2.],
+                [track_thickness, y0 - y1 + track_thickness],
+            ),
+        };
+        widget_utils::push_rounded_rectangle(
+            vertices,
+            indices,
+            indice_offset,
+            &GUIPosition::from_pixels(left + position[0], top + position[1]),
+            &GUISize::from_pixels(extent[0], extent[1]),
+            GUILength::from_pixels(track_thickness / 2.),
+            color,
+        );
+    };
+    push_segment(0., 1., track_color);
+    if fill.1 > fill.0 {
+        push_segment(fill.0, fill.1, fill_color);
+    }
+
+    for fraction in thumb_fractions {
+        let [x, y] = thumb_center(axis, size, thumb_size, *fraction);
+        widget_utils::push_rounded_rectangle(
+            vertices,
+            indices,
+            indice_offset,
+            &GUIPosition::from_pixels(left + x - thumb_size / 2., top + y - thumb_size / 2.),
+            &GUISize::from_pixels(thumb_size, thumb_size),
+            GUILength::from_pixels(thumb_size / 2.),
+            thumb_color,
+        );
+    }
+}
+
+/// Adds an invisible rectangle over the whole of a slider, so that clicking anywhere
+/// on it moves a thumb there, and returns the polygon that's hit tested.
+pub(super) fn push_hit_area(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    origin: &GUIPosition,
+    size: &GUISize,
+    widget_id: u128,
+) -> Polygon {
+    widget_utils::push_rectangle(
+        vertices,
+        indices,
+        indice_offset,
+        origin,
+        size,
+        &GUIColor {
+            r: 0.,
+            g: 0.,
+            b: 0.,
+            a: 0.,
+        },
+    );
+    Polygon {
+        start_index: indice_offset as usize,
+        end_index: indice_offset as usize + vertices.len(),
+        widget_id,
+        convex: true,
+        rendered: true,
+        clip: None,
+    }
+}
+
+impl Widget for GUISlider {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        if !event.pressed {
+            return false;
+        }
+        match key_target(event.key, self.value, self.min, self.max, self.step) {
+            Some(value) => {
+                self.input_value(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        if event.kind == GUIPointerEventKind::Released {
+            return false;
+        }
+        let fraction = fraction_at(
+            self.axis,
+            &self.size,
+            self.thumb_size.get_length(),
+            &event.position,
+        );
+        self.input_value(self.min + fraction * (self.max - self.min))
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.value_changed)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let polygon = push_hit_area(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            self.id,
+        );
+
+        let (fill_color, thumb_color) = if self.enabled {
+            (&self.fill_color, &self.thumb_color)
+        } else {
+            (&self.disabled_color, &self.disabled_color)
+        };
+        let fraction = fraction_of(self.value, self.min, self.max);
+        push_track_and_thumbs(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            self.axis,
+            &self.size,
+            self.track_thickness.get_length(),
+            self.thumb_size.get_length(),
+            (0., fraction),
+            &[fraction],
+            [&self.track_color, fill_color, thumb_color],
+        );
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUISlider {
+    /// Returns a horizontal slider from 0 to 1 with all of the default values.
+    fn default() -> Self {
+        Self {
+            value: 0.,
+            min: 0.,
+            max: 1.,
+            step: 0.,
+            axis: GUIAxis::Horizontal,
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(24.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            track_thickness: GUILength::from_pixels(4.),
+            thumb_size: GUILength::from_pixels(18.),
+            track_color: GUIColor {
+                r: 0.8,
+                g: 0.8,
+                b: 0.8,
+                a: 1.,
+            },
+            fill_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            thumb_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.,
+            },
+            enabled: true,
+            value_changed: false,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guikeys::GUIModifiers;
+
+    fn pointer(kind: GUIPointerEventKind, x: f64, y: f64) -> GUIPointerEvent {
+        GUIPointerEvent {
+            kind,
+            position: GUIPosition::from_pixels(x, y),
+            modifiers: GUIModifiers::default(),
+        }
+    }
+
+    #[test]
+    fn values_snap_to_steps_within_the_range() {
+        let mut slider = GUISlider {
+            step: 5.,
+            ..GUISlider::new(0., 100.)
+        };
+        assert!(slider.set_value(42.));
+        assert_eq!(slider.value, 40.);
+        assert!(!slider.set_value(41.));
+        slider.set_value(150.);
+        assert_eq!(slider.value, 100.);
+        // Only the gui changing the value runs the change callback.
+        assert!(!slider.take_changed());
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        assert!(slider.take_changed());
+        assert!(!slider.take_changed());
+    }
+
+    #[test]
+    fn dragging_follows_the_pointer() {
+        // The thumb's center travels from 9 to 191.
+        let mut slider = GUISlider::new(0., 10.);
+        assert!(slider.pointer_input(&pointer(GUIPointerEventKind::Pressed, 100., 12.)));
+        assert_eq!(slider.value, 5.);
+        // The pointer is still captured after it's left the slider.
+        assert!(slider.pointer_input(&pointer(GUIPointerEventKind::Moved, 500., 80.)));
+        assert_eq!(slider.value, 10.);
+        assert!(!slider.pointer_input(&pointer(GUIPointerEventKind::Released, 500., 80.)));
+
+        // Vertical sliders go up from the bottom.
+        let mut slider = GUISlider {
+            axis: GUIAxis::Vertical,
+            size: GUISize::from_pixels(24., 200.),
+            ..GUISlider::new(0., 10.)
+        };
+        slider.pointer_input(&pointer(GUIPointerEventKind::Pressed, 12., 191. - 18.2));
+        assert!((slider.value - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keys_move_by_steps() {
+        let mut slider = GUISlider {
+            step: 2.,
+            ..GUISlider::new(0., 100.)
+        };
+        assert!(slider.key_input(&GUIKeyEvent::pressed(GUIKey::Right)));
+        assert!(slider.key_input(&GUIKeyEvent::pressed(GUIKey::Up)));
+        assert_eq!(slider.value, 4.);
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::PageUp));
+        assert_eq!(slider.value, 24.);
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        assert_eq!(slider.value, 100.);
+        slider.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        assert_eq!(slider.value, 0.);
+        assert!(!slider.key_input(&GUIKeyEvent::pressed(GUIKey::Enter)));
+    }
+}
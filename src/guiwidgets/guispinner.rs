@@ -0,0 +1,195 @@
+use std::any::Any;
+use std::f64::consts::{FRAC_PI_2, TAU};
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+use super::guiprogressbar::{animation_phase, FRAME_INTERVAL};
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// A circular arc that spins around while a task of unknown length is running.
+pub struct GUISpinner {
+    /// Whether the arc is spinning. The spinner isn't drawn while it's stopped.
+    pub spinning: bool,
+    /// The size of the spinner. The circle fits within the shorter side.
+    pub size: GUISize,
+    /// The location of the spinner.
+    pub position: GUIPosition,
+    /// How thick the circle is.
+    pub thickness: GUILength,
+    /// How much of the circle the arc covers, from 0 to 1.
+    pub arc_fraction: f64,
+    /// How long the arc takes to go around once.
+    pub period: Duration,
+    /// The color of the rest of the circle.
+    pub track_color: GUIColor,
+    /// The color of the arc.
+    pub arc_color: GUIColor,
+    /// When the arc started to spin. Set by the gui.
+    pub animation_start: Option<Instant>,
+    /// The angle, in radians counter clockwise from pointing right, that
+    /// the arc starts at. Set by the gui.
+    pub start_angle: f64,
+    pub id: u128,
+}
+
+impl GUISpinner {
+    /// Returns a spinning spinner with the default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Widget for GUISpinner {
+    fn animate(&mut self, now: Instant) -> Option<Instant> {
+        if !self.spinning {
+            self.animation_start = None;
+            return None;
+        }
+        let start = *self.animation_start.get_or_insert(now);
+        // Going clockwise, starting from the top.
+        self.start_angle = FRAC_PI_2 - TAU * animation_phase(start, now, self.period);
+        Some(now + FRAME_INTERVAL)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        if self.spinning {
+            let width = self.size.width.get_length();
+            let height = self.size.height.get_length();
+            let center = GUIPosition::from_pixels(
+                self.position.x.get_length() + parent_position.x.get_length() + width / 2.,
+                self.position.y.get_length() + parent_position.y.get_length() + height / 2.,
+            );
+            let outer_radius = width.min(height) / 2.;
+            let inner_radius = (outer_radius - self.thickness.get_length()).max(0.);
+            let sweep = TAU * self.arc_fraction.clamp(0., 1.);
+            // The arc ends where it starts, so the track fills in the rest of the circle.
+            for (start_angle, sweep, color) in [
+                (self.start_angle + sweep, TAU - sweep, &self.track_color),
+                (self.start_angle, sweep, &self.arc_color),
+            ] {
+                if sweep > 0. {
+                    widget_utils::push_arc(
+                        &mut vertices,
+                        &mut indices,
+                        indice_offset,
+                        &center,
+                        GUILength::from_pixels(inner_radius),
+                        GUILength::from_pixels(outer_radius),
+                        start_angle,
+                        sweep,
+                        color,
+                    );
+                }
+            }
+        }
+        // The spinner only shows that something is happening, so clicks go through it.
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUISpinner {
+    /// Returns a spinning spinner with all of the default values.
+    fn default() -> Self {
+        Self {
+            spinning: true,
+            size: GUISize {
+                width: GUILength::from_pixels(32.),
+                height: GUILength::from_pixels(32.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            thickness: GUILength::from_pixels(4.),
+            arc_fraction: 0.25,
+            period: Duration::from_millis(1000),
+            track_color: GUIColor {
+                r: 0.8,
+                g: 0.8,
+                b: 0.8,
+                a: 1.,
+            },
+            arc_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            animation_start: None,
+            start_angle: FRAC_PI_2,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_arc_turns_clockwise_until_stopped() {
+        let mut spinner = GUISpinner::new();
+        let start = Instant::now();
+        assert_eq!(spinner.animate(start), Some(start + FRAME_INTERVAL));
+        assert_eq!(spinner.start_angle, FRAC_PI_2);
+        spinner.animate(start + Duration::from_millis(250));
+        assert!(spinner.start_angle.abs() < 1e-9);
+
+        // The arc's first vertex has turned from the top to the right.
+        let (vertices, _, _) = spinner.get_vertices_and_indices(&GUIPosition::default(), 0);
+        let arc = vertices
+            .iter()
+            .find(|vertex| vertex.color == spinner.arc_color.to_f32_array())
+            .unwrap();
+        assert!((arc.position[0] - 32.).abs() < 1e-4);
+        assert!((arc.position[1] - 16.).abs() < 1e-4);
+
+        spinner.spinning = false;
+        assert_eq!(spinner.animate(start + Duration::from_millis(300)), None);
+        let (vertices, _, _) = spinner.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert!(vertices.is_empty());
+    }
+}
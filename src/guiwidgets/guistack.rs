@@ -0,0 +1,364 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guilayout::{
+    GUIAlignment, GUIAxis, GUIJustify, GUILayoutChild, GUIPadding,
+};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::{Parent, Widget};
+
+/// A container that places its children one after another along an axis,
+/// growing and shrinking them to fit like a css flexbox.
+pub struct GUIStack {
+    /// The direction that children are placed in.
+    pub axis: GUIAxis,
+    /// The space between neighboring children.
+    pub spacing: GUILength,
+    /// The space between the edges of the stack and its children.
+    pub padding: GUIPadding,
+    /// Where children sit across the axis.
+    pub alignment: GUIAlignment,
+    /// How left over space along the axis is distributed.
+    pub justify: GUIJustify,
+    /// The size of the stack.
+    pub size: GUISize,
+    /// The location of the stack.
+    pub position: GUIPosition,
+    pub id: u128,
+}
+
+impl GUIStack {
+    /// Returns a stack with the default values that places children along axis.
+    pub fn new(axis: GUIAxis) -> Self {
+        Self {
+            axis,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the length of size along the stack's axis and across it.
+    fn main_and_cross(&self, size: &GUISize) -> (f64, f64) {
+        match self.axis {
+            GUIAxis::Horizontal => (size.width.get_length(), size.height.get_length()),
+            GUIAxis::Vertical => (size.height.get_length(), size.width.get_length()),
+        }
+    }
+
+    /// Turns lengths along and across the stack's axis back into x and y.
+    fn x_and_y(&self, main: f64, cross: f64) -> (f64, f64) {
+        match self.axis {
+            GUIAxis::Horizontal => (main, cross),
+            GUIAxis::Vertical => (cross, main),
+        }
+    }
+}
+
+impl Widget for GUIStack {
+    fn get_vertices_and_indices(
+        &self,
+        _parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize,
+            widget_id: self.id,
+            ..Default::default()
+        };
+
+        (Vec::new(), Vec::new(), polygon)
+    }
+
+    fn arrange_children(&self, children: &[GUILayoutChild]) -> Option<Vec<(GUIPosition, GUISize)>> {
+        if children.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let (inner_position, inner_size) = self.padding.inset(&self.size);
+        let (main_start, cross_start) =
+            self.main_and_cross(&GUISize::from_lengths(inner_position.x, inner_position.y));
+        let (available_main, available_cross) = self.main_and_cross(&inner_size);
+        let spacing = self.spacing.get_length();
+
+        let bases: Vec<(f64, f64)> = children
+            .iter()
+            .map(|child| self.main_and_cross(&child.preferred_size))
+            .collect();
+        let used: f64 =
+            bases.iter().map(|(main, _)| main).sum::<f64>() + spacing * (children.len() - 1) as f64;
+        let mut free = available_main - used;
+
+        let mut mains: Vec<f64> = bases.iter().map(|(main, _)| *main).collect();
+        let total_grow: f64 = children.iter().map(|child| child.item.grow).sum();
+        let total_shrink: f64 = children
+            .iter()
+            .zip(bases.iter())
+            .map(|(child, (main, _))| child.item.shrink * main)
+            .sum();
+        if free > 0. && total_grow > 0. {
+            for (main, child) in mains.iter_mut().zip(children) {
+                *main += free * child.item.grow / total_grow;
+            }
+            free = 0.;
+        } else if free < 0. && total_shrink > 0. {
+            for (main, child) in mains.iter_mut().zip(children) {
+                *main = (*main + free * child.item.shrink * *main / total_shrink).max(0.);
+            }
+            free = 0.;
+        }
+
+        let count = children.len() as f64;
+        let free = free.max(0.);
+        let (mut offset, gap) = match self.justify {
+            GUIJustify::Start => (0., spacing),
+            GUIJustify::Center => (free / 2., spacing),
+            GUIJustify::End => (free, spacing),
+            GUIJustify::SpaceBetween if children.len() > 1 => (0., spacing + free / (count - 1.)),
+            GUIJustify::SpaceBetween => (0., spacing),
+            GUIJustify::SpaceAround => (free / count / 2., spacing + free / count),
+            GUIJustify::SpaceEvenly => (free / (count + 1.), spacing + free / (count + 1.)),
+        };
+
+        let mut areas = Vec::with_capacity(children.len());
+        for (main, (_, preferred_cross)) in mains.iter().zip(bases.iter()) {
+            let (cross_offset, cross) = match self.alignment {
+                GUIAlignment::Start => (0., *preferred_cross),
+                GUIAlignment::Center => {
+                    ((available_cross - preferred_cross) / 2., *preferred_cross)
+                }
+                GUIAlignment::End => (available_cross - preferred_cross, *preferred_cross),
+                GUIAlignment::Stretch => (0., available_cross),
+            };
+            let (x, y) = self.x_and_y(main_start + offset, cross_start + cross_offset);
+            let (width, height) = self.x_and_y(*main, cross);
+            areas.push((
+                GUIPosition::from_pixels(x, y),
+                GUISize::from_pixels(width, height),
+            ));
+            offset += main + gap;
+        }
+
+        Some(areas)
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Parent for GUIStack {}
+
+impl Default for GUIStack {
+    /// Returns a stack with all of the default values.
+    fn default() -> Self {
+        Self {
+            axis: GUIAxis::Horizontal,
+            spacing: GUILength::from_pixels(0.),
+            padding: GUIPadding::default(),
+            alignment: GUIAlignment::Start,
+            justify: GUIJustify::Start,
+            size: GUISize {
+                width: GUILength::from_pixels(300.),
+                height: GUILength::from_pixels(300.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::guiproperties::guilayout::GUILayoutItem;
+    use crate::guiwidgets::{GUIBase, GUIButton, GUIWindow};
+
+    fn child(width: f64, height: f64, grow: f64, shrink: f64) -> GUILayoutChild {
+        GUILayoutChild {
+            preferred_size: GUISize::from_pixels(width, height),
+            item: GUILayoutItem {
+                grow,
+                shrink,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn make_stack(axis: GUIAxis, width: f64, height: f64) -> GUIStack {
+        GUIStack {
+            axis,
+            size: GUISize::from_pixels(width, height),
+            ..Default::default()
+        }
+    }
+
+    fn xywh(area: &(GUIPosition, GUISize)) -> [f64; 4] {
+        [
+            area.0.x.get_length(),
+            area.0.y.get_length(),
+            area.1.width.get_length(),
+            area.1.height.get_length(),
+        ]
+    }
+
+    #[test]
+    fn places_children_in_a_row_with_spacing_and_padding() {
+        let mut stack = make_stack(GUIAxis::Horizontal, 300., 100.);
+        stack.spacing = GUILength::from_pixels(10.);
+        stack.padding = GUIPadding::from_pixels(5.);
+        let areas = stack
+            .arrange_children(&[child(50., 20., 0., 1.), child(60., 30., 0., 1.)])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [5., 5., 50., 20.]);
+        assert_eq!(xywh(&areas[1]), [65., 5., 60., 30.]);
+    }
+
+    #[test]
+    fn places_children_in_a_column() {
+        let stack = make_stack(GUIAxis::Vertical, 100., 300.);
+        let areas = stack
+            .arrange_children(&[child(50., 20., 0., 1.), child(60., 30., 0., 1.)])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 0., 50., 20.]);
+        assert_eq!(xywh(&areas[1]), [0., 20., 60., 30.]);
+    }
+
+    #[test]
+    fn grows_children_by_factor() {
+        let stack = make_stack(GUIAxis::Horizontal, 400., 100.);
+        let areas = stack
+            .arrange_children(&[
+                child(100., 20., 1., 1.),
+                child(100., 20., 3., 1.),
+                child(100., 20., 0., 1.),
+            ])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 0., 125., 20.]);
+        assert_eq!(xywh(&areas[1]), [125., 0., 175., 20.]);
+        assert_eq!(xywh(&areas[2]), [300., 0., 100., 20.]);
+    }
+
+    #[test]
+    fn shrinks_children_by_factor() {
+        let stack = make_stack(GUIAxis::Horizontal, 150., 100.);
+        let areas = stack
+            .arrange_children(&[child(100., 20., 0., 1.), child(100., 20., 0., 0.)])
+            .unwrap();
+        assert_eq!(xywh(&areas[0]), [0., 0., 50., 20.]);
+        assert_eq!(xywh(&areas[1]), [50., 0., 100., 20.]);
+    }
+
+    #[test]
+    fn justifies_left_over_space() {
+        let children = [child(50., 20., 0., 1.), child(50., 20., 0., 1.)];
+        let mut stack = make_stack(GUIAxis::Horizontal, 200., 100.);
+        let starts = |stack: &GUIStack| -> Vec<f64> {
+            stack
+                .arrange_children(&children)
+                .unwrap()
+                .iter()
+                .map(|area| area.0.x.get_length())
+                .collect()
+        };
+
+        stack.justify = GUIJustify::Center;
+        assert_eq!(starts(&stack), vec![50., 100.]);
+        stack.justify = GUIJustify::End;
+        assert_eq!(starts(&stack), vec![100., 150.]);
+        stack.justify = GUIJustify::SpaceBetween;
+        assert_eq!(starts(&stack), vec![0., 150.]);
+        stack.justify = GUIJustify::SpaceAround;
+        assert_eq!(starts(&stack), vec![25., 125.]);
+        stack.justify = GUIJustify::SpaceEvenly;
+        stack.size = GUISize::from_pixels(250., 100.);
+        assert_eq!(starts(&stack), vec![50., 150.]);
+    }
+
+    #[test]
+    fn aligns_children_across_axis() {
+        let children = [child(50., 20., 0., 1.)];
+        let mut stack = make_stack(GUIAxis::Horizontal, 200., 100.);
+
+        stack.alignment = GUIAlignment::Center;
+        assert_eq!(
+            xywh(&stack.arrange_children(&children).unwrap()[0]),
+            [0., 40., 50., 20.]
+        );
+        stack.alignment = GUIAlignment::End;
+        assert_eq!(
+            xywh(&stack.arrange_children(&children).unwrap()[0]),
+            [0., 80., 50., 20.]
+        );
+        stack.alignment = GUIAlignment::Stretch;
+        assert_eq!(
+            xywh(&stack.arrange_children(&children).unwrap()[0]),
+            [0., 0., 50., 100.]
+        );
+    }
+
+    #[test]
+    fn layout_follows_window_size() {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let stack_id = guibase.add_child_to_parent_with_layout(
+            GUIStack::new(GUIAxis::Horizontal),
+            window_id,
+            GUILayoutItem {
+                fill: true,
+                ..Default::default()
+            },
+        );
+        let button_id = guibase.add_child_to_parent_with_layout(
+            GUIButton::default(),
+            stack_id,
+            GUILayoutItem {
+                grow: 1.,
+                ..Default::default()
+            },
+        );
+
+        guibase.get_base_window_mut().size = GUISize::from_pixels(800., 600.);
+        guibase.layout();
+        assert_eq!(
+            guibase.get_widget(stack_id).get_size().width.get_length(),
+            800.
+        );
+        assert_eq!(
+            guibase.get_widget(button_id).get_size().width.get_length(),
+            800.
+        );
+
+        guibase.get_base_window_mut().size = GUISize::from_pixels(400., 600.);
+        guibase.layout();
+        assert_eq!(
+            guibase.get_widget(button_id).get_size().width.get_length(),
+            400.
+        );
+    }
+}
@@ -0,0 +1,980 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+use uuid::Uuid;
+
+use super::guilistview::GUIListView;
+use super::guiscrollview::{thumb_offset, thumb_span};
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::GUIKeyEvent;
+use crate::guiproperties::guilayout::GUICell;
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitraits::{Parent, Widget};
+use crate::guiproperties::GUIColor;
+
+/// How close to the edge between two column headers the pointer has to be pressed
+/// to resize the column on the left, and how far a header has to be dragged to move it.
+const DRAG_DISTANCE: f64 = 4.;
+
+/// A column of a table.
+pub struct GUITableColumn {
+    /// The text in the column's header.
+    pub title: String,
+    /// How wide the column is.
+    pub width: GUILength,
+    /// The narrowest that dragging the edge of the column's header makes the column.
+    pub min_width: GUILength,
+    /// Whether the column can be resized by dragging the right edge of its header.
+    pub resizable: bool,
+    /// Orders two rows of data by the column, for sorting the table by the column when its
+    /// header is clicked, or None if the table can't be sorted by the column.
+    pub compare: Option<Box<dyn Fn(usize, usize) -> Ordering>>,
+}
+
+impl GUITableColumn {
+    /// Returns a column with the default values that's headed by title.
+    pub fn new(title: &str, width: GUILength) -> Self {
+        Self {
+            title: String::from(title),
+            width,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the column, which the table can be sorted by with compare,
+    /// which orders two rows of data.
+    pub fn sorted_by(self, compare: impl Fn(usize, usize) -> Ordering + 'static) -> Self {
+        Self {
+            compare: Some(Box::new(compare)),
+            ..self
+        }
+    }
+}
+
+impl Default for GUITableColumn {
+    /// Returns a resizable column that can't be sorted by, with all of the default values.
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            width: GUILength::from_pixels(100.),
+            min_width: GUILength::from_pixels(24.),
+            resizable: true,
+            compare: None,
+        }
+    }
+}
+
+/// What the pointer is doing with a table's header while the mouse button is down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GUIColumnDrag {
+    /// A header was pressed, and is clicked if it's released without being dragged.
+    Pressed { column: usize, start_x: f64 },
+    /// A header is being dragged to another place among the headers.
+    Moving { column: usize },
+    /// The right edge of a column's header is being dragged.
+    Resizing {
+        column: usize,
+        start_x: f64,
+        start_width: f64,
+    },
+}
+
+/// A grid of rows of data below a header of columns. Clicking a column's header sorts the
+/// rows by it, dragging the right edge of a header resizes its column and dragging a header
+/// moves its column. Like a list view, the table only has widgets for the cells that can be
+/// seen, which are made and filled in by the callbacks given to GUIBase::set_cell_builder,
+/// one for each column. It's scrolled up and down, and left and right when the columns
+/// are wider than it is, with the scrollbar along its bottom edge.
+pub struct GUITable {
+    /// The columns, in the order that they were given in. Cells are built by these indices.
+    pub columns: Vec<GUITableColumn>,
+    /// The order that the columns are shown in, as indices into columns.
+    pub column_order: Vec<usize>,
+    /// The column that the rows are sorted by, as an index into columns,
+    /// and whether they're sorted in ascending order.
+    pub sort: Option<(usize, bool)>,
+    /// The rows of data in the order that they're shown, after sorting. Set by the gui.
+    pub row_order: Vec<usize>,
+    /// The rows below the header, which handle scrolling up and down, selection and
+    /// the keyboard. Its rows are the rows as they're shown, rather than the rows of data.
+    pub rows: GUIListView,
+    /// How far the header and the cells are scrolled right.
+    pub horizontal_offset: GUILength,
+    /// How tall the header is.
+    pub header_height: GUILength,
+    /// The height of the font in the header.
+    pub font_size: GUILength,
+    /// The color behind the header.
+    pub header_color: GUIColor,
+    /// The color of the text in the header.
+    pub header_text_color: GUIColor,
+    /// The color of the lines between the headers, and of the arrow showing the sort.
+    pub divider_color: GUIColor,
+    /// The size of the table, including its header.
+    pub size: GUISize,
+    /// The location of the table.
+    pub position: GUIPosition,
+    /// What the pointer is doing with the header. Set by the gui.
+    pub column_drag: Option<GUIColumnDrag>,
+    /// How far along the horizontal scrollbar's thumb it was grabbed,
+    /// while it's being dragged. Set by the gui.
+    pub scrollbar_drag: Option<f64>,
+    pub id: u128,
+}
+
+impl GUITable {
+    /// Returns a table with the default values that has columns and row_count rows.
+    pub fn new(columns: Vec<GUITableColumn>, row_count: usize) -> Self {
+        let mut table = Self {
+            column_order: (0..columns.len()).collect(),
+            columns,
+            ..Default::default()
+        };
+        table.set_size(table.size);
+        table.set_row_count(row_count);
+        table
+    }
+
+    /// Changes how many rows of data there are, sorting them again.
+    /// The same rows of data stay selected, apart from ones that are gone.
+    pub fn set_row_count(&mut self, row_count: usize) {
+        self.selection_to_data_rows();
+        self.rows.set_row_count(row_count);
+        self.row_order = (0..row_count).collect();
+        self.sort_rows();
+        self.selection_to_shown_rows();
+        // The rows' scrollbar may have come or gone, which changes how wide they are.
+        self.fit_rows();
+    }
+
+    /// Sorts the rows by column, in ascending order or not, keeping the same rows of data
+    /// selected. Does nothing if the table can't be sorted by the column.
+    pub fn sort_by(&mut self, column: usize, ascending: bool) {
+        if !matches!(self.columns.get(column), Some(column) if column.compare.is_some()) {
+            return;
+        }
+        self.sort = Some((column, ascending));
+        self.selection_to_data_rows();
+        self.sort_rows();
+        self.selection_to_shown_rows();
+    }
+
+    /// Puts row_order in the order that sort asks for.
+    fn sort_rows(&mut self) {
+        let (compare, ascending) = match self.sort {
+            Some((column, ascending)) => match &self.columns[column].compare {
+                Some(compare) => (compare, ascending),
+                None => return,
+            },
+            None => return,
+        };
+        // The sort is stable, so rows that are the same keep their order.
+        if ascending {
+            self.row_order.sort_by(|a, b| compare(*a, *b));
+        } else {
+            self.row_order.sort_by(|a, b| compare(*b, *a));
+        }
+    }
+
+    /// Turns the selected rows, the cursor and the anchor from the rows that are shown
+    /// into the rows of data that they show, so that they can follow the data as it moves.
+    fn selection_to_data_rows(&mut self) {
+        let row_order = &self.row_order;
+        let rows = &mut self.rows;
+        rows.selected_rows = rows
+            .selected_rows
+            .iter()
+            .map(|row| row_order[*row])
+            .collect();
+        rows.cursor_row = rows.cursor_row.map(|row| row_order[row]);
+        rows.anchor_row = rows.anchor_row.map(|row| row_order[row]);
+    }
+
+    /// Turns the selected rows, the cursor and the anchor back from rows of data
+    /// into the rows that now show them.
+    fn selection_to_shown_rows(&mut self) {
+        let mut shown_rows = vec![0; self.row_order.len()];
+        for (shown_row, row) in self.row_order.iter().enumerate() {
+            shown_rows[*row] = shown_row;
+        }
+        let rows = &mut self.rows;
+        rows.selected_rows = rows
+            .selected_rows
+            .iter()
+            .map(|row| shown_rows[*row])
+            .collect();
+        rows.cursor_row = rows.cursor_row.map(|row| shown_rows[row]);
+        rows.anchor_row = rows.anchor_row.map(|row| shown_rows[row]);
+    }
+
+    /// Returns the rows of data that are selected.
+    pub fn selected_data_rows(&self) -> BTreeSet<usize> {
+        self.rows
+            .selected_rows
+            .iter()
+            .map(|row| self.row_order[*row])
+            .collect()
+    }
+
+    /// Returns the left of each column, in the order that they're shown, along with its index.
+    fn column_lefts(&self) -> Vec<(usize, f64)> {
+        let mut left = 0.;
+        self.column_order
+            .iter()
+            .map(|column| {
+                let column_left = (*column, left);
+                left += self.columns[*column].width.get_length();
+                column_left
+            })
+            .collect()
+    }
+
+    /// Returns how wide all of the columns are together.
+    fn columns_width(&self) -> f64 {
+        self.columns
+            .iter()
+            .map(|column| column.width.get_length())
+            .sum()
+    }
+
+    /// Returns how wide the area that the cells are shown in is.
+    fn view_width(&self) -> f64 {
+        self.rows
+            .get_viewport()
+            .map_or(0., |(_, size)| size.width.get_length())
+    }
+
+    /// Returns whether the horizontal scrollbar is shown,
+    /// which it is when the columns are wider than the rows.
+    fn shows_horizontal_scrollbar(&self) -> bool {
+        self.columns_width() > self.view_width()
+    }
+
+    /// Returns how far the columns can be scrolled right.
+    fn max_horizontal_offset(&self) -> f64 {
+        (self.columns_width() - self.view_width()).max(0.)
+    }
+
+    /// Sizes the rows to fit below the header, leaving room for the horizontal scrollbar
+    /// if the columns don't fit, and keeps the scrolling within the columns.
+    fn fit_rows(&mut self) {
+        let width = self.size.width.get_length();
+        let height = (self.size.height.get_length() - self.header_height.get_length()).max(0.);
+        self.rows.set_size(GUISize::from_pixels(width, height));
+        if self.shows_horizontal_scrollbar() {
+            let thickness = self.rows.scrollbar_thickness.get_length();
+            self.rows
+                .set_size(GUISize::from_pixels(width, (height - thickness).max(0.)));
+        }
+        self.set_scroll_offset(self.get_scroll_offset());
+    }
+
+    /// Returns where the horizontal scrollbar's thumb starts and how long it is.
+    fn horizontal_thumb(&self) -> (f64, f64) {
+        thumb_span(
+            self.view_width(),
+            self.columns_width(),
+            self.horizontal_offset.get_length(),
+            self.max_horizontal_offset(),
+            self.rows.min_thumb_length.get_length(),
+        )
+    }
+
+    /// Scrolls so that the horizontal scrollbar's thumb starts at thumb_start.
+    /// Returns true if the columns moved.
+    fn drag_horizontal_thumb_to(&mut self, thumb_start: f64) -> bool {
+        let (_, length) = self.horizontal_thumb();
+        let offset = thumb_offset(
+            self.view_width(),
+            length,
+            thumb_start,
+            self.max_horizontal_offset(),
+        );
+        self.set_scroll_offset(GUIPosition::from_lengths(
+            GUILength::from_pixels(offset),
+            self.rows.scroll_offset.y,
+        ))
+    }
+
+    /// Handles the pointer being pressed on, dragged from, or released after being
+    /// pressed on the horizontal scrollbar. Returns true if the columns moved.
+    fn scrollbar_pointer_input(&mut self, kind: GUIPointerEventKind, x: f64) -> bool {
+        match (kind, self.scrollbar_drag) {
+            (GUIPointerEventKind::Pressed, _) => {
+                let (start, length) = self.horizontal_thumb();
+                if (start..start + length).contains(&x) {
+                    self.scrollbar_drag = Some(x - start);
+                    false
+                } else {
+                    // Clicking the track jumps the thumb to the pointer, ready to be dragged.
+                    self.scrollbar_drag = Some(length / 2.);
+                    self.drag_horizontal_thumb_to(x - length / 2.)
+                }
+            }
+            (GUIPointerEventKind::Moved, Some(grab)) => self.drag_horizontal_thumb_to(x - grab),
+            (GUIPointerEventKind::Moved, None) => false,
+            (GUIPointerEventKind::Released, _) => {
+                self.scrollbar_drag = None;
+                false
+            }
+        }
+    }
+
+    /// Returns the column that's under x, along with its left.
+    fn column_at(&self, x: f64) -> Option<(usize, f64)> {
+        self.column_lefts().into_iter().find(|(column, left)| {
+            (*left..left + self.columns[*column].width.get_length()).contains(&x)
+        })
+    }
+
+    /// Returns the resizable column whose right edge is near x.
+    fn edge_at(&self, x: f64) -> Option<usize> {
+        self.column_lefts()
+            .into_iter()
+            .find(|(column, left)| {
+                let right = left + self.columns[*column].width.get_length();
+                self.columns[*column].resizable && (x - right).abs() <= DRAG_DISTANCE
+            })
+            .map(|(column, _)| column)
+    }
+
+    /// Handles the pointer being pressed on, dragged from, or released after being
+    /// pressed on the header. Returns true if the table changed.
+    fn header_pointer_input(&mut self, kind: GUIPointerEventKind, x: f64) -> bool {
+        match (kind, self.column_drag) {
+            (GUIPointerEventKind::Pressed, _) => {
+                self.column_drag = if let Some(column) = self.edge_at(x) {
+                    Some(GUIColumnDrag::Resizing {
+                        column,
+                        start_x: x,
+                        start_width: self.columns[column].width.get_length(),
+                    })
+                } else {
+                    self.column_at(x)
+                        .map(|(column, _)| GUIColumnDrag::Pressed { column, start_x: x })
+                };
+                false
+            }
+            (
+                GUIPointerEventKind::Moved,
+                Some(GUIColumnDrag::Resizing {
+                    column,
+                    start_x,
+                    start_width,
+                }),
+            ) => {
+                let column = &mut self.columns[column];
+                let width = (start_width + x - start_x).max(column.min_width.get_length());
+                let changed = width != column.width.get_length();
+                column.width = GUILength::from_pixels(width);
+                self.fit_rows();
+                changed
+            }
+            (GUIPointerEventKind::Moved, Some(GUIColumnDrag::Pressed { column, start_x })) => {
+                if (x - start_x).abs() < DRAG_DISTANCE {
+                    return false;
+                }
+                self.column_drag = Some(GUIColumnDrag::Moving { column });
+                self.header_pointer_input(kind, x)
+            }
+            (GUIPointerEventKind::Moved, Some(GUIColumnDrag::Moving { column })) => {
+                // The column takes the place of whichever column the pointer is over.
+                let target = match self.column_at(x) {
+                    Some((target, _)) if target != column => target,
+                    _ => return false,
+                };
+                let from = self.column_order.iter().position(|c| *c == column).unwrap();
+                let to = self.column_order.iter().position(|c| *c == target).unwrap();
+                self.column_order.remove(from);
+                self.column_order.insert(to, column);
+                true
+            }
+            (GUIPointerEventKind::Released, Some(GUIColumnDrag::Pressed { column, .. })) => {
+                self.column_drag = None;
+                // Clicking the column that the rows are sorted by reverses them.
+                let ascending = self.sort != Some((column, true));
+                let sorted = self.sort;
+                self.sort_by(column, ascending);
+                self.sort != sorted
+            }
+            (GUIPointerEventKind::Released, _) => {
+                self.column_drag = None;
+                false
+            }
+            (GUIPointerEventKind::Moved, None) => false,
+        }
+    }
+
+    /// Returns the columns that can be seen in the header, in the order that they're shown,
+    /// along with their left and right edges after they've been scrolled.
+    fn shown_columns(&self) -> Vec<(usize, f64, f64)> {
+        let offset = self.horizontal_offset.get_length();
+        let table_width = self.size.width.get_length();
+        self.column_lefts()
+            .into_iter()
+            .map(|(column, left)| {
+                let left = left - offset;
+                (column, left, left + self.columns[column].width.get_length())
+            })
+            .filter(|(_, left, right)| *right > 0. && *left < table_width)
+            .collect()
+    }
+
+    /// Returns the event moved from the table's coordinates into the rows'.
+    fn rows_event(&self, event: &GUIPointerEvent) -> GUIPointerEvent {
+        GUIPointerEvent {
+            position: GUIPosition::from_lengths(
+                event.position.x,
+                event.position.y.subtract(&self.header_height),
+            ),
+            ..*event
+        }
+    }
+}
+
+impl Widget for GUITable {
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.rows.set_focused(focused);
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        self.rows.key_input(event)
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        let (x, y) = (event.position.x.get_length(), event.position.y.get_length());
+        let on_header = y < self.header_height.get_length();
+        let on_scrollbar = self.shows_horizontal_scrollbar()
+            && y >= self.header_height.add(&self.rows.size.height).get_length()
+            && x < self.view_width();
+        // The header is dragged along the columns, which are scrolled.
+        let header_x = x + self.horizontal_offset.get_length();
+        match event.kind {
+            GUIPointerEventKind::Pressed if on_header => {
+                self.header_pointer_input(event.kind, header_x)
+            }
+            GUIPointerEventKind::Pressed if on_scrollbar => {
+                self.scrollbar_pointer_input(event.kind, x)
+            }
+            GUIPointerEventKind::Pressed => self.rows.pointer_input(&self.rows_event(event)),
+            _ if self.column_drag.is_some() => self.header_pointer_input(event.kind, header_x),
+            _ if self.scrollbar_drag.is_some() => self.scrollbar_pointer_input(event.kind, x),
+            _ => self.rows.pointer_input(&self.rows_event(event)),
+        }
+    }
+
+    fn take_changed(&mut self) -> bool {
+        self.rows.take_changed()
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        let left = self.position.x.get_length() + parent_position.x.get_length();
+        let top = self.position.y.get_length() + parent_position.y.get_length();
+        let table_width = self.size.width.get_length();
+        let padding = self.font_size.get_length() / 2.;
+        self.shown_columns()
+            .into_iter()
+            .map(|(column, column_left, right)| {
+                // A header that's partly scrolled out on the left keeps its title in view,
+                // and room is left on the right for the arrow that shows the sort.
+                let column_left = column_left.max(0.);
+                let right = right.min(table_width);
+                GUIText {
+                    text: self.columns[column].title.clone(),
+                    position: GUIPosition::from_pixels(left + column_left + padding, top),
+                    size: GUISize::from_lengths(
+                        GUILength::from_pixels((right - column_left - 3. * padding).max(0.)),
+                        self.header_height,
+                    ),
+                    font_size: self.font_size,
+                    color: self.header_text_color,
+                    horizontal_alignment: GUIHorizontalAlignment::Left,
+                    vertical_alignment: GUIVerticalAlignment::Center,
+                    wrap: false,
+                    overflow: GUITextOverflow::Ellipsis,
+                }
+            })
+            .collect()
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+        self.fit_rows();
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_viewport(&self) -> Option<(GUIPosition, GUISize)> {
+        let (_, size) = self.rows.get_viewport()?;
+        Some((
+            GUIPosition::from_lengths(GUILength::from_pixels(0.), self.header_height),
+            size,
+        ))
+    }
+
+    fn get_scroll_offset(&self) -> GUIPosition {
+        GUIPosition::from_lengths(self.horizontal_offset, self.rows.scroll_offset.y)
+    }
+
+    fn set_scroll_offset(&mut self, offset: GUIPosition) -> bool {
+        let horizontal_offset = GUILength::from_pixels(
+            offset
+                .x
+                .get_length()
+                .clamp(0., self.max_horizontal_offset()),
+        );
+        let scrolled = horizontal_offset != self.horizontal_offset;
+        self.horizontal_offset = horizontal_offset;
+        self.rows.set_scroll_offset(offset) || scrolled
+    }
+
+    fn get_visible_cells(&self) -> Vec<GUICell> {
+        let offset = self.horizontal_offset.get_length();
+        let view_width = self.view_width();
+        // The cells are placed along the columns, and the gui moves them back
+        // by the scroll offset.
+        let columns: Vec<(usize, f64)> = self
+            .column_lefts()
+            .into_iter()
+            .filter(|(column, left)| {
+                *left < offset + view_width
+                    && left + self.columns[*column].width.get_length() > offset
+            })
+            .collect();
+        let header_height = self.header_height.get_length();
+        let row_height = self.rows.row_height.get_length();
+        self.rows
+            .visible_rows()
+            .flat_map(|shown_row| {
+                columns.iter().map(move |(column, left)| GUICell {
+                    row: self.row_order[shown_row],
+                    column: *column,
+                    position: GUIPosition::from_pixels(
+                        *left,
+                        header_height + shown_row as f64 * row_height,
+                    ),
+                    size: GUISize::from_lengths(self.columns[*column].width, self.rows.row_height),
+                })
+            })
+            .collect()
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            &self.rows.background_color,
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        // The rows draw the selection and their scrollbar below the header,
+        // over the table's background.
+        self.rows.push_selection_and_scrollbar(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &GUIPosition::from_lengths(origin.x, origin.y.add(&self.header_height)),
+        );
+        if self.shows_horizontal_scrollbar() {
+            let thickness = self.rows.scrollbar_thickness;
+            let bottom = origin
+                .y
+                .add(&self.header_height)
+                .add(&self.rows.size.height);
+            let (start, length) = self.horizontal_thumb();
+            widget_utils::push_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_lengths(origin.x, bottom),
+                &GUISize::from_lengths(GUILength::from_pixels(self.view_width()), thickness),
+                &self.rows.track_color,
+            );
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_lengths(origin.x.add(&GUILength::from_pixels(start)), bottom),
+                &GUISize::from_lengths(GUILength::from_pixels(length), thickness),
+                GUILength::from_pixels(thickness.get_length() / 2.),
+                &self.rows.thumb_color,
+            );
+        }
+
+        let (left, top) = (origin.x.get_length() as f32, origin.y.get_length() as f32);
+        let table_width = self.size.width.get_length();
+        let header_height = self.header_height.get_length() as f32;
+        let padding = self.font_size.get_length() / 2.;
+        widget_utils::push_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &GUISize::from_lengths(self.size.width, self.header_height),
+            &self.header_color,
+        );
+        for (column, _, right) in self.shown_columns() {
+            if right <= table_width {
+                let x = left + right as f32;
+                widget_utils::push_line(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    [x - 0.5, top],
+                    [x - 0.5, top + header_height],
+                    1.,
+                    &self.divider_color,
+                );
+            }
+            let sorted = self.sort.filter(|(sorted, _)| *sorted == column);
+            // The arrow is left out once there isn't room for it in what can be seen.
+            if let Some((_, ascending)) = sorted.filter(|_| right > 2. * padding) {
+                // An arrow pointing up for ascending and down for descending.
+                let padding = padding as f32;
+                let x = left + right.min(table_width) as f32 - padding * 1.5;
+                let y = top + header_height / 2.;
+                let (tip, tail) = if ascending {
+                    (y - padding / 2., y + padding / 2.)
+                } else {
+                    (y + padding / 2., y - padding / 2.)
+                };
+                for side in [-padding / 2., padding / 2.] {
+                    widget_utils::push_line(
+                        &mut vertices,
+                        &mut indices,
+                        indice_offset,
+                        [x + side, tail],
+                        [x, tip],
+                        1.5,
+                        &self.divider_color,
+                    );
+                }
+            }
+        }
+        widget_utils::push_line(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            [left, top + header_height - 0.5],
+            [left + table_width as f32, top + header_height - 0.5],
+            1.,
+            &self.divider_color,
+        );
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Parent for GUITable {}
+
+impl Default for GUITable {
+    /// Returns an empty table without any columns and with all of the default values.
+    fn default() -> Self {
+        let header_height = GUILength::from_pixels(28.);
+        let size = GUISize {
+            width: GUILength::from_pixels(400.),
+            height: GUILength::from_pixels(300.),
+        };
+        Self {
+            columns: Vec::new(),
+            column_order: Vec::new(),
+            sort: None,
+            row_order: Vec::new(),
+            rows: GUIListView {
+                size: GUISize::from_lengths(size.width, size.height.subtract(&header_height)),
+                ..Default::default()
+            },
+            horizontal_offset: GUILength::from_pixels(0.),
+            header_height,
+            font_size: GUILength::from_pixels(14.),
+            header_color: GUIColor {
+                r: 0.93,
+                g: 0.93,
+                b: 0.93,
+                a: 1.,
+            },
+            header_text_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            divider_color: GUIColor {
+                r: 0.6,
+                g: 0.6,
+                b: 0.6,
+                a: 1.,
+            },
+            size,
+            position: GUIPosition::from_pixels(0., 0.),
+            column_drag: None,
+            scrollbar_drag: None,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiproperties::guikeys::GUIModifiers;
+    use crate::guiwidgets::{GUIBase, GUICheckState, GUICheckbox, GUILabel, GUIWindow};
+
+    /// Returns a 300 by 100 table with a 20 high header and 20 high rows,
+    /// showing a thousand rows of names, ages and whether they're subscribed.
+    fn make_table() -> (GUIBase, u128, Rc<RefCell<Vec<bool>>>) {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let ages = |row: usize| (row * 37) % 100;
+        let columns = vec![
+            GUITableColumn::new("Name", GUILength::from_pixels(100.)),
+            GUITableColumn::new("Age", GUILength::from_pixels(60.))
+                .sorted_by(move |a, b| ages(a).cmp(&ages(b))),
+            GUITableColumn::new("Subscribed", GUILength::from_pixels(100.)),
+        ];
+        let mut table = GUITable::new(columns, 1000);
+        table.header_height = GUILength::from_pixels(20.);
+        table.rows.row_height = GUILength::from_pixels(20.);
+        table.set_size(GUISize::from_pixels(300., 100.));
+        let table_id = guibase.add_child_to_parent(table, window_id);
+
+        let subscribed = Rc::new(RefCell::new(vec![false; 1000]));
+        guibase.set_cell_builder(table_id, 0, |label: &mut GUILabel, row| {
+            label.set_text(&format!("Person {}", row))
+        });
+        guibase.set_cell_builder(table_id, 1, move |label: &mut GUILabel, row| {
+            label.set_text(&ages(row).to_string())
+        });
+        let data = subscribed.clone();
+        guibase.set_cell_builder(table_id, 2, move |checkbox: &mut GUICheckbox, row| {
+            checkbox.state = match data.borrow()[row] {
+                true => GUICheckState::Checked,
+                false => GUICheckState::Unchecked,
+            };
+        });
+        let data = subscribed.clone();
+        guibase.on_cell_change(table_id, 2, move |guibase, row| {
+            let checked = data.borrow()[row];
+            data.borrow_mut()[row] = !checked;
+            guibase.rebuild_rows(table_id);
+        });
+        guibase.layout();
+        (guibase, table_id, subscribed)
+    }
+
+    fn drag(guibase: &mut GUIBase, table_id: u128, from: (f64, f64), to: (f64, f64)) {
+        let modifiers = GUIModifiers::default();
+        let from = GUIPosition::from_pixels(from.0, from.1);
+        let to = GUIPosition::from_pixels(to.0, to.1);
+        guibase.pointer_input(table_id, GUIPointerEventKind::Pressed, &from, modifiers);
+        guibase.pointer_input(table_id, GUIPointerEventKind::Moved, &to, modifiers);
+        guibase.pointer_input(table_id, GUIPointerEventKind::Released, &to, modifiers);
+        guibase.take_dirty_widgets();
+    }
+
+    fn cell_text(guibase: &GUIBase, table_id: u128, row: usize, column: usize) -> String {
+        let cell_id = guibase.get_cell_widget(table_id, row, column).unwrap();
+        guibase
+            .get_widget_as::<GUILabel>(cell_id)
+            .unwrap()
+            .text
+            .clone()
+    }
+
+    #[test]
+    fn clicking_a_header_sorts_the_rows() {
+        let (mut guibase, table_id, _) = make_table();
+        // Four rows of three cells can be seen below the header.
+        assert_eq!(guibase.widgets.len(), 13);
+        guibase.set_focus(Some(table_id));
+        drag(&mut guibase, table_id, (150., 40.), (150., 40.));
+        assert_eq!(cell_text(&guibase, table_id, 1, 0), "Person 1");
+
+        drag(&mut guibase, table_id, (130., 10.), (130., 10.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(table.sort, Some((1, true)));
+        assert_eq!(&table.row_order[..3], &[0, 100, 200]);
+        assert_eq!(cell_text(&guibase, table_id, 100, 0), "Person 100");
+        assert_eq!(guibase.get_cell_widget(table_id, 1, 0), None);
+        // The same row of data stays selected.
+        assert_eq!(table.selected_data_rows(), BTreeSet::from([1]));
+
+        drag(&mut guibase, table_id, (130., 10.), (130., 10.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(table.sort, Some((1, false)));
+        assert_eq!(cell_text(&guibase, table_id, table.row_order[0], 1), "99");
+    }
+
+    #[test]
+    fn adding_a_row_keeps_the_selection_on_its_data() {
+        let (mut guibase, table_id, _) = make_table();
+        let table = guibase.get_widget_as_mut::<GUITable>(table_id).unwrap();
+        table.sort_by(1, true);
+        table.rows.selected_rows = BTreeSet::from([1, 2]);
+        table.rows.cursor_row = Some(2);
+        table.rows.anchor_row = Some(1);
+        let selected = table.selected_data_rows();
+        let cursor_row = table.row_order[2];
+        let anchor_row = table.row_order[1];
+
+        table.set_row_count(1001);
+        assert_eq!(table.selected_data_rows(), selected);
+        assert_eq!(
+            table.rows.cursor_row.map(|row| table.row_order[row]),
+            Some(cursor_row)
+        );
+        assert_eq!(
+            table.rows.anchor_row.map(|row| table.row_order[row]),
+            Some(anchor_row)
+        );
+    }
+
+    #[test]
+    fn dragging_headers_resizes_and_moves_columns() {
+        let (mut guibase, table_id, _) = make_table();
+        drag(&mut guibase, table_id, (100., 10.), (150., 10.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(table.columns[0].width, GUILength::from_pixels(150.));
+        let age_id = guibase.get_cell_widget(table_id, 0, 1).unwrap();
+        assert_eq!(
+            guibase.get_absolute_position(age_id),
+            GUIPosition::from_pixels(150., 20.)
+        );
+        // Resizing the column doesn't sort by it.
+        assert_eq!(table.sort, None);
+
+        // The subscribed column is dragged onto the name column.
+        drag(&mut guibase, table_id, (250., 10.), (50., 10.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(table.column_order, vec![2, 0, 1]);
+        let name_id = guibase.get_cell_widget(table_id, 0, 0).unwrap();
+        assert_eq!(
+            guibase.get_absolute_position(name_id),
+            GUIPosition::from_pixels(100., 20.)
+        );
+        let age_id = guibase.get_cell_widget(table_id, 0, 1).unwrap();
+        assert_eq!(
+            guibase.get_absolute_position(age_id),
+            GUIPosition::from_pixels(250., 20.)
+        );
+    }
+
+    #[test]
+    fn columns_wider_than_the_table_scroll_sideways() {
+        let (mut guibase, table_id, _) = make_table();
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert!(!table.shows_horizontal_scrollbar());
+        let (_, background_only, _) = table.get_vertices_and_indices(&GUIPosition::default(), 0);
+
+        // Widening the name column to 400 makes the columns 560 wide,
+        // and the scrollbar along the bottom takes 10 from the rows.
+        drag(&mut guibase, table_id, (100., 10.), (400., 10.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert!(table.shows_horizontal_scrollbar());
+        assert_eq!(table.rows.size, GUISize::from_pixels(300., 70.));
+        assert_eq!(guibase.get_cell_widget(table_id, 0, 2), None);
+        let (_, with_scrollbar, _) = table.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert!(with_scrollbar.len() > background_only.len());
+
+        assert!(guibase.scroll_input(table_id, &GUIPosition::from_pixels(1e9, 0.)));
+        guibase.take_dirty_widgets();
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(
+            table.get_scroll_offset(),
+            GUIPosition::from_pixels(270., 0.)
+        );
+        let subscribed_id = guibase.get_cell_widget(table_id, 0, 2).unwrap();
+        assert_eq!(
+            guibase.get_absolute_position(subscribed_id),
+            GUIPosition::from_pixels(190., 20.)
+        );
+        // The headers move with the cells.
+        let titles: Vec<(String, f64)> = table
+            .get_text(&GUIPosition::default())
+            .into_iter()
+            .map(|text| (text.text, text.position.x.get_length()))
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                (String::from("Name"), 7.),
+                (String::from("Age"), 137.),
+                (String::from("Subscribed"), 197.)
+            ]
+        );
+
+        // Pressing the left end of the scrollbar's track jumps back to the start.
+        drag(&mut guibase, table_id, (1., 95.), (1., 95.));
+        let table = guibase.get_widget_as::<GUITable>(table_id).unwrap();
+        assert_eq!(table.horizontal_offset, GUILength::from_pixels(0.));
+        assert_eq!(guibase.get_cell_widget(table_id, 0, 2), None);
+    }
+
+    #[test]
+    fn changing_a_checkbox_cell_reports_its_row() {
+        let (mut guibase, table_id, subscribed) = make_table();
+        guibase.scroll_input(table_id, &GUIPosition::from_pixels(0., 40.));
+        guibase.take_dirty_widgets();
+        let checkbox_id = guibase.get_cell_widget(table_id, 3, 2).unwrap();
+        assert!(guibase.click(checkbox_id));
+        assert!(subscribed.borrow()[3]);
+        let checkbox = guibase.get_widget_as::<GUICheckbox>(checkbox_id).unwrap();
+        assert!(checkbox.is_checked());
+        assert_eq!(subscribed.borrow().iter().filter(|s| **s).count(), 1);
+    }
+}
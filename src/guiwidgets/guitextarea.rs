@@ -0,0 +1,1241 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::guiscrollview::thumb_span;
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent, GUIModifiers};
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitextbuffer::GUITextBuffer;
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::{GUIColor, GUIFont};
+
+/// A change to the text of a GUITextArea, which can be undone and redone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GUITextEdit {
+    /// Where the change was made, in characters.
+    pub position: usize,
+    /// The text that was removed from position.
+    pub removed: String,
+    /// The text that was put in its place.
+    pub inserted: String,
+    /// The caret and selection anchor from before the change.
+    pub selection_before: (usize, usize),
+    /// Whether the change was typed, in which case the characters typed
+    /// after it are added to it so that they're undone together.
+    pub typed: bool,
+}
+
+/// A line of a GUITextArea as it's drawn, after the text has been wrapped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GUITextLine {
+    /// The position of the line's first character in the text.
+    pub start: usize,
+    /// How far from the start of the line each position that the caret can be at is,
+    /// so there's one more than there are characters in the line.
+    pub caret_offsets: Vec<f32>,
+}
+
+impl GUITextLine {
+    /// Returns the position after the line's last character.
+    pub fn end(&self) -> usize {
+        self.start + self.caret_offsets.len() - 1
+    }
+}
+
+/// Several lines of editable text that wrap to the width of the text area
+/// and scroll to keep the caret in view. They're also scrolled with the mouse wheel,
+/// and a thumb along the right edge shows how far.
+/// Tab types a tab, so the focus is moved out with Ctrl+Tab or Shift+Tab.
+pub struct GUITextArea {
+    /// The text that's been entered. Lines are separated by '\n'.
+    pub text: GUITextBuffer,
+    /// The size of the text area.
+    pub size: GUISize,
+    /// The location of the text area.
+    pub position: GUIPosition,
+    /// Radius of the text area corners.
+    pub radius: GUILength,
+    /// The space between the edges of the text area and its text.
+    pub padding: GUILength,
+    /// The height of the font.
+    pub font_size: GUILength,
+    /// How many spaces wide a tab is.
+    pub tab_width: usize,
+    /// The background color for the text area.
+    pub background_color: GUIColor,
+    /// The background color while the text area is disabled.
+    pub disabled_color: GUIColor,
+    /// The color of the text.
+    pub text_color: GUIColor,
+    /// The color drawn behind selected text.
+    pub selection_color: GUIColor,
+    /// The color of the caret.
+    pub caret_color: GUIColor,
+    /// The width of the caret.
+    pub caret_width: GUILength,
+    /// Whether the text area responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// Whether the text area has keyboard focus. Set by the gui.
+    pub focused: bool,
+    /// The number of characters before the caret.
+    pub caret: usize,
+    /// The other end of the selection from the caret, in characters.
+    /// It's the same as caret when nothing is selected.
+    pub selection_anchor: usize,
+    /// The edits that can be undone, with the most recent last.
+    pub undo_history: Vec<GUITextEdit>,
+    /// The edits that have been undone and can be redone, with the most recently undone last.
+    pub redo_history: Vec<GUITextEdit>,
+    /// The most edits that are kept to be undone.
+    pub history_limit: usize,
+    /// The index of the first line that's shown.
+    pub scroll_line: usize,
+    /// How thick the scrollbar's thumb is. It's drawn in the padding along the right edge.
+    pub scrollbar_thickness: GUILength,
+    /// The shortest that the scrollbar's thumb gets, however many lines there are.
+    pub min_thumb_length: GUILength,
+    /// The color of the scrollbar's thumb.
+    pub thumb_color: GUIColor,
+    /// Whether the next measurement should scroll the caret into view. Set by the gui.
+    pub follow_caret: bool,
+    /// Where the caret was across the line when it started moving up or down,
+    /// so that it goes back there after passing through shorter lines. Set by the gui.
+    pub preferred_offset: Option<f32>,
+    /// The lines that the text wraps into. Measured by the gui.
+    pub lines: Vec<GUITextLine>,
+    /// How many characters at the start and at the end of the text haven't changed since
+    /// it was last measured, or None if none of it has. Only the lines in between
+    /// are wrapped again. Set by the gui.
+    pub unchanged_ends: Option<(usize, usize)>,
+    /// The width, font size and tab advance that the lines were wrapped with.
+    /// Measured by the gui.
+    pub wrapped_with: (f32, f32, f32),
+    /// How many characters long the text was when its lines were wrapped. Measured by the gui.
+    pub wrapped_length: usize,
+    /// The distance between the tops of two lines. Measured by the gui.
+    pub line_height: f32,
+    /// The height of the caret. Measured by the gui.
+    pub caret_height: f32,
+    /// Whether the text has changed since the change callback was last run. Set by the gui.
+    pub value_changed: bool,
+    pub id: u128,
+}
+
+impl GUITextArea {
+    /// Returns a text area with the default values that contains text.
+    pub fn new(text: &str) -> Self {
+        let mut text_area = Self::default();
+        text_area.set_text(text);
+        text_area
+    }
+
+    /// Replaces the text, forgetting the edit history, and moves the caret to the start.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = GUITextBuffer::new(text);
+        self.lines.clear();
+        self.undo_history.clear();
+        self.redo_history.clear();
+        self.move_caret(0, false);
+    }
+
+    /// Returns the selected text, which is empty if nothing is selected.
+    pub fn selected_text(&self) -> String {
+        let (start, end) = self.selection();
+        self.text.slice(start..end)
+    }
+
+    /// Selects all of the text.
+    pub fn select_all(&mut self) {
+        self.selection_anchor = 0;
+        self.caret = self.text.len();
+        self.preferred_offset = None;
+    }
+
+    /// Replaces the selection with text, or inserts it at the caret if nothing is selected.
+    /// Returns false if nothing changed.
+    pub fn insert(&mut self, text: &str) -> bool {
+        self.replace_selection(text, false)
+    }
+
+    /// Undoes the most recent edit. Returns false if there wasn't one.
+    pub fn undo(&mut self) -> bool {
+        let edit = match self.undo_history.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+        let inserted_end = edit.position + edit.inserted.chars().count();
+        self.text.remove(edit.position..inserted_end);
+        self.text.insert(edit.position, &edit.removed);
+        self.note_change(edit.position, edit.removed.chars().count());
+        (self.caret, self.selection_anchor) = edit.selection_before;
+        self.redo_history.push(edit);
+        self.follow_caret = true;
+        self.preferred_offset = None;
+        true
+    }
+
+    /// Redoes the most recently undone edit. Returns false if there wasn't one.
+    pub fn redo(&mut self) -> bool {
+        let edit = match self.redo_history.pop() {
+            Some(edit) => edit,
+            None => return false,
+        };
+        let removed_end = edit.position + edit.removed.chars().count();
+        self.text.remove(edit.position..removed_end);
+        self.text.insert(edit.position, &edit.inserted);
+        self.note_change(edit.position, edit.inserted.chars().count());
+        self.move_caret(edit.position + edit.inserted.chars().count(), false);
+        self.undo_history.push(edit);
+        true
+    }
+
+    /// Makes an edit, such as an undo or replace_selection, noting that the gui made it
+    /// so that the change callback is run. Returns whether the edit changed the text.
+    fn input_edit(&mut self, edit: impl FnOnce(&mut Self) -> bool) -> bool {
+        let changed = edit(self);
+        self.value_changed |= changed;
+        changed
+    }
+
+    /// Replaces the selection with text and records the change so that it can be undone.
+    fn replace_selection(&mut self, text: &str, typed: bool) -> bool {
+        let (start, end) = self.selection();
+        if text.is_empty() && start == end {
+            return false;
+        }
+        let removed = self.text.remove(start..end);
+        self.text.insert(start, text);
+        self.note_change(start, text.chars().count());
+        let edit = GUITextEdit {
+            position: start,
+            removed,
+            inserted: String::from(text),
+            selection_before: (self.caret, self.selection_anchor),
+            typed,
+        };
+        self.redo_history.clear();
+        self.record(edit);
+        self.move_caret(start + text.chars().count(), false);
+        true
+    }
+
+    /// Notes that the text has changed from start up to inserted characters after it,
+    /// so that those lines are wrapped again.
+    fn note_change(&mut self, start: usize, inserted: usize) {
+        let unchanged_end = self.text.len() - (start + inserted);
+        self.unchanged_ends = Some(match self.unchanged_ends {
+            Some((unchanged_start, previous_end)) => {
+                (unchanged_start.min(start), previous_end.min(unchanged_end))
+            }
+            None => (start, unchanged_end),
+        });
+    }
+
+    /// Wraps the text into lines that are no wider than width, starting a line before the
+    /// first one that's changed and stopping once the lines start where they did before.
+    fn wrap_lines(&mut self, font: &GUIFont, px: f32, width: f32, tab_advance: f32) {
+        let length = self.text.len();
+        let (unchanged_start, unchanged_end) = match self.unchanged_ends {
+            Some((start, end)) => (start.min(length), end.min(length - start.min(length))),
+            None => (0, 0),
+        };
+        // Where the text that's the same as before starts again.
+        let changed_end = length - unchanged_end;
+        let shift = length as isize - self.wrapped_length as isize;
+        // An edit can make room for the first word of its line on the line above.
+        let first_line = self.line_of(unchanged_start).saturating_sub(1);
+        let old_lines = self.lines.split_off(first_line.min(self.lines.len()));
+        let start = old_lines.first().map_or(0, |line| line.start);
+
+        let mut line = GUITextLine {
+            start,
+            caret_offsets: vec![0.],
+        };
+        let mut previous = match start {
+            0 => None,
+            _ => self
+                .text
+                .get(start - 1)
+                .filter(|character| *character != '\n'),
+        };
+        // The number of characters in the line that it can be broken after.
+        let mut last_break: Option<usize> = None;
+        for (position, character) in self.text.chars().enumerate().skip(start) {
+            if character == '\n' {
+                let next_line = GUITextLine {
+                    start: position + 1,
+                    caret_offsets: vec![0.],
+                };
+                self.lines.push(std::mem::replace(&mut line, next_line));
+                previous = None;
+                last_break = None;
+                if let Some(index) = resume_index(&old_lines, line.start, changed_end, shift) {
+                    self.lines.extend(shifted_lines(old_lines, index, shift));
+                    return;
+                }
+                continue;
+            }
+
+            let advance = match (previous, character) {
+                (_, '\t') => tab_advance,
+                (Some(previous), _) if previous != '\t' => {
+                    font.kern(previous, character, px) + font.metrics(character, px).advance_width
+                }
+                _ => font.metrics(character, px).advance_width,
+            };
+            let x = *line.caret_offsets.last().unwrap();
+            // Whitespace can hang past the edge, so lines are only broken before other characters.
+            if x + advance > width && line.caret_offsets.len() > 1 && !character.is_whitespace() {
+                // Break after the last whitespace if there is some, and otherwise
+                // break the word where it reaches the edge.
+                let length = last_break.unwrap_or(line.caret_offsets.len() - 1);
+                let break_offset = line.caret_offsets[length];
+                let next_line = GUITextLine {
+                    start: line.start + length,
+                    caret_offsets: line.caret_offsets[length..]
+                        .iter()
+                        .map(|offset| offset - break_offset)
+                        .collect(),
+                };
+                line.caret_offsets.truncate(length + 1);
+                self.lines.push(std::mem::replace(&mut line, next_line));
+                last_break = None;
+                // The rest of the line is laid out from the same characters as before,
+                // so the lines from here on are too.
+                if let Some(index) = resume_index(&old_lines, line.start, changed_end, shift) {
+                    self.lines.extend(shifted_lines(old_lines, index, shift));
+                    return;
+                }
+            }
+            let x = *line.caret_offsets.last().unwrap();
+            line.caret_offsets.push(x + advance);
+            previous = Some(character);
+            if character.is_whitespace() {
+                last_break = Some(line.caret_offsets.len() - 1);
+            }
+        }
+        self.lines.push(line);
+    }
+
+    /// Adds an edit to the undo history, joining typing onto the typing just before it
+    /// so that a line is undone at a time rather than a character.
+    fn record(&mut self, edit: GUITextEdit) {
+        if let Some(last) = self.undo_history.last_mut() {
+            if edit.typed
+                && last.typed
+                && edit.removed.is_empty()
+                && last.removed.is_empty()
+                && last.position + last.inserted.chars().count() == edit.position
+                && !last.inserted.ends_with('\n')
+            {
+                last.inserted.push_str(&edit.inserted);
+                return;
+            }
+        }
+        self.undo_history.push(edit);
+        if self.undo_history.len() > self.history_limit {
+            let excess = self.undo_history.len() - self.history_limit;
+            self.undo_history.drain(..excess);
+        }
+    }
+
+    /// Returns the start and end of the selection, in characters.
+    fn selection(&self) -> (usize, usize) {
+        (
+            self.caret.min(self.selection_anchor),
+            self.caret.max(self.selection_anchor),
+        )
+    }
+
+    /// Moves the caret to position. The selection is extended if extend is true,
+    /// and otherwise removed.
+    fn move_caret(&mut self, position: usize, extend: bool) {
+        self.caret = position.min(self.text.len());
+        if !extend {
+            self.selection_anchor = self.caret;
+        }
+        self.follow_caret = true;
+        self.preferred_offset = None;
+    }
+
+    /// Returns the start of the word before the caret.
+    fn previous_word_start(&self) -> usize {
+        let mut position = self.caret;
+        while position > 0 && self.text.get(position - 1).is_some_and(char::is_whitespace) {
+            position -= 1;
+        }
+        while position > 0
+            && self
+                .text
+                .get(position - 1)
+                .is_some_and(|character| !character.is_whitespace())
+        {
+            position -= 1;
+        }
+        position
+    }
+
+    /// Returns the end of the word after the caret.
+    fn next_word_end(&self) -> usize {
+        let mut position = self.caret;
+        while self.text.get(position).is_some_and(char::is_whitespace) {
+            position += 1;
+        }
+        while self
+            .text
+            .get(position)
+            .is_some_and(|character| !character.is_whitespace())
+        {
+            position += 1;
+        }
+        position
+    }
+
+    /// Removes the characters between the caret and position, or the selection if there is one.
+    fn delete_to(&mut self, position: usize) -> bool {
+        if self.caret == self.selection_anchor {
+            self.selection_anchor = position;
+        }
+        self.replace_selection("", false)
+    }
+
+    /// Returns the index of the line that position is drawn on.
+    /// A position where a line wraps is at the start of the next line.
+    fn line_of(&self, position: usize) -> usize {
+        self.lines
+            .partition_point(|line| line.start <= position)
+            .saturating_sub(1)
+    }
+
+    /// Returns the distance from the start of its line to the caret position.
+    fn offset_of(&self, position: usize) -> f32 {
+        match self.lines.get(self.line_of(position)) {
+            Some(line) => {
+                let column = position.saturating_sub(line.start);
+                line.caret_offsets[column.min(line.caret_offsets.len() - 1)]
+            }
+            None => 0.,
+        }
+    }
+
+    /// Returns the caret position on a line that's closest to offset.
+    fn nearest_on_line(&self, line_index: usize, offset: f32) -> usize {
+        let line = &self.lines[line_index];
+        let mut nearest = 0;
+        for (column, column_offset) in line.caret_offsets.iter().enumerate() {
+            if (column_offset - offset).abs() < (line.caret_offsets[nearest] - offset).abs() {
+                nearest = column;
+            }
+        }
+        line.start + nearest
+    }
+
+    /// Returns where the caret ends up after moving lines lines down, or up if it's negative.
+    /// Moving past the first or last line goes to the start or end of the text.
+    fn vertical_target(&self, lines: isize) -> (usize, f32) {
+        let offset = self
+            .preferred_offset
+            .unwrap_or_else(|| self.offset_of(self.caret));
+        if self.lines.is_empty() {
+            return (if lines < 0 { 0 } else { self.text.len() }, offset);
+        }
+        let target = self.line_of(self.caret) as isize + lines;
+        if target < 0 {
+            (0, offset)
+        } else if target as usize >= self.lines.len() {
+            (self.text.len(), offset)
+        } else {
+            (self.nearest_on_line(target as usize, offset), offset)
+        }
+    }
+
+    /// Moves the caret lines lines down, or up if it's negative, staying as close as it can
+    /// to where it was across the line.
+    fn move_vertically(&mut self, lines: isize, extend: bool) {
+        let (position, offset) = self.vertical_target(lines);
+        self.move_caret(position, extend);
+        self.preferred_offset = Some(offset);
+    }
+
+    /// Returns how many whole lines fit in the text area.
+    fn visible_line_count(&self) -> usize {
+        if self.line_height <= 0. {
+            return 1;
+        }
+        let height = self.size.height.get_length() - 2. * self.padding.get_length();
+        ((height / self.line_height as f64) as usize).max(1)
+    }
+
+    /// Returns the index of the last line that can be scrolled to the top.
+    fn max_scroll_line(&self) -> usize {
+        self.lines.len().saturating_sub(self.visible_line_count())
+    }
+
+    /// Returns the range of lines that are shown.
+    fn visible_lines(&self) -> std::ops::Range<usize> {
+        let start = self.scroll_line.min(self.lines.len());
+        start..(start + self.visible_line_count()).min(self.lines.len())
+    }
+}
+
+/// Returns the index of the old line that starts where a new line starting at start would
+/// have, if the text from just before start is the same as it was, in which case
+/// the lines from there on haven't changed.
+fn resume_index(
+    old_lines: &[GUITextLine],
+    start: usize,
+    changed_end: usize,
+    shift: isize,
+) -> Option<usize> {
+    if start <= changed_end {
+        return None;
+    }
+    let old_start = (start as isize - shift) as usize;
+    old_lines
+        .binary_search_by_key(&old_start, |line| line.start)
+        .ok()
+}
+
+/// Returns the old lines from index on, moved along by how much longer the text has got.
+fn shifted_lines(
+    old_lines: Vec<GUITextLine>,
+    index: usize,
+    shift: isize,
+) -> impl Iterator<Item = GUITextLine> {
+    old_lines.into_iter().skip(index).map(move |mut line| {
+        line.start = (line.start as isize + shift) as usize;
+        line
+    })
+}
+
+/// Returns whether the modifier that makes the caret jump whole words is held down,
+/// which is option on macOS and ctrl everywhere else.
+fn jumps_words(modifiers: &GUIModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.alt
+    } else {
+        modifiers.ctrl
+    }
+}
+
+impl Widget for GUITextArea {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        if !event.pressed {
+            return false;
+        }
+        let modifiers = &event.modifiers;
+        let extend = modifiers.shift;
+        let (start, end) = self.selection();
+        let page = self.visible_line_count() as isize;
+        match event.key {
+            GUIKey::Letter('z') if modifiers.shortcut() && modifiers.shift => {
+                self.input_edit(Self::redo)
+            }
+            GUIKey::Letter('z') if modifiers.shortcut() => self.input_edit(Self::undo),
+            GUIKey::Letter('y') if modifiers.shortcut() => self.input_edit(Self::redo),
+            GUIKey::Letter('a') if modifiers.shortcut() => {
+                self.select_all();
+                true
+            }
+            // Shift+Tab and Ctrl+Tab are left to move the focus, so it can't get stuck here.
+            GUIKey::Tab
+                if !modifiers.shift && !modifiers.ctrl && !modifiers.alt && !modifiers.logo =>
+            {
+                self.input_edit(|area| area.replace_selection("\t", true))
+            }
+            GUIKey::Enter => self.input_edit(|area| area.replace_selection("\n", true)),
+            GUIKey::Backspace if jumps_words(modifiers) => {
+                self.input_edit(|area| area.delete_to(area.previous_word_start()))
+            }
+            GUIKey::Delete if jumps_words(modifiers) => {
+                self.input_edit(|area| area.delete_to(area.next_word_end()))
+            }
+            GUIKey::Backspace => {
+                self.input_edit(|area| area.delete_to(area.caret.saturating_sub(1)))
+            }
+            GUIKey::Delete => {
+                self.input_edit(|area| area.delete_to((area.caret + 1).min(area.text.len())))
+            }
+            _ => {
+                match event.key {
+                    GUIKey::Left if jumps_words(modifiers) => {
+                        self.move_caret(self.previous_word_start(), extend)
+                    }
+                    GUIKey::Right if jumps_words(modifiers) => {
+                        self.move_caret(self.next_word_end(), extend)
+                    }
+                    // Without shift, an arrow collapses the selection to the side it points at.
+                    GUIKey::Left if !extend && start != end => self.move_caret(start, false),
+                    GUIKey::Right if !extend && start != end => self.move_caret(end, false),
+                    GUIKey::Left => self.move_caret(self.caret.saturating_sub(1), extend),
+                    GUIKey::Right => self.move_caret(self.caret + 1, extend),
+                    GUIKey::Up => self.move_vertically(-1, extend),
+                    GUIKey::Down => self.move_vertically(1, extend),
+                    GUIKey::PageUp => self.move_vertically(-page, extend),
+                    GUIKey::PageDown => self.move_vertically(page, extend),
+                    GUIKey::Home if modifiers.shortcut() => self.move_caret(0, extend),
+                    GUIKey::End if modifiers.shortcut() => self.move_caret(self.text.len(), extend),
+                    GUIKey::Home => {
+                        let line_start = match self.lines.get(self.line_of(self.caret)) {
+                            Some(line) => line.start,
+                            None => 0,
+                        };
+                        self.move_caret(line_start, extend)
+                    }
+                    GUIKey::End => {
+                        let line_end = match self.lines.get(self.line_of(self.caret)) {
+                            Some(line) => line.end(),
+                            None => self.text.len(),
+                        };
+                        self.move_caret(line_end, extend)
+                    }
+                    _ => return false,
+                }
+                true
+            }
+        }
+    }
+
+    fn character_input(&mut self, character: char) -> bool {
+        // Backspace, enter, tab and the like come through as key presses.
+        if character.is_control() {
+            return false;
+        }
+        self.input_edit(|area| area.replace_selection(&character.to_string(), true))
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.value_changed)
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        if self.lines.is_empty() || self.line_height <= 0. {
+            return false;
+        }
+        let x = (event.position.x.get_length() - self.padding.get_length()) as f32;
+        let y = (event.position.y.get_length() - self.padding.get_length()) as f32;
+        // Dragging above or below the text area picks a line that's out of view,
+        // which scrolls it into view.
+        let line = (self.scroll_line as f32 + (y / self.line_height).floor())
+            .clamp(0., (self.lines.len() - 1) as f32) as usize;
+        let position = self.nearest_on_line(line, x);
+        match event.kind {
+            GUIPointerEventKind::Pressed => self.move_caret(position, event.modifiers.shift),
+            GUIPointerEventKind::Moved => self.move_caret(position, true),
+            GUIPointerEventKind::Released => return false,
+        }
+        true
+    }
+
+    fn measure_text(&mut self, font: &GUIFont) {
+        let px = self.font_size.get_length() as f32;
+        let line_metrics = font.line_metrics(px);
+        self.line_height = line_metrics.new_line_size;
+        self.caret_height = line_metrics.ascent - line_metrics.descent;
+        let tab_advance = font.metrics(' ', px).advance_width * self.tab_width as f32;
+        let width = (self.size.width.get_length() - 2. * self.padding.get_length()) as f32;
+
+        // Every line is wrapped again when the width or the font changes,
+        // and otherwise only the lines that have been edited are.
+        let wrapped_with = (width, px, tab_advance);
+        if self.lines.is_empty() || wrapped_with != self.wrapped_with {
+            self.lines.clear();
+            self.unchanged_ends = None;
+            self.wrap_lines(font, px, width, tab_advance);
+        } else if self.unchanged_ends.is_some() {
+            self.wrap_lines(font, px, width, tab_advance);
+        }
+        self.unchanged_ends = None;
+        self.wrapped_with = wrapped_with;
+        self.wrapped_length = self.text.len();
+
+        // Keep the caret in view after it's moved, and otherwise just keep the scrolling
+        // within the text.
+        let visible_line_count = self.visible_line_count();
+        if self.follow_caret {
+            let caret_line = self.line_of(self.caret);
+            if caret_line < self.scroll_line {
+                self.scroll_line = caret_line;
+            } else if caret_line >= self.scroll_line + visible_line_count {
+                self.scroll_line = caret_line + 1 - visible_line_count;
+            }
+            self.follow_caret = false;
+        }
+        self.scroll_line = self.scroll_line.min(self.max_scroll_line());
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_scroll_offset(&self) -> GUIPosition {
+        GUIPosition::from_pixels(0., self.scroll_line as f64 * self.line_height as f64)
+    }
+
+    fn set_scroll_offset(&mut self, offset: GUIPosition) -> bool {
+        if self.line_height <= 0. {
+            return false;
+        }
+        let current = self.scroll_line as f64;
+        let target = offset.y.get_length() / self.line_height as f64;
+        let mut line = target.round();
+        // The text scrolls by whole lines, so a trackpad scrolling by less than
+        // half of one still moves a line the way it's going.
+        if line == current && (target - current).abs() > 0.01 {
+            line += (target - current).signum();
+        }
+        let line = (line.max(0.) as usize).min(self.max_scroll_line());
+        if line == self.scroll_line {
+            return false;
+        }
+        self.scroll_line = line;
+        true
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        let left = self.position.x.add(&parent_position.x).add(&self.padding);
+        let top = self.position.y.add(&parent_position.y).add(&self.padding);
+        let tab: String = " ".repeat(self.tab_width);
+        // Only the lines that are in view are laid out.
+        self.visible_lines()
+            .enumerate()
+            .map(|(row, line_index)| {
+                let line = &self.lines[line_index];
+                GUIText {
+                    text: self.text.slice(line.start..line.end()).replace('\t', &tab),
+                    position: GUIPosition::from_lengths(
+                        left,
+                        top.add(&GUILength::from_pixels(
+                            row as f64 * self.line_height as f64,
+                        )),
+                    ),
+                    size: GUISize::from_lengths(
+                        self.size
+                            .width
+                            .subtract(&self.padding)
+                            .subtract(&self.padding),
+                        GUILength::from_pixels(self.line_height as f64),
+                    ),
+                    font_size: self.font_size,
+                    color: self.text_color,
+                    horizontal_alignment: GUIHorizontalAlignment::Left,
+                    vertical_alignment: GUIVerticalAlignment::Top,
+                    wrap: false,
+                    overflow: GUITextOverflow::Visible,
+                }
+            })
+            .collect()
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let background_color = if self.enabled {
+            self.background_color
+        } else {
+            self.disabled_color
+        };
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            self.radius,
+            &background_color,
+        );
+        // Only the background is hit tested.
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        if self.focused {
+            let left = origin.x.add(&self.padding).get_length();
+            let top = origin.y.add(&self.padding).get_length();
+            let caret_height = GUILength::from_pixels(self.caret_height as f64);
+            let (start, end) = self.selection();
+            let caret_line = self.line_of(self.caret);
+
+            for (row, line_index) in self.visible_lines().enumerate() {
+                let line = &self.lines[line_index];
+                let line_top = top + row as f64 * self.line_height as f64;
+                let selection_start = start.max(line.start);
+                let selection_end = end.min(line.end());
+                if selection_start < end && start <= line.end() {
+                    let start_offset = line.caret_offsets[selection_start - line.start] as f64;
+                    let mut end_offset = line.caret_offsets[selection_end - line.start] as f64;
+                    // Show that the selection carries on past the end of the line.
+                    if end > line.end() {
+                        end_offset += self.caret_height as f64 / 4.;
+                    }
+                    widget_utils::push_rectangle(
+                        &mut vertices,
+                        &mut indices,
+                        indice_offset,
+                        &GUIPosition::from_pixels(left + start_offset, line_top),
+                        &GUISize::from_lengths(
+                            GUILength::from_pixels(end_offset - start_offset),
+                            caret_height,
+                        ),
+                        &self.selection_color,
+                    );
+                }
+
+                if line_index == caret_line {
+                    let caret_offset = self.offset_of(self.caret) as f64;
+                    widget_utils::push_rectangle(
+                        &mut vertices,
+                        &mut indices,
+                        indice_offset,
+                        &GUIPosition::from_pixels(
+                            left + caret_offset - self.caret_width.get_length() / 2.,
+                            line_top,
+                        ),
+                        &GUISize::from_lengths(self.caret_width, caret_height),
+                        &self.caret_color,
+                    );
+                }
+            }
+        }
+
+        let visible_line_count = self.visible_line_count();
+        if self.lines.len() > visible_line_count {
+            let padding = self.padding.get_length();
+            let thickness = self.scrollbar_thickness.get_length();
+            let track = self.size.height.get_length() - 2. * padding;
+            // The thumb is as long, compared to the track, as the lines in view are
+            // compared to all of them.
+            let (start, length) = thumb_span(
+                track,
+                track * self.lines.len() as f64 / visible_line_count as f64,
+                self.scroll_line as f64,
+                self.max_scroll_line() as f64,
+                self.min_thumb_length.get_length(),
+            );
+            widget_utils::push_rounded_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(
+                    origin.x.add(&self.size.width).get_length() - (padding + thickness) / 2.,
+                    origin.y.get_length() + padding + start,
+                ),
+                &GUISize::from_lengths(self.scrollbar_thickness, GUILength::from_pixels(length)),
+                GUILength::from_pixels(thickness / 2.),
+                &self.thumb_color,
+            );
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUITextArea {
+    /// Returns an empty text area with all of the default values.
+    fn default() -> Self {
+        Self {
+            text: GUITextBuffer::default(),
+            size: GUISize {
+                width: GUILength::from_pixels(300.),
+                height: GUILength::from_pixels(200.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            radius: GUILength::from_pixels(4.),
+            padding: GUILength::from_pixels(8.),
+            font_size: GUILength::from_pixels(16.),
+            tab_width: 4,
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.85,
+                g: 0.85,
+                b: 0.85,
+                a: 1.,
+            },
+            text_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            selection_color: GUIColor {
+                r: 0.6,
+                g: 0.75,
+                b: 1.,
+                a: 1.,
+            },
+            caret_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            caret_width: GUILength::from_pixels(1.),
+            enabled: true,
+            focused: false,
+            caret: 0,
+            selection_anchor: 0,
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+            history_limit: 1000,
+            scroll_line: 0,
+            scrollbar_thickness: GUILength::from_pixels(4.),
+            min_thumb_length: GUILength::from_pixels(16.),
+            thumb_color: GUIColor {
+                r: 0.6,
+                g: 0.6,
+                b: 0.6,
+                a: 1.,
+            },
+            follow_caret: false,
+            preferred_offset: None,
+            lines: Vec::new(),
+            unchanged_ends: None,
+            wrapped_with: (0., 0., 0.),
+            wrapped_length: 0,
+            line_height: 0.,
+            caret_height: 0.,
+            value_changed: false,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiwidgets::{GUIBase, GUIWindow};
+
+    fn shortcut() -> GUIModifiers {
+        GUIModifiers {
+            ctrl: !cfg!(target_os = "macos"),
+            logo: cfg!(target_os = "macos"),
+            ..Default::default()
+        }
+    }
+
+    fn press(text_area: &mut GUITextArea, key: GUIKey, modifiers: GUIModifiers) -> bool {
+        text_area.key_input(&GUIKeyEvent {
+            key,
+            modifiers,
+            pressed: true,
+        })
+    }
+
+    fn type_text(text_area: &mut GUITextArea, text: &str) {
+        for character in text.chars() {
+            text_area.character_input(character);
+        }
+    }
+
+    /// Returns a text area that's measured with the default font,
+    /// narrow enough that "one two three" wraps after "two".
+    fn measured(text: &str) -> GUITextArea {
+        let font = GUIFont::default();
+        let font_size = GUILength::from_pixels(16.);
+        let padding = GUILength::from_pixels(8.);
+        let width = font.measure("one two ", &font_size).get_length() + 2. * padding.get_length();
+        let mut text_area = GUITextArea {
+            size: GUISize::from_pixels(width + 1., 80.),
+            padding,
+            font_size,
+            ..GUITextArea::new(text)
+        };
+        text_area.measure_text(&font);
+        text_area
+    }
+
+    #[test]
+    fn enter_and_tab_insert_and_undo_redo_whole_edits() {
+        let mut text_area = GUITextArea::default();
+        type_text(&mut text_area, "hello");
+        assert!(press(
+            &mut text_area,
+            GUIKey::Enter,
+            GUIModifiers::default()
+        ));
+        assert!(press(&mut text_area, GUIKey::Tab, GUIModifiers::default()));
+        type_text(&mut text_area, "world");
+        assert_eq!(text_area.text.to_string(), "hello\n\tworld");
+
+        // Shift+Tab is left for moving the focus.
+        let shift = GUIModifiers {
+            shift: true,
+            ..Default::default()
+        };
+        assert!(!press(&mut text_area, GUIKey::Tab, shift));
+
+        assert!(press(&mut text_area, GUIKey::Letter('z'), shortcut()));
+        assert_eq!(text_area.text.to_string(), "hello\n");
+        assert!(press(&mut text_area, GUIKey::Letter('z'), shortcut()));
+        assert_eq!(text_area.text.to_string(), "");
+        assert_eq!(text_area.caret, 0);
+
+        assert!(text_area.redo());
+        assert!(text_area.redo());
+        assert_eq!(text_area.text.to_string(), "hello\n\tworld");
+        assert!(!text_area.redo());
+
+        // A new edit can't be redone past.
+        text_area.undo();
+        text_area.insert("!");
+        assert!(!text_area.redo());
+        assert_eq!(text_area.text.to_string(), "hello\n!");
+    }
+
+    #[test]
+    fn deleting_a_selection_is_undone_with_the_selection() {
+        let mut text_area = GUITextArea::new("one two");
+        text_area.caret = 7;
+        text_area.selection_anchor = 4;
+        press(&mut text_area, GUIKey::Backspace, GUIModifiers::default());
+        assert_eq!(text_area.text.to_string(), "one ");
+        text_area.undo();
+        assert_eq!(text_area.text.to_string(), "one two");
+        assert_eq!(text_area.selected_text(), "two");
+    }
+
+    #[test]
+    fn long_lines_wrap_between_words() {
+        let text_area = measured("one two three\n\nfour");
+        let starts: Vec<usize> = text_area.lines.iter().map(|line| line.start).collect();
+        assert_eq!(starts, vec![0, 8, 14, 15]);
+        assert_eq!(text_area.lines[0].end(), 8);
+        assert_eq!(text_area.lines[1].end(), 13);
+        assert_eq!(text_area.lines[2].end(), 14);
+
+        // A word that's too long for a line on its own is broken.
+        let text_area = measured("onetwothreefour");
+        assert!(text_area.lines.len() > 1);
+        assert_eq!(text_area.lines.last().unwrap().end(), 15);
+    }
+
+    #[test]
+    fn edits_wrap_the_same_as_wrapping_everything_again() {
+        let font = GUIFont::default();
+        let text: Vec<&str> = (0..10).map(|_| "one two three four").collect();
+        let mut text_area = measured(&text.join("\n"));
+        let same_as_rewrapped = |text_area: &GUITextArea| {
+            let rewrapped = measured(&text_area.text.to_string());
+            assert_eq!(text_area.lines, rewrapped.lines);
+        };
+
+        // Shortening a word lets the next one move up a line.
+        text_area.caret = 6;
+        text_area.selection_anchor = 6;
+        press(&mut text_area, GUIKey::Backspace, GUIModifiers::default());
+        press(&mut text_area, GUIKey::Backspace, GUIModifiers::default());
+        text_area.measure_text(&font);
+        same_as_rewrapped(&text_area);
+
+        // Edits in several places before the text is measured again.
+        type_text(&mut text_area, "wo and more");
+        text_area.caret = text_area.text.len();
+        text_area.selection_anchor = 100;
+        text_area.insert("!");
+        text_area.caret = 40;
+        text_area.selection_anchor = 40;
+        press(&mut text_area, GUIKey::Enter, GUIModifiers::default());
+        text_area.measure_text(&font);
+        same_as_rewrapped(&text_area);
+
+        text_area.undo();
+        text_area.undo();
+        text_area.measure_text(&font);
+        same_as_rewrapped(&text_area);
+        text_area.redo();
+        text_area.measure_text(&font);
+        same_as_rewrapped(&text_area);
+    }
+
+    #[test]
+    fn up_and_down_keep_the_caret_across_the_line() {
+        let mut text_area = measured("abcdef\nab\nabcdef");
+        text_area.caret = 5;
+        text_area.selection_anchor = 5;
+        press(&mut text_area, GUIKey::Down, GUIModifiers::default());
+        assert_eq!(text_area.caret, 9);
+        press(&mut text_area, GUIKey::Down, GUIModifiers::default());
+        assert_eq!(text_area.caret, 15);
+        press(&mut text_area, GUIKey::Down, GUIModifiers::default());
+        assert_eq!(text_area.caret, 16);
+
+        press(&mut text_area, GUIKey::Home, GUIModifiers::default());
+        assert_eq!(text_area.caret, 10);
+        press(&mut text_area, GUIKey::Home, shortcut());
+        assert_eq!(text_area.caret, 0);
+        press(&mut text_area, GUIKey::End, GUIModifiers::default());
+        assert_eq!(text_area.caret, 6);
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_caret_in_view() {
+        let font = GUIFont::default();
+        let text: Vec<String> = (0..20).map(|line| line.to_string()).collect();
+        let mut text_area = measured(&text.join("\n"));
+        let visible_line_count = text_area.visible_line_count();
+        assert!(visible_line_count < 20);
+        assert_eq!(
+            text_area.get_text(&GUIPosition::default()).len(),
+            visible_line_count
+        );
+
+        press(&mut text_area, GUIKey::End, shortcut());
+        text_area.measure_text(&font);
+        assert_eq!(text_area.scroll_line, 20 - visible_line_count);
+        let texts = text_area.get_text(&GUIPosition::default());
+        assert_eq!(texts.last().unwrap().text, "19");
+
+        press(&mut text_area, GUIKey::PageUp, GUIModifiers::default());
+        text_area.measure_text(&font);
+        assert_eq!(text_area.line_of(text_area.caret), 19 - visible_line_count);
+        assert_eq!(text_area.scroll_line, 19 - visible_line_count);
+    }
+
+    #[test]
+    fn scrolls_by_lines_and_shows_a_thumb() {
+        let text: Vec<String> = (0..20).map(|line| line.to_string()).collect();
+        let mut text_area = measured(&text.join("\n"));
+        let line_height = text_area.line_height as f64;
+        let max_scroll_line = 20 - text_area.visible_line_count();
+
+        assert!(text_area.set_scroll_offset(GUIPosition::from_pixels(0., 2. * line_height)));
+        assert_eq!(text_area.scroll_line, 2);
+        assert_eq!(
+            text_area.get_scroll_offset(),
+            GUIPosition::from_pixels(0., 2. * line_height)
+        );
+        // Scrolling sideways doesn't move the lines, and a small scroll moves a whole line.
+        assert!(!text_area.set_scroll_offset(text_area.get_scroll_offset()));
+        assert!(text_area.set_scroll_offset(GUIPosition::from_pixels(0., 2. * line_height - 1.)));
+        assert_eq!(text_area.scroll_line, 1);
+        assert!(text_area.set_scroll_offset(GUIPosition::from_pixels(0., 1000. * line_height)));
+        assert_eq!(text_area.scroll_line, max_scroll_line);
+        let texts = text_area.get_text(&GUIPosition::default());
+        assert_eq!(texts.last().unwrap().text, "19");
+
+        // Only the thumb is drawn over the background, since the text area isn't focused.
+        let (vertices, _, polygon) = text_area.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert!(vertices.len() > polygon.end_index);
+        let (vertices, _, polygon) =
+            measured("one").get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert_eq!(vertices.len(), polygon.end_index);
+    }
+
+    #[test]
+    fn pointer_drags_a_selection_across_lines() {
+        let mut text_area = measured("abc\ndef\nghi");
+        let padding = text_area.padding.get_length();
+        let line_height = text_area.line_height as f64;
+        let pointer = |kind, x: f64, line: f64| GUIPointerEvent {
+            kind,
+            position: GUIPosition::from_pixels(padding + x, padding + (line + 0.5) * line_height),
+            modifiers: GUIModifiers::default(),
+        };
+
+        text_area.pointer_input(&pointer(GUIPointerEventKind::Pressed, 0., 0.));
+        assert_eq!(text_area.caret, 0);
+        text_area.pointer_input(&pointer(GUIPointerEventKind::Moved, 1000., 1.));
+        assert_eq!(text_area.selected_text(), "abc\ndef");
+
+        text_area.set_focused(true);
+        let (vertices, _, polygon) = text_area.get_vertices_and_indices(&GUIPosition::default(), 0);
+        // A selection rectangle on each of the two lines and the caret.
+        assert_eq!(vertices.len(), polygon.end_index + 3 * 4);
+    }
+
+    #[test]
+    fn editing_runs_the_change_callback() {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let area_id = guibase.add_child_to_parent(GUITextArea::default(), window_id);
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        guibase.on_change(area_id, move |_| counter.set(counter.get() + 1));
+        guibase.set_focus(Some(area_id));
+        let enter = GUIKeyEvent {
+            key: GUIKey::Enter,
+            modifiers: GUIModifiers::default(),
+            pressed: true,
+        };
+        let undo = GUIKeyEvent {
+            key: GUIKey::Letter('z'),
+            modifiers: shortcut(),
+            pressed: true,
+        };
+
+        guibase.character_input(window_id, 'a');
+        guibase.key_input(window_id, &enter);
+        assert_eq!(changes.get(), 2);
+        guibase.key_input(window_id, &undo);
+        assert_eq!(changes.get(), 3);
+        // Inserting text from code isn't a change made by the user.
+        guibase
+            .get_widget_as_mut::<GUITextArea>(area_id)
+            .unwrap()
+            .insert("code");
+        assert_eq!(changes.get(), 3);
+    }
+}
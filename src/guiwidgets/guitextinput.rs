@@ -0,0 +1,792 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guikeys::{GUIKey, GUIKeyEvent, GUIModifiers};
+use crate::guiproperties::guipointer::{GUIPointerEvent, GUIPointerEventKind};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitext::{
+    GUIHorizontalAlignment, GUIText, GUITextOverflow, GUIVerticalAlignment,
+};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::{GUIColor, GUIFont};
+
+/// A single line of editable text.
+pub struct GUITextInput {
+    /// The text that's been entered.
+    pub text: String,
+    /// Shown in place of the text while it's empty.
+    pub placeholder: String,
+    /// The most characters that can be entered, or None if there's no limit.
+    pub max_length: Option<usize>,
+    /// The size of the text input.
+    pub size: GUISize,
+    /// The location of the text input.
+    pub position: GUIPosition,
+    /// Radius of the text input corners.
+    pub radius: GUILength,
+    /// The space between the edges of the text input and its text.
+    pub padding: GUILength,
+    /// The height of the font.
+    pub font_size: GUILength,
+    /// The background color for the text input.
+    pub background_color: GUIColor,
+    /// The background color while the text input is disabled.
+    pub disabled_color: GUIColor,
+    /// The color of the text.
+    pub text_color: GUIColor,
+    /// The color of the placeholder.
+    pub placeholder_color: GUIColor,
+    /// The color drawn behind selected text.
+    pub selection_color: GUIColor,
+    /// The color of the caret.
+    pub caret_color: GUIColor,
+    /// The width of the caret.
+    pub caret_width: GUILength,
+    /// Whether the text input responds to the mouse and keyboard.
+    pub enabled: bool,
+    /// Whether the text input has keyboard focus. Set by the gui.
+    pub focused: bool,
+    /// The number of characters before the caret.
+    pub caret: usize,
+    /// The other end of the selection from the caret, in characters.
+    /// It's the same as caret when nothing is selected.
+    pub selection_anchor: usize,
+    /// How far from the start of the text each position that the caret can be at is,
+    /// so there's one more than there are characters. Measured by the gui.
+    pub caret_offsets: Vec<f32>,
+    /// The height of the caret. Measured by the gui.
+    pub caret_height: f32,
+    /// The first character that's shown, which moves along as the caret does
+    /// so that the caret stays in view once the text is wider than the text input.
+    /// Set by the gui.
+    pub scroll_start: usize,
+    /// Whether the text has changed since the change callback was last run. Set by the gui.
+    pub value_changed: bool,
+    pub id: u128,
+}
+
+impl GUITextInput {
+    /// Returns a text input with the default values that shows placeholder while it's empty.
+    pub fn new(placeholder: &str) -> Self {
+        Self {
+            placeholder: String::from(placeholder),
+            ..Default::default()
+        }
+    }
+
+    /// Replaces the text, cutting it down to max_length, and moves the caret to its end.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = match self.max_length {
+            Some(max_length) => text.chars().take(max_length).collect(),
+            None => String::from(text),
+        };
+        self.caret = self.char_count();
+        self.selection_anchor = self.caret;
+    }
+
+    /// Returns the selected text, which is empty if nothing is selected.
+    pub fn selected_text(&self) -> &str {
+        let (start, end) = self.selection();
+        &self.text[self.byte_index(start)..self.byte_index(end)]
+    }
+
+    /// Selects all of the text.
+    pub fn select_all(&mut self) {
+        self.selection_anchor = 0;
+        self.caret = self.char_count();
+    }
+
+    /// Replaces the selection with text, or inserts it at the caret if nothing is selected.
+    /// As much of text as fits within max_length is used.
+    /// Returns false if nothing changed.
+    pub fn insert(&mut self, text: &str) -> bool {
+        let (start, end) = self.selection();
+        let room = match self.max_length {
+            Some(max_length) => max_length.saturating_sub(self.char_count() - (end - start)),
+            None => usize::MAX,
+        };
+        let text: String = text.chars().take(room).collect();
+        if text.is_empty() && start == end {
+            return false;
+        }
+        let inserted = text.chars().count();
+        let (start_byte, end_byte) = (self.byte_index(start), self.byte_index(end));
+        self.text.replace_range(start_byte..end_byte, &text);
+        self.caret = start + inserted;
+        self.selection_anchor = self.caret;
+        true
+    }
+
+    /// Inserts text the way that insert does, noting that the gui changed it
+    /// so that the change callback is run.
+    fn input_text(&mut self, text: &str) -> bool {
+        let changed = self.insert(text);
+        self.value_changed |= changed;
+        changed
+    }
+
+    /// Returns the start and end of the selection, in characters.
+    fn selection(&self) -> (usize, usize) {
+        (
+            self.caret.min(self.selection_anchor),
+            self.caret.max(self.selection_anchor),
+        )
+    }
+
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Converts a position in characters into one in bytes.
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.text.len(), |(index, _)| index)
+    }
+
+    /// Moves the caret to position. The selection is extended if extend is true,
+    /// and otherwise removed.
+    fn move_caret(&mut self, position: usize, extend: bool) {
+        self.caret = position.min(self.char_count());
+        if !extend {
+            self.selection_anchor = self.caret;
+        }
+    }
+
+    /// Returns the start of the word before the caret.
+    fn previous_word_start(&self) -> usize {
+        let characters: Vec<char> = self.text.chars().collect();
+        let mut position = self.caret.min(characters.len());
+        while position > 0 && characters[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        while position > 0 && !characters[position - 1].is_whitespace() {
+            position -= 1;
+        }
+        position
+    }
+
+    /// Returns the end of the word after the caret.
+    fn next_word_end(&self) -> usize {
+        let characters: Vec<char> = self.text.chars().collect();
+        let mut position = self.caret.min(characters.len());
+        while position < characters.len() && characters[position].is_whitespace() {
+            position += 1;
+        }
+        while position < characters.len() && !characters[position].is_whitespace() {
+            position += 1;
+        }
+        position
+    }
+
+    /// Removes the characters between the caret and position, or the selection if there is one.
+    fn delete_to(&mut self, position: usize) {
+        if self.caret == self.selection_anchor {
+            self.selection_anchor = position;
+        }
+        self.input_text("");
+    }
+
+    /// Returns the distance from the start of the text to the caret position,
+    /// or 0 if the text hasn't been measured yet.
+    fn caret_offset(&self, position: usize) -> f32 {
+        self.caret_offsets
+            .get(position)
+            .or_else(|| self.caret_offsets.last())
+            .copied()
+            .unwrap_or(0.)
+    }
+
+    /// Returns the width of the area within the padding that the text is shown in.
+    fn text_width(&self) -> f32 {
+        (self.size.width.get_length() - 2. * self.padding.get_length()).max(0.) as f32
+    }
+
+    /// Returns how far the text is scrolled to the left.
+    fn scroll_offset(&self) -> f32 {
+        self.caret_offset(self.scroll_start)
+    }
+
+    /// Moves scroll_start along, a character at a time, until the caret is in view,
+    /// and back for as long as the rest of the text still fits, such as after the end
+    /// of the text has been deleted.
+    fn scroll_to_caret(&mut self) {
+        let width = self.text_width();
+        let count = self.char_count();
+        let caret = self.caret.min(count);
+        let mut start = self.scroll_start.min(caret);
+        while start < caret && self.caret_offset(caret) - self.caret_offset(start) > width {
+            start += 1;
+        }
+        while start > 0 && self.caret_offset(count) - self.caret_offset(start - 1) <= width {
+            start -= 1;
+        }
+        self.scroll_start = start;
+    }
+
+    /// Returns the caret position that's closest to x, measured from the start of the text.
+    fn nearest_caret_position(&self, x: f32) -> usize {
+        let mut nearest = 0;
+        for (position, offset) in self.caret_offsets.iter().enumerate() {
+            if (offset - x).abs() < (self.caret_offset(nearest) - x).abs() {
+                nearest = position;
+            }
+        }
+        nearest.min(self.char_count())
+    }
+}
+
+/// Returns whether the modifier that makes the caret jump whole words is held down,
+/// which is option on macOS and ctrl everywhere else.
+fn jumps_words(modifiers: &GUIModifiers) -> bool {
+    if cfg!(target_os = "macos") {
+        modifiers.alt
+    } else {
+        modifiers.ctrl
+    }
+}
+
+impl Widget for GUITextInput {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn key_input(&mut self, event: &GUIKeyEvent) -> bool {
+        if !event.pressed {
+            return false;
+        }
+        let modifiers = &event.modifiers;
+        let extend = modifiers.shift;
+        let (start, end) = self.selection();
+        match event.key {
+            GUIKey::Left if jumps_words(modifiers) => {
+                self.move_caret(self.previous_word_start(), extend)
+            }
+            GUIKey::Right if jumps_words(modifiers) => {
+                self.move_caret(self.next_word_end(), extend)
+            }
+            // Without shift, an arrow collapses the selection to the side it points at.
+            GUIKey::Left if !extend && start != end => self.move_caret(start, false),
+            GUIKey::Right if !extend && start != end => self.move_caret(end, false),
+            GUIKey::Left => self.move_caret(self.caret.saturating_sub(1), extend),
+            GUIKey::Right => self.move_caret(self.caret + 1, extend),
+            GUIKey::Home | GUIKey::Up => self.move_caret(0, extend),
+            GUIKey::End | GUIKey::Down => self.move_caret(self.char_count(), extend),
+            GUIKey::Backspace if jumps_words(modifiers) => {
+                self.delete_to(self.previous_word_start())
+            }
+            GUIKey::Delete if jumps_words(modifiers) => self.delete_to(self.next_word_end()),
+            GUIKey::Backspace => self.delete_to(self.caret.saturating_sub(1)),
+            GUIKey::Delete => self.delete_to((self.caret + 1).min(self.char_count())),
+            GUIKey::Letter('a') if modifiers.shortcut() => self.select_all(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn character_input(&mut self, character: char) -> bool {
+        // Backspace, enter, tab and the like come through as key presses.
+        if character.is_control() {
+            return false;
+        }
+        self.input_text(&character.to_string())
+    }
+
+    fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.value_changed)
+    }
+
+    fn pointer_input(&mut self, event: &GUIPointerEvent) -> bool {
+        let x = (event.position.x.get_length() - self.padding.get_length()) as f32
+            + self.scroll_offset();
+        let position = self.nearest_caret_position(x);
+        match event.kind {
+            GUIPointerEventKind::Pressed => self.move_caret(position, event.modifiers.shift),
+            GUIPointerEventKind::Moved => self.move_caret(position, true),
+            GUIPointerEventKind::Released => return false,
+        }
+        true
+    }
+
+    fn measure_text(&mut self, font: &GUIFont) {
+        let px = self.font_size.get_length() as f32;
+        let line_metrics = font.line_metrics(px);
+        self.caret_height = line_metrics.ascent - line_metrics.descent;
+
+        self.caret_offsets.clear();
+        self.caret_offsets.push(0.);
+        let mut offset = 0.;
+        let mut previous: Option<char> = None;
+        for character in self.text.chars() {
+            if let Some(previous) = previous {
+                offset += font.kern(previous, character, px);
+            }
+            offset += font.metrics(character, px).advance_width;
+            self.caret_offsets.push(offset);
+            previous = Some(character);
+        }
+        self.scroll_to_caret();
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_text(&self, parent_position: &GUIPosition) -> Vec<GUIText> {
+        // Only the text from the first character that's shown on is laid out,
+        // and it's clipped to the padding.
+        let (text, color) = if self.text.is_empty() {
+            (self.placeholder.clone(), self.placeholder_color)
+        } else {
+            (
+                String::from(&self.text[self.byte_index(self.scroll_start)..]),
+                self.text_color,
+            )
+        };
+        Vec::from([GUIText {
+            text,
+            position: GUIPosition::from_lengths(
+                self.position.x.add(&parent_position.x).add(&self.padding),
+                self.position.y.add(&parent_position.y),
+            ),
+            size: GUISize::from_lengths(
+                self.size
+                    .width
+                    .subtract(&self.padding)
+                    .subtract(&self.padding),
+                self.size.height,
+            ),
+            font_size: self.font_size,
+            color,
+            horizontal_alignment: GUIHorizontalAlignment::Left,
+            vertical_alignment: GUIVerticalAlignment::Center,
+            wrap: false,
+            overflow: GUITextOverflow::Clip,
+        }])
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let background_color = if self.enabled {
+            self.background_color
+        } else {
+            self.disabled_color
+        };
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            self.radius,
+            &background_color,
+        );
+        // Only the background is hit tested.
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        if self.focused {
+            let left = origin.x.add(&self.padding).get_length();
+            let top = origin.y.get_length()
+                + (self.size.height.get_length() - self.caret_height as f64) / 2.;
+            let caret_height = GUILength::from_pixels(self.caret_height as f64);
+            // Offsets from where the shown text starts, kept within the padding.
+            let shown_offset = |position: usize| {
+                (self.caret_offset(position) - self.scroll_offset()).clamp(0., self.text_width())
+                    as f64
+            };
+
+            let (start, end) = self.selection();
+            if start != end {
+                let start_offset = shown_offset(start);
+                let end_offset = shown_offset(end);
+                widget_utils::push_rectangle(
+                    &mut vertices,
+                    &mut indices,
+                    indice_offset,
+                    &GUIPosition::from_pixels(left + start_offset, top),
+                    &GUISize::from_lengths(
+                        GUILength::from_pixels(end_offset - start_offset),
+                        caret_height,
+                    ),
+                    &self.selection_color,
+                );
+            }
+
+            let caret_offset = shown_offset(self.caret);
+            widget_utils::push_rectangle(
+                &mut vertices,
+                &mut indices,
+                indice_offset,
+                &GUIPosition::from_pixels(
+                    left + caret_offset - self.caret_width.get_length() / 2.,
+                    top,
+                ),
+                &GUISize::from_lengths(self.caret_width, caret_height),
+                &self.caret_color,
+            );
+        }
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUITextInput {
+    /// Returns an empty text input with all of the default values.
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            placeholder: String::new(),
+            max_length: None,
+            size: GUISize {
+                width: GUILength::from_pixels(200.),
+                height: GUILength::from_pixels(32.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            radius: GUILength::from_pixels(4.),
+            padding: GUILength::from_pixels(8.),
+            font_size: GUILength::from_pixels(16.),
+            background_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.85,
+                g: 0.85,
+                b: 0.85,
+                a: 1.,
+            },
+            text_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            placeholder_color: GUIColor {
+                r: 0.5,
+                g: 0.5,
+                b: 0.5,
+                a: 1.,
+            },
+            selection_color: GUIColor {
+                r: 0.6,
+                g: 0.75,
+                b: 1.,
+                a: 1.,
+            },
+            caret_color: GUIColor {
+                r: 0.,
+                g: 0.,
+                b: 0.,
+                a: 1.,
+            },
+            caret_width: GUILength::from_pixels(1.),
+            enabled: true,
+            focused: false,
+            caret: 0,
+            selection_anchor: 0,
+            caret_offsets: Vec::new(),
+            caret_height: 0.,
+            scroll_start: 0,
+            value_changed: false,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::guiwidgets::{GUIBase, GUIWindow};
+
+    fn key(key: GUIKey, modifiers: GUIModifiers) -> GUIKeyEvent {
+        GUIKeyEvent {
+            key,
+            modifiers,
+            pressed: true,
+        }
+    }
+
+    fn shift() -> GUIModifiers {
+        GUIModifiers {
+            shift: true,
+            ..Default::default()
+        }
+    }
+
+    fn word() -> GUIModifiers {
+        GUIModifiers {
+            ctrl: !cfg!(target_os = "macos"),
+            alt: cfg!(target_os = "macos"),
+            ..Default::default()
+        }
+    }
+
+    fn type_text(input: &mut GUITextInput, text: &str) {
+        for character in text.chars() {
+            input.character_input(character);
+        }
+    }
+
+    #[test]
+    fn typing_and_deleting() {
+        let mut input = GUITextInput::default();
+        type_text(&mut input, "helo\u{8}");
+        assert_eq!(input.text, "helo");
+
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Left));
+        input.character_input('l');
+        assert_eq!((input.text.as_str(), input.caret), ("hello", 4));
+
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Backspace));
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Delete));
+        assert_eq!((input.text.as_str(), input.caret), ("hel", 3));
+
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        input.character_input('é');
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        input.character_input('!');
+        assert_eq!(input.text, "éhel!");
+    }
+
+    #[test]
+    fn shift_selects_and_typing_replaces_the_selection() {
+        let mut input = GUITextInput::default();
+        input.set_text("hello world");
+        input.key_input(&key(GUIKey::Left, shift()));
+        input.key_input(&key(GUIKey::Left, shift()));
+        assert_eq!(input.selected_text(), "ld");
+
+        input.key_input(&key(GUIKey::Home, shift()));
+        assert_eq!(input.selected_text(), "hello world");
+
+        // Left without shift collapses the selection to its start.
+        input.key_input(&key(GUIKey::End, shift()));
+        input.key_input(&key(GUIKey::Left, shift()));
+        input.key_input(&key(GUIKey::Left, shift()));
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Left));
+        assert_eq!((input.caret, input.selected_text()), (9, ""));
+
+        input.select_all();
+        type_text(&mut input, "hi");
+        assert_eq!((input.text.as_str(), input.caret), ("hi", 2));
+    }
+
+    #[test]
+    fn words_are_jumped_and_deleted() {
+        let mut input = GUITextInput::default();
+        input.set_text("one two  three");
+        input.key_input(&key(GUIKey::Left, word()));
+        assert_eq!(input.caret, 9);
+        input.key_input(&key(GUIKey::Left, word()));
+        assert_eq!(input.caret, 4);
+        input.key_input(&key(GUIKey::Right, word()));
+        assert_eq!(input.caret, 7);
+
+        input.key_input(&key(GUIKey::Backspace, word()));
+        assert_eq!((input.text.as_str(), input.caret), ("one   three", 4));
+        input.key_input(&key(GUIKey::Delete, word()));
+        assert_eq!(input.text, "one ");
+    }
+
+    #[test]
+    fn max_length_limits_typing_and_pasting() {
+        let mut input = GUITextInput {
+            max_length: Some(5),
+            ..Default::default()
+        };
+        type_text(&mut input, "abcdefg");
+        assert_eq!(input.text, "abcde");
+
+        input.key_input(&key(GUIKey::Left, shift()));
+        assert!(input.insert("xyz"));
+        assert_eq!(input.text, "abcdx");
+
+        input.set_text("0123456789");
+        assert_eq!(input.text, "01234");
+    }
+
+    #[test]
+    fn control_characters_and_unused_keys_are_ignored() {
+        let mut input = GUITextInput::default();
+        assert!(!input.character_input('\r'));
+        assert!(!input.character_input('\t'));
+        assert!(!input.key_input(&GUIKeyEvent::pressed(GUIKey::Enter)));
+        assert!(!input.key_input(&GUIKeyEvent::pressed(GUIKey::Tab)));
+        assert!(input.text.is_empty());
+    }
+
+    #[test]
+    fn pointer_places_the_caret_and_drags_a_selection() {
+        let mut input = GUITextInput::default();
+        input.set_text("hello");
+        input.measure_text(&GUIFont::default());
+        assert_eq!(input.caret_offsets.len(), 6);
+        let padding = input.padding.get_length();
+
+        let between = |input: &GUITextInput, position: usize| {
+            padding
+                + (input.caret_offsets[position] + input.caret_offsets[position + 1]) as f64 / 2.
+        };
+        let pointer = |kind, x: f64| GUIPointerEvent {
+            kind,
+            position: GUIPosition::from_pixels(x, 10.),
+            modifiers: GUIModifiers::default(),
+        };
+
+        // Just past the middle of the second character puts the caret after it.
+        input.pointer_input(&pointer(
+            GUIPointerEventKind::Pressed,
+            between(&input, 1) + 0.1,
+        ));
+        assert_eq!((input.caret, input.selected_text()), (2, ""));
+
+        input.pointer_input(&pointer(GUIPointerEventKind::Moved, 1000.));
+        assert_eq!(input.selected_text(), "llo");
+
+        input.pointer_input(&pointer(GUIPointerEventKind::Pressed, 0.));
+        assert_eq!((input.caret, input.selected_text()), (0, ""));
+    }
+
+    #[test]
+    fn long_text_scrolls_to_keep_the_caret_in_view() {
+        let font = GUIFont::default();
+        let mut input = GUITextInput {
+            size: GUISize::from_pixels(66., 32.),
+            ..Default::default()
+        };
+        input.set_text("the quick brown fox jumps");
+        input.measure_text(&font);
+        let text_width = input.text_width();
+        assert!(input.scroll_start > 0);
+        let shown = input.get_text(&GUIPosition::default()).remove(0);
+        assert_eq!(shown.overflow, GUITextOverflow::Clip);
+        assert_eq!(shown.size.width.get_length() as f32, text_width);
+        assert!(input.text[input.byte_index(input.scroll_start)..].ends_with("jumps"));
+        assert!(input.caret_offset(input.caret) - input.scroll_offset() <= text_width);
+
+        // Going back to the start scrolls back to it.
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::Home));
+        input.measure_text(&font);
+        assert_eq!(input.scroll_start, 0);
+
+        // Clicking goes by where the text has been scrolled to.
+        input.key_input(&GUIKeyEvent::pressed(GUIKey::End));
+        input.measure_text(&font);
+        input.pointer_input(&GUIPointerEvent {
+            kind: GUIPointerEventKind::Pressed,
+            position: GUIPosition::from_pixels(input.padding.get_length(), 10.),
+            modifiers: GUIModifiers::default(),
+        });
+        assert_eq!(input.caret, input.scroll_start);
+
+        // Once the end of the text is deleted, the rest of it fits again.
+        input.set_text("fits");
+        input.measure_text(&font);
+        assert_eq!(input.scroll_start, 0);
+    }
+
+    #[test]
+    fn caret_is_only_drawn_while_focused() {
+        let mut input = GUITextInput::default();
+        input.set_text("hi");
+        input.measure_text(&GUIFont::default());
+        let (unfocused, _, polygon) = input.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert_eq!(polygon.end_index, unfocused.len());
+
+        input.set_focused(true);
+        let (focused, indices, polygon) =
+            input.get_vertices_and_indices(&GUIPosition::default(), 0);
+        assert_eq!(focused.len(), unfocused.len() + 4);
+        assert_eq!(polygon.end_index, unfocused.len());
+        assert_eq!(*indices.iter().max().unwrap() as usize, focused.len() - 1);
+    }
+
+    #[test]
+    fn typing_runs_the_change_callback() {
+        let mut guibase = GUIBase::new();
+        let window_id = guibase.add_window(GUIWindow::default());
+        let input_id = guibase.add_child_to_parent(GUITextInput::default(), window_id);
+        let changes = Rc::new(Cell::new(0));
+        let counter = changes.clone();
+        guibase.on_change(input_id, move |_| counter.set(counter.get() + 1));
+        guibase.set_focus(Some(input_id));
+
+        guibase.character_input(window_id, 'a');
+        guibase.character_input(window_id, 'b');
+        assert_eq!(changes.get(), 2);
+        guibase.key_input(window_id, &key(GUIKey::Backspace, GUIModifiers::default()));
+        assert_eq!(changes.get(), 3);
+        // Moving the caret and setting the text from code aren't changes made by the user.
+        guibase.key_input(window_id, &key(GUIKey::Left, GUIModifiers::default()));
+        guibase
+            .get_widget_as_mut::<GUITextInput>(input_id)
+            .unwrap()
+            .set_text("code");
+        guibase.key_input(window_id, &key(GUIKey::Left, GUIModifiers::default()));
+        assert_eq!(changes.get(), 3);
+        assert_eq!(
+            guibase
+                .get_widget_as::<GUITextInput>(input_id)
+                .unwrap()
+                .text,
+            "code"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use std::any::Any;
+
+use uuid::Uuid;
+
+use super::widget_utils;
+use crate::guiprocessing::vertices::{LogicalVertex, Polygon};
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::guitraits::Widget;
+use crate::guiproperties::GUIColor;
+
+/// A switch that's turned on and off by clicking on it.
+pub struct GUIToggle {
+    /// Whether the switch is on.
+    pub on: bool,
+    /// The size of the switch's track.
+    pub size: GUISize,
+    /// The location of the switch.
+    pub position: GUIPosition,
+    /// The space between the knob and the edges of the track.
+    pub knob_margin: GUILength,
+    /// The color of the track while the switch is on.
+    pub on_color: GUIColor,
+    /// The color of the track while the switch is off.
+    pub off_color: GUIColor,
+    /// The color of the knob.
+    pub knob_color: GUIColor,
+    /// The color of the track while the switch is disabled.
+    pub disabled_color: GUIColor,
+    /// Whether the switch responds to the mouse and keyboard.
+    pub enabled: bool,
+    pub id: u128,
+}
+
+impl GUIToggle {
+    /// Returns a switch with the default values that starts on or off.
+    pub fn new(on: bool) -> Self {
+        Self {
+            on,
+            ..Default::default()
+        }
+    }
+}
+
+impl Widget for GUIToggle {
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn activate(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        self.on = !self.on;
+        true
+    }
+
+    fn get_size(&self) -> &GUISize {
+        &self.size
+    }
+
+    fn get_position(&self) -> &GUIPosition {
+        &self.position
+    }
+
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
+    fn get_id(&self) -> &u128 {
+        &self.id
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn get_vertices_and_indices(
+        &self,
+        parent_position: &GUIPosition,
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
+        let origin = GUIPosition::from_lengths(
+            self.position.x.add(&parent_position.x),
+            self.position.y.add(&parent_position.y),
+        );
+        let track_color = if !self.enabled {
+            self.disabled_color
+        } else if self.on {
+            self.on_color
+        } else {
+            self.off_color
+        };
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        // Rounding the track by half of its height makes its ends semicircles.
+        let height = self.size.height.get_length();
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &origin,
+            &self.size,
+            GUILength::from_pixels(height / 2.),
+            &track_color,
+        );
+        let polygon = Polygon {
+            start_index: indice_offset as usize,
+            end_index: indice_offset as usize + vertices.len(),
+            widget_id: self.id,
+            convex: true,
+            rendered: true,
+            clip: None,
+        };
+
+        let margin = self.knob_margin.get_length();
+        let knob_size = (height - 2. * margin).max(0.);
+        let knob_left = if self.on {
+            self.size.width.get_length() - margin - knob_size
+        } else {
+            margin
+        };
+        widget_utils::push_rounded_rectangle(
+            &mut vertices,
+            &mut indices,
+            indice_offset,
+            &GUIPosition::from_pixels(
+                origin.x.get_length() + knob_left,
+                origin.y.get_length() + margin,
+            ),
+            &GUISize::from_pixels(knob_size, knob_size),
+            GUILength::from_pixels(knob_size / 2.),
+            &self.knob_color,
+        );
+
+        (vertices, indices, polygon)
+    }
+}
+
+impl Default for GUIToggle {
+    /// Returns a switch that's off with all of the default values.
+    fn default() -> Self {
+        Self {
+            on: false,
+            size: GUISize {
+                width: GUILength::from_pixels(44.),
+                height: GUILength::from_pixels(24.),
+            },
+            position: GUIPosition::from_pixels(0., 0.),
+            knob_margin: GUILength::from_pixels(3.),
+            on_color: GUIColor {
+                r: 0.7,
+                g: 0.1,
+                b: 0.4,
+                a: 1.,
+            },
+            off_color: GUIColor {
+                r: 0.6,
+                g: 0.6,
+                b: 0.6,
+                a: 1.,
+            },
+            knob_color: GUIColor {
+                r: 1.,
+                g: 1.,
+                b: 1.,
+                a: 1.,
+            },
+            disabled_color: GUIColor {
+                r: 0.8,
+                g: 0.8,
+                b: 0.8,
+                a: 1.,
+            },
+            enabled: true,
+            id: Uuid::new_v4().as_u128(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn knob_left(toggle: &GUIToggle) -> f32 {
+        let (vertices, _, polygon) = toggle.get_vertices_and_indices(&GUIPosition::default(), 0);
+        vertices[polygon.end_index..]
+            .iter()
+            .map(|vertex| vertex.position[0])
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn clicking_moves_the_knob_across() {
+        let mut toggle = GUIToggle::default();
+        assert_eq!(knob_left(&toggle), 3.);
+        assert!(toggle.activate());
+        assert!(toggle.on);
+        assert_eq!(knob_left(&toggle), 23.);
+
+        toggle.enabled = false;
+        assert!(!toggle.activate());
+        assert!(toggle.on);
+    }
+}
@@ -41,8 +41,8 @@ impl Widget for GUIWindow {
     fn get_vertices_and_indices(
         &self,
         parent_position: &GUIPosition,
-        indice_offset: u16,
-    ) -> (Vec<LogicalVertex>, Vec<u16>, Polygon) {
+        indice_offset: u32,
+    ) -> (Vec<LogicalVertex>, Vec<u32>, Polygon) {
         let mut polygon = Polygon::default();
         polygon.widget_id = self.id;
 
@@ -57,6 +57,14 @@ impl Widget for GUIWindow {
         &self.position
     }
 
+    fn set_size(&mut self, size: GUISize) {
+        self.size = size;
+    }
+
+    fn set_position(&mut self, position: GUIPosition) {
+        self.position = position;
+    }
+
     fn get_id(&self) -> &u128 {
         &self.id
     }
@@ -1,5 +1,5 @@
 mod guibase;
-pub use guibase::GUIBase;
+pub use guibase::{GUIBase, GUIHandler};
 
 mod guiwindow;
 pub use guiwindow::GUIWindow;
@@ -7,4 +7,52 @@ pub use guiwindow::GUIWindow;
 mod guibutton;
 pub use guibutton::GUIButton;
 
+mod guilabel;
+pub use guilabel::GUILabel;
+
+mod guistack;
+pub use guistack::GUIStack;
+
+mod guigrid;
+pub use guigrid::GUIGrid;
+
+mod guitextinput;
+pub use guitextinput::GUITextInput;
+
+mod guitextarea;
+pub use guitextarea::{GUITextArea, GUITextEdit, GUITextLine};
+
+mod guicheckbox;
+pub use guicheckbox::{GUICheckState, GUICheckbox};
+
+mod guitoggle;
+pub use guitoggle::GUIToggle;
+
+mod guiradiobutton;
+pub use guiradiobutton::GUIRadioButton;
+
+mod guiradiogroup;
+pub use guiradiogroup::GUIRadioGroup;
+
+mod guislider;
+pub use guislider::GUISlider;
+
+mod guirangeslider;
+pub use guirangeslider::{GUIRangeSlider, GUIRangeThumb};
+
+mod guiprogressbar;
+pub use guiprogressbar::GUIProgressBar;
+
+mod guispinner;
+pub use guispinner::GUISpinner;
+
+mod guiscrollview;
+pub use guiscrollview::GUIScrollView;
+
+mod guilistview;
+pub use guilistview::{GUIListView, GUISelectionMode};
+
+mod guitable;
+pub use guitable::{GUIColumnDrag, GUITable, GUITableColumn};
+
 pub mod widget_utils;
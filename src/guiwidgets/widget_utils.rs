@@ -1,4 +1,7 @@
-use crate::guiproperties::guiposition::{GUILength, GUIPosition};
+use crate::guiprocessing::processing_utils::{to_index, INDEX_OVERFLOW};
+use crate::guiprocessing::vertices::LogicalVertex;
+use crate::guiproperties::guiposition::{GUILength, GUIPosition, GUISize};
+use crate::guiproperties::GUIColor;
 
 pub mod arcs {
     use crate::guiproperties::guiposition::{GUILength, GUIPosition};
@@ -87,6 +90,32 @@ pub mod arcs {
             })
             .collect()
     }
+
+    /// Creates a set of vertices to approximate part of a circle, starting at
+    /// start_angle and going counter clockwise through sweep_angle, both in radians
+    /// with 0 pointing right. The center of the arc has the coordinates 0, 0.
+    /// fascets is the number of fascets in the arc.
+    /// The vertice at start_angle is the first with them in counter clockwise order.
+    pub fn make_arc(
+        radius: GUILength,
+        start_angle: f64,
+        sweep_angle: f64,
+        fascets: usize,
+    ) -> Vec<GUIPosition> {
+        (0..=fascets)
+            .map(|i| {
+                let angle = start_angle + sweep_angle * i as f64 / fascets as f64;
+                GUIPosition {
+                    x: GUILength {
+                        length: radius.length * angle.cos(),
+                    },
+                    y: GUILength {
+                        length: -radius.length * angle.sin(),
+                    },
+                }
+            })
+            .collect()
+    }
 }
 
 pub fn translate(
@@ -102,6 +131,174 @@ pub fn translate(
     vertices
 }
 
+/// Returns the index that the next of vertices will have, given that indice_offset is the
+/// index of the first of them. Panics instead of silently wrapping around if the count
+/// vertices that are about to be added can't all be addressed by a u32 index.
+fn first_index(indice_offset: u32, vertex_count: usize, count: usize) -> u32 {
+    let first = indice_offset
+        .checked_add(to_index(vertex_count))
+        .expect(INDEX_OVERFLOW);
+    first.checked_add(to_index(count)).expect(INDEX_OVERFLOW);
+    first
+}
+
+/// Adds a rectangle with its top left corner at position, as two triangles,
+/// to vertices and indices. indice_offset is the index of the first of vertices.
+pub fn push_rectangle(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    position: &GUIPosition,
+    size: &GUISize,
+    color: &GUIColor,
+) {
+    let left = position.x.get_length() as f32;
+    let top = position.y.get_length() as f32;
+    let right = left + size.width.get_length() as f32;
+    let bottom = top + size.height.get_length() as f32;
+    let first = first_index(indice_offset, vertices.len(), 4);
+    // Counter clockwise, starting from the top left.
+    for [x, y] in [[left, top], [left, bottom], [right, bottom], [right, top]] {
+        vertices.push(LogicalVertex {
+            position: [x, y, 0.],
+            color: color.to_f32_array(),
+        });
+    }
+    indices.extend([0, 1, 2, 0, 2, 3].iter().map(|index| first + index));
+}
+
+/// Adds a rectangle with rounded corners and its top left corner at position,
+/// as a fan of triangles, to vertices and indices.
+/// indice_offset is the index of the first of vertices.
+pub fn push_rounded_rectangle(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    position: &GUIPosition,
+    size: &GUISize,
+    radius: GUILength,
+    color: &GUIColor,
+) {
+    const FASCET_COUNT: usize = 7;
+    // The corners can't be bigger than half of the shortest side.
+    let radius = GUILength::from_pixels(
+        radius
+            .get_length()
+            .min(size.width.get_length() / 2.)
+            .min(size.height.get_length() / 2.)
+            .max(0.),
+    );
+    let left = radius.add(&position.x);
+    let top = radius.add(&position.y);
+    let right = size.width.subtract(&radius).add(&position.x);
+    let bottom = size.height.subtract(&radius).add(&position.y);
+
+    let mut outline = translate(arcs::make_top_left_arc(radius, FASCET_COUNT), &left, &top);
+    outline.extend(translate(
+        arcs::make_bottom_left_arc(radius, FASCET_COUNT),
+        &left,
+        &bottom,
+    ));
+    outline.extend(translate(
+        arcs::make_bottom_right_arc(radius, FASCET_COUNT),
+        &right,
+        &bottom,
+    ));
+    outline.extend(translate(
+        arcs::make_top_right_arc(radius, FASCET_COUNT),
+        &right,
+        &top,
+    ));
+
+    let first = first_index(indice_offset, vertices.len(), outline.len());
+    for point in outline.iter() {
+        vertices.push(LogicalVertex {
+            position: [point.x.get_length() as f32, point.y.get_length() as f32, 0.],
+            color: color.to_f32_array(),
+        });
+    }
+    for i in 1..outline.len() as u32 - 1 {
+        indices.extend([first, first + i, first + i + 1]);
+    }
+}
+
+/// Adds a straight line from start to end, as a rectangle that's width thick,
+/// to vertices and indices. indice_offset is the index of the first of vertices.
+pub fn push_line(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    start: [f32; 2],
+    end: [f32; 2],
+    width: f32,
+    color: &GUIColor,
+) {
+    let (dx, dy) = (end[0] - start[0], end[1] - start[1]);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0. {
+        return;
+    }
+    // Half of the width, across the line, so that the corners go counter clockwise.
+    let (nx, ny) = (dy / length * width / 2., -dx / length * width / 2.);
+    let first = first_index(indice_offset, vertices.len(), 4);
+    for [x, y] in [
+        [start[0] + nx, start[1] + ny],
+        [start[0] - nx, start[1] - ny],
+        [end[0] - nx, end[1] - ny],
+        [end[0] + nx, end[1] + ny],
+    ] {
+        vertices.push(LogicalVertex {
+            position: [x, y, 0.],
+            color: color.to_f32_array(),
+        });
+    }
+    indices.extend([0, 1, 2, 0, 2, 3].iter().map(|index| first + index));
+}
+
+/// Adds part of a ring around center, between inner_radius and outer_radius,
+/// to vertices and indices. The angles are in radians, as with arcs::make_arc.
+/// indice_offset is the index of the first of vertices.
+#[allow(clippy::too_many_arguments)]
+pub fn push_arc(
+    vertices: &mut Vec<LogicalVertex>,
+    indices: &mut Vec<u32>,
+    indice_offset: u32,
+    center: &GUIPosition,
+    inner_radius: GUILength,
+    outer_radius: GUILength,
+    start_angle: f64,
+    sweep_angle: f64,
+    color: &GUIColor,
+) {
+    // Enough fascets for a full circle to look round.
+    let fascets = ((sweep_angle.abs() * 32. / std::f64::consts::TAU).ceil() as usize).max(1);
+    let outer = translate(
+        arcs::make_arc(outer_radius, start_angle, sweep_angle, fascets),
+        &center.x,
+        &center.y,
+    );
+    let inner = translate(
+        arcs::make_arc(inner_radius, start_angle, sweep_angle, fascets),
+        &center.x,
+        &center.y,
+    );
+
+    // The outer and inner vertices alternate.
+    let first = first_index(indice_offset, vertices.len(), 2 * outer.len());
+    for (outer, inner) in outer.iter().zip(inner.iter()) {
+        for point in [outer, inner] {
+            vertices.push(LogicalVertex {
+                position: [point.x.get_length() as f32, point.y.get_length() as f32, 0.],
+                color: color.to_f32_array(),
+            });
+        }
+    }
+    for i in 0..fascets as u32 {
+        let (outer, inner) = (first + 2 * i, first + 2 * i + 1);
+        indices.extend([outer, outer + 2, inner + 2, outer, inner + 2, inner]);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::guiproperties::guiposition::{GUILength, GUIPosition};
@@ -159,4 +356,18 @@ mod tests {
         ]);
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn make_arc_good() {
+        let actual = {
+            use std::f64::consts::PI;
+            make_arc(GUILength::from_pixels(5.), PI / 2., PI, 2)
+        };
+        let expected = [(0., -5.), (-5., 0.), (0., 5.)];
+        assert_eq!(actual.len(), expected.len());
+        for (position, (x, y)) in actual.iter().zip(expected) {
+            assert!((position.x.get_length() - x).abs() < 1e-9);
+            assert!((position.y.get_length() - y).abs() < 1e-9);
+        }
+    }
 }
@@ -1,3 +1,4 @@
+pub mod guierror;
 pub mod guiprocessing;
 pub mod guiproperties;
 pub mod guiresources;
@@ -2,12 +2,12 @@
 
 struct VertexInput {
     [[location(0)]] position: vec3<f32>;
-    [[location(1)]] color: vec3<f32>;
+    [[location(1)]] color: vec4<f32>;
 };
 
 struct VertexOutput {
     [[builtin(position)]] clip_position: vec4<f32>;
-    [[location(0)]] color: vec3<f32>;
+    [[location(0)]] color: vec4<f32>;
 };
 
 [[stage(vertex)]]
@@ -24,5 +24,6 @@ fn vs_main(
 
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    // The pipeline blends premultiplied colors.
+    return vec4<f32>(in.color.rgb * in.color.a, in.color.a);
 }
\ No newline at end of file